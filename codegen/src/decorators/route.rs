@@ -112,6 +112,24 @@ impl RouteParams {
         ).expect("data statement"))
     }
 
+    fn generate_limit_statement(&self, ecx: &ExtCtxt) -> Option<Stmt> {
+        let limit = match (self.limit.as_ref(), self.data_param.as_ref()) {
+            (Some(limit), Some(_)) => *limit.value(),
+            _ => return None
+        };
+
+        Some(quote_stmt!(ecx,
+            let __data = match __data.limit($limit as usize) {
+                Ok(data) => data,
+                Err(_) => {
+                    return ::rocket::Outcome::Failure(
+                        ::rocket::http::Status::PayloadTooLarge
+                    );
+                }
+            };
+        ).expect("limit statement"))
+    }
+
     fn generate_query_statement(&self, ecx: &ExtCtxt) -> Option<Stmt> {
         let param = self.query_param.as_ref();
         let expr = quote_expr!(ecx,
@@ -286,6 +304,7 @@ fn generic_route_decorator(known_method: Option<Spanned<Method>>,
 
     let param_statements = route.generate_param_statements(ecx);
     let query_statement = route.generate_query_statement(ecx);
+    let limit_statement = route.generate_limit_statement(ecx);
     let data_statement = route.generate_data_statement(ecx);
     let fn_arguments = route.generate_fn_arguments(ecx);
     let uri_macro = route.generate_uri_macro(ecx);
@@ -301,6 +320,7 @@ fn generic_route_decorator(known_method: Option<Spanned<Method>>,
                 -> ::rocket::handler::Outcome<'_b> {
              $param_statements
              $query_statement
+             $limit_statement
              $data_statement
              let responder = $user_fn_name($fn_arguments);
             ::rocket::handler::Outcome::from(__req, responder)
@@ -35,10 +35,12 @@
 //! kv_param := 'rank' '=' INTEGER
 //!           | 'format' '=' STRING
 //!           | 'data' '=' DYNAMIC_PARAM
+//!           | 'limit' '=' SIZE
 //!
 //! INTEGER := isize, as defined by Rust
 //! STRING := UTF-8 string literal, as defined by Rust
 //! IDENT := valid identifier, as defined by Rust
+//! SIZE := INTEGER ('B' | 'KiB' | 'MiB' | 'GiB')? (string literal)
 //!
 //! URI_SEG := valid HTTP URI Segment
 //! DYNAMIC_PARAM := '<' IDENT '..'? '>' (string literal)
@@ -53,6 +55,11 @@
 //!
 //!     #[get("/hello")]
 //!
+//! A `limit` caps the size of the body read by the `data` parameter's guard,
+//! which fails with `413 Payload Too Large` when the body is larger:
+//!
+//!     #[post("/upload", data = "<upload>", limit = "100MiB")]
+//!
 //! The syntax for the **catch** attribute is:
 //!
 //! <pre>
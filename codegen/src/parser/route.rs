@@ -11,6 +11,7 @@ use super::keyvalue::KVSpanned;
 use super::uri::validate_uri;
 use rocket::http::{Method, MediaType};
 use rocket::http::uri::Uri;
+use rocket::data::parse_size;
 
 /// This structure represents the parsed `route` attribute.
 ///
@@ -27,6 +28,7 @@ pub struct RouteParams {
     pub query_param: Option<Spanned<Ident>>,
     pub format: Option<KVSpanned<MediaType>>,
     pub rank: Option<KVSpanned<isize>>,
+    pub limit: Option<KVSpanned<u64>>,
 }
 
 impl RouteParams {
@@ -76,7 +78,7 @@ impl RouteParams {
 
         // Parse all of the optional parameters.
         let mut seen_keys = HashSet::new();
-        let (mut rank, mut data, mut format) = Default::default();
+        let (mut rank, mut data, mut format, mut limit) = Default::default();
         for param in &attr_params[1..] {
             let kv_opt = kv_from_nested(param);
             if kv_opt.is_none() {
@@ -89,6 +91,7 @@ impl RouteParams {
                 "rank" => rank = parse_opt(ecx, &kv, parse_rank),
                 "data" => data = parse_opt(ecx, &kv, parse_data),
                 "format" => format = parse_opt(ecx, &kv, parse_format),
+                "limit" => limit = parse_opt(ecx, &kv, parse_limit),
                 _ => {
                     let msg = format!("'{}' is not a known parameter", kv.key());
                     ecx.span_err(kv.span, &msg);
@@ -116,6 +119,16 @@ impl RouteParams {
             }
         }
 
+        // Sanity check: `limit` only applies to the `data` parameter's body.
+        if let Some(ref limit_param) = limit {
+            if data.is_none() {
+                ecx.struct_span_err(limit_param.span, "`limit` route parameters \
+                        can only be used with a `data` parameter")
+                    .help(r#"example: #[post("/", data = "<d>", limit = "1MiB")]"#)
+                    .emit();
+            }
+        }
+
         RouteParams {
             method: method,
             uri: uri,
@@ -123,6 +136,7 @@ impl RouteParams {
             query_param: query,
             format: format,
             rank: rank,
+            limit: limit,
             annotated_fn: function,
         }
     }
@@ -277,3 +291,27 @@ fn parse_format(ecx: &ExtCtxt, kv: &KVSpanned<LitKind>) -> MediaType {
 
     MediaType::Any
 }
+
+fn parse_limit(ecx: &ExtCtxt, kv: &KVSpanned<LitKind>) -> u64 {
+    if let LitKind::Str(ref s, _) = *kv.value() {
+        if let Some(limit) = parse_size(&s.as_str()) {
+            if limit <= usize::max_value() as u64 {
+                return limit;
+            }
+
+            ecx.span_err(kv.value.span, "limit exceeds the platform's maximum size");
+            return 0;
+        }
+
+        ecx.span_err(kv.value.span, "malformed size");
+    }
+
+    ecx.struct_span_err(kv.span, r#"`limit` must be a size, e.g: "100MiB""#)
+        .help(r#"limit, if specified, must be a key-value pair where
+              the key is `limit` and the value is a string holding a number of
+              bytes with an optional unit of `B`, `KiB`, `MiB`, or `GiB`.
+              e.g: limit = "512KiB""#)
+        .emit();
+
+    0
+}
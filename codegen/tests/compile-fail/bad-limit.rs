@@ -0,0 +1,16 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[post("/", data = "<d>", limit = "10MB")] //~ ERROR malformed size
+//~^ ERROR `limit` must be a size
+fn one(d: String) -> String { d }
+
+#[post("/", data = "<d>", limit = 10)] //~ ERROR `limit` must be a size
+fn two(d: String) -> String { d }
+
+#[post("/", limit = "1KiB")] //~ ERROR can only be used with a `data` parameter
+fn three() -> &'static str { "hi" }
+
+fn main() {  }
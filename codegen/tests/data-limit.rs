@@ -0,0 +1,32 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::local::Client;
+use rocket::http::Status;
+
+#[post("/", data = "<body>", limit = "8B")]
+fn small(body: String) -> String {
+    body
+}
+
+#[post("/big", data = "<body>", limit = "1 KiB")]
+fn big(body: String) -> String {
+    body
+}
+
+#[test]
+fn route_limits_are_enforced() {
+    let rocket = rocket::ignite().mount("/", routes![small, big]);
+    let client = Client::new(rocket).unwrap();
+
+    let mut response = client.post("/").body("12345678").dispatch();
+    assert_eq!(response.body_string(), Some("12345678".into()));
+
+    let response = client.post("/").body("123456789").dispatch();
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+
+    let mut response = client.post("/big").body("123456789").dispatch();
+    assert_eq!(response.body_string(), Some("123456789".into()));
+}
@@ -1,11 +1,11 @@
 use std::ops::{Deref, DerefMut};
-use std::io::Read;
+use std::io::{self, Read};
 
 use rocket::outcome::{Outcome, IntoOutcome};
 use rocket::request::Request;
 use rocket::data::{self, Data, FromData};
-use rocket::response::{self, Responder, content};
-use rocket::http::Status;
+use rocket::response::{self, Responder, Response, content, DEFAULT_CHUNK_SIZE};
+use rocket::http::{Status, ContentType};
 
 use serde::Serialize;
 use serde::de::DeserializeOwned;
@@ -216,6 +216,163 @@ impl<'a> Responder<'a> for JsonValue {
     }
 }
 
+/// A responder that streams the items of an iterator as newline-delimited
+/// JSON (NDJSON): each item is serialized to JSON on a line of its own.
+///
+/// Items are serialized as the body is written, so the response never holds
+/// more than one item and the bytes waiting to be written. The body is
+/// written out whenever [`flush_every`](#method.flush_every) bytes, 4KiB by
+/// default, have accumulated. The `Content-Type` of the response is
+/// `application/x-ndjson`.
+///
+/// This pairs with a handler that reads a large body record by record,
+/// transforms each record, and emits the results:
+///
+/// ```rust,ignore
+/// #[post("/transform", data = "<records>")]
+/// fn transform(records: Data) -> JsonLinesStream<impl Iterator<Item = Output>> {
+///     let lines = BufReader::new(records.open()).lines();
+///     JsonLinesStream::new(lines.filter_map(|line| line.ok()).map(Output::from))
+/// }
+/// ```
+///
+/// ## Serialization Errors
+///
+/// If the first item fails to serialize, the response fails with `500
+/// Internal Server Error`. Once the response has begun, its status can no
+/// longer change: by default, an item that fails to serialize is logged and
+/// then ends the response abruptly, without the terminating chunk of a chunked
+/// body, so that the client can tell the body is incomplete. With
+/// [`skip_errors`](#method.skip_errors), such items are logged and skipped
+/// instead.
+#[derive(Debug)]
+pub struct JsonLinesStream<I> {
+    items: I,
+    flush_every: u64,
+    skip_errors: bool,
+}
+
+impl<I: Iterator> JsonLinesStream<I> {
+    /// Creates a responder that streams the items of `items` as NDJSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket_contrib::JsonLinesStream;
+    /// let stream = JsonLinesStream::new(vec![1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn new<T: IntoIterator<IntoIter = I>>(items: T) -> JsonLinesStream<I> {
+        JsonLinesStream {
+            items: items.into_iter(),
+            flush_every: DEFAULT_CHUNK_SIZE,
+            skip_errors: false
+        }
+    }
+
+    /// Sets the number of bytes of serialized items that accumulate before
+    /// they're written out to `bytes`. Smaller values get items to the client
+    /// sooner at the cost of more writes. A value of `0` is treated as `1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket_contrib::JsonLinesStream;
+    /// let stream = JsonLinesStream::new(vec![1, 2, 3]).flush_every(512);
+    /// ```
+    #[inline]
+    pub fn flush_every(mut self, bytes: u64) -> Self {
+        self.flush_every = ::std::cmp::max(bytes, 1);
+        self
+    }
+
+    /// Sets whether items that fail to serialize once the response has begun
+    /// are skipped, rather than ending the response. Skipped items are logged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket_contrib::JsonLinesStream;
+    /// let stream = JsonLinesStream::new(vec![1, 2, 3]).skip_errors(true);
+    /// ```
+    #[inline]
+    pub fn skip_errors(mut self, skip: bool) -> Self {
+        self.skip_errors = skip;
+        self
+    }
+}
+
+/// The body of a `JsonLinesStream`: serializes one item at a time as it's
+/// read.
+struct JsonLinesReader<I> {
+    items: I,
+    skip_errors: bool,
+    line: Vec<u8>,
+    pos: usize,
+}
+
+impl<I: Iterator> JsonLinesReader<I> where I::Item: Serialize {
+    /// Replaces the pending line with the next item's. Returns `false` once
+    /// the items run out.
+    fn next_line(&mut self) -> io::Result<bool> {
+        loop {
+            let item = match self.items.next() {
+                Some(item) => item,
+                None => return Ok(false)
+            };
+
+            self.line.clear();
+            self.pos = 0;
+            match serde_json::to_writer(&mut self.line, &item) {
+                Ok(()) => {
+                    self.line.push(b'\n');
+                    return Ok(true);
+                }
+                Err(e) => {
+                    error_!("JSON lines item failed to serialize: {:?}", e);
+                    if !self.skip_errors {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I: Iterator> Read for JsonLinesReader<I> where I::Item: Serialize {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.line.len() && !self.next_line()? {
+            return Ok(0);
+        }
+
+        let n = ::std::cmp::min(buf.len(), self.line.len() - self.pos);
+        buf[..n].copy_from_slice(&self.line[self.pos..(self.pos + n)]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Streams the items as NDJSON in a chunked body with a Content-Type of
+/// `application/x-ndjson`. If the first item fails to serialize, an `Err` of
+/// `Status::InternalServerError` is returned.
+impl<'r, I: Iterator + 'r> Responder<'r> for JsonLinesStream<I> where I::Item: Serialize {
+    fn respond_to(self, _: &Request) -> response::Result<'r> {
+        let mut reader = JsonLinesReader {
+            items: self.items,
+            skip_errors: false,
+            line: vec![],
+            pos: 0
+        };
+
+        reader.next_line().map_err(|_| Status::InternalServerError)?;
+        reader.skip_errors = self.skip_errors;
+        Response::build()
+            .header(ContentType::new("application", "x-ndjson"))
+            .chunked_body(reader, self.flush_every)
+            .ok()
+    }
+}
+
 /// A macro to create ad-hoc JSON serializable values using JSON syntax.
 ///
 /// # Usage
@@ -54,7 +54,7 @@ extern crate serde_json;
 pub mod json;
 
 #[cfg(feature = "json")]
-pub use json::{Json, SerdeError, JsonValue};
+pub use json::{Json, SerdeError, JsonValue, JsonLinesStream};
 
 #[cfg(feature = "msgpack")]
 #[doc(hidden)]
@@ -0,0 +1,52 @@
+extern crate rocket;
+extern crate rocket_contrib;
+
+#[cfg(feature = "json")]
+mod json_lines_tests {
+    use std::collections::HashMap;
+
+    use rocket;
+    use rocket::local::Client;
+    use rocket::response::Responder;
+    use rocket::http::{ContentType, Status};
+    use rocket_contrib::JsonLinesStream;
+
+    // A map with non-string keys can't be serialized to JSON unless it's empty.
+    fn item(bad: bool) -> HashMap<Vec<u8>, u8> {
+        let mut map = HashMap::new();
+        if bad {
+            map.insert(vec![1], 1);
+        }
+
+        map
+    }
+
+    #[test]
+    fn test_json_lines_stream() {
+        let client = Client::new(rocket::ignite()).unwrap();
+        let request = client.get("/");
+        let stream = JsonLinesStream::new(0..3).flush_every(2);
+        let mut response = stream.respond_to(request.inner()).unwrap();
+        assert_eq!(response.content_type(), Some(ContentType::new("application", "x-ndjson")));
+        assert_eq!(response.body_string(), Some("0\n1\n2\n".into()));
+    }
+
+    #[test]
+    fn test_json_lines_stream_errors() {
+        let client = Client::new(rocket::ignite()).unwrap();
+        let request = client.get("/");
+
+        let stream = JsonLinesStream::new(vec![item(false), item(true), item(false)]);
+        let mut response = stream.skip_errors(true).respond_to(request.inner()).unwrap();
+        assert_eq!(response.body_string(), Some("{}\n{}\n".into()));
+
+        // Without skipping, the body ends at the item that failed.
+        let stream = JsonLinesStream::new(vec![item(false), item(true), item(false)]);
+        let mut response = stream.respond_to(request.inner()).unwrap();
+        assert_eq!(response.body_string(), None);
+
+        let stream = JsonLinesStream::new(vec![item(true)]);
+        let error = stream.respond_to(request.inner()).err();
+        assert_eq!(error, Some(Status::InternalServerError));
+    }
+}
@@ -0,0 +1,28 @@
+#![feature(test)]
+
+extern crate rocket;
+
+mod benches {
+    extern crate test;
+
+    use self::test::Bencher;
+    use rocket::Data;
+
+    #[bench]
+    fn bench_small_body(b: &mut Bencher) {
+        let body = vec![b'a'; 128];
+        b.iter(|| Data::bench_roundtrip(body.clone()));
+    }
+
+    #[bench]
+    fn bench_peek_sized_body(b: &mut Bencher) {
+        let body = vec![b'a'; 512];
+        b.iter(|| Data::bench_roundtrip(body.clone()));
+    }
+
+    #[bench]
+    fn bench_large_body(b: &mut Bencher) {
+        let body = vec![b'a'; 64 * 1024];
+        b.iter(|| Data::bench_roundtrip(body.clone()));
+    }
+}
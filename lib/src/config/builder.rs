@@ -1,7 +1,10 @@
 use std::collections::HashMap;
+use std::ascii::AsciiExt;
 use std::path::{Path, PathBuf};
 
-use config::{Result, Config, Value, Environment, Limits, LoggingLevel};
+use config::{Result, Config, Value, Environment, Limits, LoggingLevel, StrictBodies, Cidr};
+use config::{LengthConflict, PeekErrorPolicy, TlsVersion};
+use http::ContentType;
 
 /// Structure following the builder pattern for building `Config` structures.
 #[derive(Clone)]
@@ -22,6 +25,48 @@ pub struct ConfigBuilder {
     pub tls: Option<(String, String)>,
     /// Size limits.
     pub limits: Limits,
+    /// Number of bytes at each end of request bodies to log for debugging.
+    pub body_audit: u64,
+    /// How bodies on requests that shouldn't have one are treated.
+    pub strict_bodies: StrictBodies,
+    /// Blocks of addresses of proxies whose forwarding headers are trusted.
+    pub trusted_proxies: Vec<Cidr>,
+    /// Whether Nagle's algorithm is disabled on connections.
+    pub tcp_nodelay: bool,
+    /// Content types of bodies whose `peek` buffer isn't filled.
+    pub skip_peek: Vec<ContentType>,
+    /// The directory in which temporary files are created.
+    pub temp_dir: PathBuf,
+    /// The capacity, in bytes, of the buffer small body reads are coalesced into.
+    pub read_buffer: u64,
+    /// The maximum number of seconds from a request's arrival to the end of its body.
+    pub max_body_duration: u64,
+    /// The maximum number of multipart uploads written to disk at once.
+    pub max_spills: u64,
+    /// How long, in milliseconds, a spill over `max_spills` waits for a slot.
+    pub spill_wait: u64,
+    /// The content codings the `Decoded` data guard may remove from a body.
+    pub accepted_content_encodings: Vec<String>,
+    /// How long, in milliseconds, reading a body may take before it's logged.
+    pub slow_body_threshold: u64,
+    /// How long, in seconds, body reads may continue once shutdown begins.
+    pub shutdown_grace: u64,
+    /// How requests with both a `Content-Length` and a chunked body are treated.
+    pub length_conflict: LengthConflict,
+    /// What happens when reading a body into the `peek` buffer fails.
+    pub peek_error: PeekErrorPolicy,
+    /// The maximum number of body bytes a single connection may send across its requests.
+    pub connection_body_budget: u64,
+    /// The charsets a request body may declare; empty to accept every charset.
+    pub accepted_charsets: Vec<String>,
+    /// Whether the reads each body makes from its connection are counted.
+    pub count_read_syscalls: bool,
+    /// The minimum average rate, in bytes per second, of a body read past `slowloris_grace`.
+    pub slowloris_min_rate: u64,
+    /// How long, in seconds, a body may be read before `slowloris_min_rate` applies.
+    pub slowloris_grace: u64,
+    /// The oldest TLS version over which request bodies are accepted, if any.
+    pub min_tls_version: Option<TlsVersion>,
     /// Any extra parameters that aren't part of Rocket's config.
     pub extras: HashMap<String, Value>,
     /// The root directory of this config.
@@ -67,6 +112,27 @@ impl ConfigBuilder {
             secret_key: None,
             tls: None,
             limits: config.limits,
+            body_audit: config.body_audit,
+            strict_bodies: config.strict_bodies,
+            trusted_proxies: config.trusted_proxies,
+            tcp_nodelay: config.tcp_nodelay,
+            skip_peek: config.skip_peek,
+            temp_dir: config.temp_dir,
+            read_buffer: config.read_buffer,
+            max_body_duration: config.max_body_duration,
+            max_spills: config.max_spills,
+            spill_wait: config.spill_wait,
+            accepted_content_encodings: config.accepted_content_encodings,
+            slow_body_threshold: config.slow_body_threshold,
+            shutdown_grace: config.shutdown_grace,
+            length_conflict: config.length_conflict,
+            peek_error: config.peek_error,
+            connection_body_budget: config.connection_body_budget,
+            accepted_charsets: config.accepted_charsets,
+            count_read_syscalls: config.count_read_syscalls,
+            slowloris_min_rate: config.slowloris_min_rate,
+            slowloris_grace: config.slowloris_grace,
+            min_tls_version: config.min_tls_version,
             extras: config.extras,
             root: root_dir,
         }
@@ -180,6 +246,406 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the number of bytes at each end of request bodies to log in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let mut config = Config::build(Environment::Staging)
+    ///     .body_audit(32)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.body_audit, 32);
+    /// ```
+    pub fn body_audit(mut self, body_audit: u64) -> Self {
+        self.body_audit = body_audit;
+        self
+    }
+
+    /// Sets the `strict_bodies` policy in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, StrictBodies};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .strict_bodies(StrictBodies::Warn)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.strict_bodies, StrictBodies::Warn);
+    /// ```
+    pub fn strict_bodies(mut self, strict_bodies: StrictBodies) -> Self {
+        self.strict_bodies = strict_bodies;
+        self
+    }
+
+    /// Sets the blocks of addresses of trusted proxies in the configuration
+    /// being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .trusted_proxies(vec!["10.0.0.0/8".parse().unwrap()])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.trusted_proxies.len(), 1);
+    /// ```
+    pub fn trusted_proxies(mut self, trusted_proxies: Vec<Cidr>) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// Sets whether Nagle's algorithm is disabled on connections in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .tcp_nodelay(true)
+    ///     .unwrap();
+    ///
+    /// assert!(config.tcp_nodelay);
+    /// ```
+    pub fn tcp_nodelay(mut self, tcp_nodelay: bool) -> Self {
+        self.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// Sets the content types of bodies whose `peek` buffer isn't filled in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    /// use rocket::http::ContentType;
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .skip_peek(vec![ContentType::Binary])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.skip_peek, vec![ContentType::Binary]);
+    /// ```
+    pub fn skip_peek(mut self, skip_peek: Vec<ContentType>) -> Self {
+        self.skip_peek = skip_peek;
+        self
+    }
+
+    /// Sets the directory in which temporary files are created in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .temp_dir("/mnt/scratch")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.temp_dir, Path::new("/mnt/scratch"));
+    /// ```
+    pub fn temp_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.temp_dir = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets the capacity of the buffer that coalesces small body reads in the
+    /// configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .read_buffer(16384)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.read_buffer, 16384);
+    /// ```
+    pub fn read_buffer(mut self, read_buffer: u64) -> Self {
+        self.read_buffer = read_buffer;
+        self
+    }
+
+    /// Sets the maximum number of seconds from a request's arrival to the end
+    /// of its body in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .max_body_duration(30)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.max_body_duration, 30);
+    /// ```
+    pub fn max_body_duration(mut self, max_body_duration: u64) -> Self {
+        self.max_body_duration = max_body_duration;
+        self
+    }
+
+    /// Sets the maximum number of multipart uploads written to disk at once in
+    /// the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .max_spills(16)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.max_spills, 16);
+    /// ```
+    pub fn max_spills(mut self, max_spills: u64) -> Self {
+        self.max_spills = max_spills;
+        self
+    }
+
+    /// Sets how long, in milliseconds, a spill over `max_spills` waits for a
+    /// slot in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .spill_wait(250)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.spill_wait, 250);
+    /// ```
+    pub fn spill_wait(mut self, spill_wait: u64) -> Self {
+        self.spill_wait = spill_wait;
+        self
+    }
+
+    /// Sets the content codings the `Decoded` data guard may remove from a body
+    /// in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .accepted_content_encodings(vec![])
+    ///     .unwrap();
+    ///
+    /// assert!(config.accepted_content_encodings.is_empty());
+    /// ```
+    pub fn accepted_content_encodings(mut self, accepted_content_encodings: Vec<String>) -> Self {
+        self.accepted_content_encodings = accepted_content_encodings;
+        self
+    }
+
+    /// Sets how long, in milliseconds, reading a body may take before it's logged
+    /// in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .slow_body_threshold(2000)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.slow_body_threshold, 2000);
+    /// ```
+    pub fn slow_body_threshold(mut self, slow_body_threshold: u64) -> Self {
+        self.slow_body_threshold = slow_body_threshold;
+        self
+    }
+
+    /// Sets how long, in seconds, body reads may continue once shutdown begins
+    /// in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .shutdown_grace(10)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.shutdown_grace, 10);
+    /// ```
+    pub fn shutdown_grace(mut self, shutdown_grace: u64) -> Self {
+        self.shutdown_grace = shutdown_grace;
+        self
+    }
+
+    /// Sets the `length_conflict` policy in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, LengthConflict};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .length_conflict(LengthConflict::Chunked)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.length_conflict, LengthConflict::Chunked);
+    /// ```
+    pub fn length_conflict(mut self, length_conflict: LengthConflict) -> Self {
+        self.length_conflict = length_conflict;
+        self
+    }
+
+    /// Sets the `peek_error` policy in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, PeekErrorPolicy};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .peek_error(PeekErrorPolicy::FailRequest)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.peek_error, PeekErrorPolicy::FailRequest);
+    /// ```
+    pub fn peek_error(mut self, peek_error: PeekErrorPolicy) -> Self {
+        self.peek_error = peek_error;
+        self
+    }
+
+    /// Sets the maximum number of body bytes a single connection may send
+    /// across its requests in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .connection_body_budget(64 * (1 << 20))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.connection_body_budget, 64 * (1 << 20));
+    /// ```
+    pub fn connection_body_budget(mut self, connection_body_budget: u64) -> Self {
+        self.connection_body_budget = connection_body_budget;
+        self
+    }
+
+    /// Sets the charsets a request body may declare in the configuration being
+    /// built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .accepted_charsets(vec!["UTF-8".to_string()])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.accepted_charsets, vec!["utf-8".to_string()]);
+    /// ```
+    pub fn accepted_charsets(mut self, accepted_charsets: Vec<String>) -> Self {
+        self.accepted_charsets = accepted_charsets.into_iter()
+            .map(|charset| charset.to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    /// Sets whether the reads each body makes from its connection are counted
+    /// in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .count_read_syscalls(true)
+    ///     .unwrap();
+    ///
+    /// assert!(config.count_read_syscalls);
+    /// ```
+    pub fn count_read_syscalls(mut self, count_read_syscalls: bool) -> Self {
+        self.count_read_syscalls = count_read_syscalls;
+        self
+    }
+
+    /// Sets the minimum average rate, in bytes per second, of a body read for
+    /// longer than `slowloris_grace` in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .slowloris_min_rate(1024)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.slowloris_min_rate, 1024);
+    /// ```
+    pub fn slowloris_min_rate(mut self, slowloris_min_rate: u64) -> Self {
+        self.slowloris_min_rate = slowloris_min_rate;
+        self
+    }
+
+    /// Sets how long, in seconds, a body may be read before
+    /// `slowloris_min_rate` applies in the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .slowloris_grace(30)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.slowloris_grace, 30);
+    /// ```
+    pub fn slowloris_grace(mut self, slowloris_grace: u64) -> Self {
+        self.slowloris_grace = slowloris_grace;
+        self
+    }
+
+    /// Sets the oldest TLS version over which request bodies are accepted in
+    /// the configuration being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, TlsVersion};
+    ///
+    /// let config = Config::build(Environment::Staging)
+    ///     .min_tls_version(TlsVersion::V1_2)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.min_tls_version, Some(TlsVersion::V1_2));
+    /// ```
+    pub fn min_tls_version(mut self, min_tls_version: TlsVersion) -> Self {
+        self.min_tls_version = Some(min_tls_version);
+        self
+    }
+
     /// Sets the TLS configuration in the configuration being built.
     ///
     /// Certificates are read from `certs_path`. The certificate chain must be
@@ -302,6 +768,29 @@ impl ConfigBuilder {
         config.set_extras(self.extras);
         config.set_root(self.root);
         config.set_limits(self.limits);
+        config.set_body_audit(self.body_audit);
+        config.set_strict_bodies(self.strict_bodies);
+        config.set_trusted_proxies(self.trusted_proxies);
+        config.set_tcp_nodelay(self.tcp_nodelay);
+        config.set_skip_peek(self.skip_peek);
+        config.set_temp_dir(self.temp_dir);
+        config.set_read_buffer(self.read_buffer);
+        config.set_max_body_duration(self.max_body_duration);
+        config.set_max_spills(self.max_spills);
+        config.set_spill_wait(self.spill_wait);
+        config.set_accepted_content_encodings(self.accepted_content_encodings);
+        config.set_slow_body_threshold(self.slow_body_threshold);
+        config.set_shutdown_grace(self.shutdown_grace);
+        config.set_length_conflict(self.length_conflict);
+        config.set_peek_error(self.peek_error);
+        config.set_connection_body_budget(self.connection_body_budget);
+        config.set_accepted_charsets(self.accepted_charsets);
+        config.set_count_read_syscalls(self.count_read_syscalls);
+        config.set_slowloris_min_rate(self.slowloris_min_rate);
+        config.set_slowloris_grace(self.slowloris_grace);
+        if let Some(min_tls_version) = self.min_tls_version {
+            config.set_min_tls_version(min_tls_version);
+        }
 
         if let Some((certs_path, key_path)) = self.tls {
             config.set_tls(&certs_path, &key_path)?;
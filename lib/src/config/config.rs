@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ascii::AsciiExt;
 use std::net::ToSocketAddrs;
 use std::path::{Path, PathBuf};
 use std::convert::AsRef;
@@ -10,7 +11,7 @@ use {num_cpus, base64};
 use config::Environment::*;
 use config::{Result, ConfigBuilder, Environment, ConfigError, LoggingLevel};
 use config::{Table, Value, Array, Datetime};
-use http::Key;
+use http::{Key, ContentType};
 
 /// Structure for Rocket application configuration.
 ///
@@ -55,6 +56,48 @@ pub struct Config {
     pub(crate) tls: Option<TlsConfig>,
     /// Streaming read size limits.
     pub limits: Limits,
+    /// Number of bytes at each end of request bodies to log for debugging.
+    pub body_audit: u64,
+    /// How bodies on requests that shouldn't have one are treated.
+    pub strict_bodies: StrictBodies,
+    /// Blocks of addresses of proxies whose forwarding headers are trusted.
+    pub trusted_proxies: Vec<Cidr>,
+    /// Whether Nagle's algorithm is disabled on connections.
+    pub tcp_nodelay: bool,
+    /// Content types of bodies whose `peek` buffer isn't filled.
+    pub skip_peek: Vec<ContentType>,
+    /// The directory in which temporary files are created.
+    pub temp_dir: PathBuf,
+    /// The capacity, in bytes, of the buffer small body reads are coalesced into.
+    pub read_buffer: u64,
+    /// The maximum number of seconds from a request's arrival to the end of its body.
+    pub max_body_duration: u64,
+    /// The maximum number of multipart uploads written to disk at once.
+    pub max_spills: u64,
+    /// How long, in milliseconds, a spill over `max_spills` waits for a slot.
+    pub spill_wait: u64,
+    /// The content codings the `Decoded` data guard may remove from a body.
+    pub accepted_content_encodings: Vec<String>,
+    /// How long, in milliseconds, reading a body may take before it's logged.
+    pub slow_body_threshold: u64,
+    /// How long, in seconds, body reads may continue once shutdown begins.
+    pub shutdown_grace: u64,
+    /// How requests with both a `Content-Length` and a chunked body are treated.
+    pub length_conflict: LengthConflict,
+    /// What happens when reading a body into the `peek` buffer fails.
+    pub peek_error: PeekErrorPolicy,
+    /// The maximum number of body bytes a single connection may send across its requests.
+    pub connection_body_budget: u64,
+    /// The charsets a request body may declare; empty to accept every charset.
+    pub accepted_charsets: Vec<String>,
+    /// Whether the reads each body makes from its connection are counted.
+    pub count_read_syscalls: bool,
+    /// The minimum average rate, in bytes per second, of a body read past `slowloris_grace`.
+    pub slowloris_min_rate: u64,
+    /// How long, in seconds, a body may be read before `slowloris_min_rate` applies.
+    pub slowloris_grace: u64,
+    /// The oldest TLS version over which request bodies are accepted, if any.
+    pub min_tls_version: Option<TlsVersion>,
     /// Extra parameters that aren't part of Rocket's core config.
     pub extras: HashMap<String, Value>,
     /// The path to the configuration file this config belongs to.
@@ -217,6 +260,27 @@ impl Config {
                     secret_key: key,
                     tls: None,
                     limits: Limits::default(),
+                    body_audit: 0,
+                    strict_bodies: StrictBodies::Off,
+                    trusted_proxies: vec![],
+                    tcp_nodelay: false,
+                    skip_peek: vec![],
+                    temp_dir: env::temp_dir(),
+                    read_buffer: 8192,
+                    max_body_duration: 0,
+                    max_spills: 0,
+                    spill_wait: 0,
+                    accepted_content_encodings: vec!["gzip".to_string()],
+                    slow_body_threshold: 10000,
+                    shutdown_grace: 5,
+                    length_conflict: LengthConflict::Reject,
+                    peek_error: PeekErrorPolicy::LogAndEmpty,
+                    connection_body_budget: 0,
+                    accepted_charsets: vec![],
+                    count_read_syscalls: false,
+                    slowloris_min_rate: 0,
+                    slowloris_grace: 10,
+                    min_tls_version: None,
                     extras: HashMap::new(),
                     config_path: config_path,
                 }
@@ -231,6 +295,27 @@ impl Config {
                     secret_key: key,
                     tls: None,
                     limits: Limits::default(),
+                    body_audit: 0,
+                    strict_bodies: StrictBodies::Off,
+                    trusted_proxies: vec![],
+                    tcp_nodelay: false,
+                    skip_peek: vec![],
+                    temp_dir: env::temp_dir(),
+                    read_buffer: 8192,
+                    max_body_duration: 0,
+                    max_spills: 0,
+                    spill_wait: 0,
+                    accepted_content_encodings: vec!["gzip".to_string()],
+                    slow_body_threshold: 10000,
+                    shutdown_grace: 5,
+                    length_conflict: LengthConflict::Reject,
+                    peek_error: PeekErrorPolicy::LogAndEmpty,
+                    connection_body_budget: 0,
+                    accepted_charsets: vec![],
+                    count_read_syscalls: false,
+                    slowloris_min_rate: 0,
+                    slowloris_grace: 10,
+                    min_tls_version: None,
                     extras: HashMap::new(),
                     config_path: config_path,
                 }
@@ -245,6 +330,27 @@ impl Config {
                     secret_key: key,
                     tls: None,
                     limits: Limits::default(),
+                    body_audit: 0,
+                    strict_bodies: StrictBodies::Off,
+                    trusted_proxies: vec![],
+                    tcp_nodelay: false,
+                    skip_peek: vec![],
+                    temp_dir: env::temp_dir(),
+                    read_buffer: 8192,
+                    max_body_duration: 0,
+                    max_spills: 0,
+                    spill_wait: 0,
+                    accepted_content_encodings: vec!["gzip".to_string()],
+                    slow_body_threshold: 10000,
+                    shutdown_grace: 5,
+                    length_conflict: LengthConflict::Reject,
+                    peek_error: PeekErrorPolicy::LogAndEmpty,
+                    connection_body_budget: 0,
+                    accepted_charsets: vec![],
+                    count_read_syscalls: false,
+                    slowloris_min_rate: 0,
+                    slowloris_grace: 10,
+                    min_tls_version: None,
                     extras: HashMap::new(),
                     config_path: config_path,
                 }
@@ -287,7 +393,28 @@ impl Config {
             secret_key => (str, set_secret_key, id),
             log => (log_level, set_log_level, ok),
             tls => (tls_config, set_raw_tls, id),
-            limits => (limits, set_limits, ok)
+            limits => (limits, set_limits, ok),
+            body_audit => (u64, set_body_audit, ok),
+            strict_bodies => (strict_bodies, set_strict_bodies, ok),
+            trusted_proxies => (trusted_proxies, set_trusted_proxies, ok),
+            tcp_nodelay => (bool, set_tcp_nodelay, ok),
+            skip_peek => (content_types, set_skip_peek, ok),
+            temp_dir => (str, set_temp_dir, ok),
+            read_buffer => (u64, set_read_buffer, ok),
+            max_body_duration => (u64, set_max_body_duration, ok),
+            max_spills => (u64, set_max_spills, ok),
+            spill_wait => (u64, set_spill_wait, ok),
+            accepted_content_encodings => (content_encodings, set_accepted_content_encodings, ok),
+            slow_body_threshold => (u64, set_slow_body_threshold, ok),
+            shutdown_grace => (u64, set_shutdown_grace, ok),
+            length_conflict => (length_conflict, set_length_conflict, ok),
+            peek_error => (peek_error, set_peek_error, ok),
+            connection_body_budget => (u64, set_connection_body_budget, ok),
+            accepted_charsets => (charsets, set_accepted_charsets, ok),
+            count_read_syscalls => (bool, set_count_read_syscalls, ok),
+            slowloris_min_rate => (u64, set_slowloris_min_rate, ok),
+            slowloris_grace => (u64, set_slowloris_grace, ok),
+            min_tls_version => (tls_version, set_min_tls_version, ok)
             | _ => {
                 self.extras.insert(name.into(), val.clone());
                 Ok(())
@@ -468,6 +595,500 @@ impl Config {
         self.limits = limits;
     }
 
+    /// Sets the number of bytes at the start and end of every request body
+    /// read that are retained and logged for debugging to `body_audit`. A
+    /// value of `0`, the default, disables body auditing. At most twice
+    /// `body_audit` bytes are retained per body regardless of its size.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    ///
+    /// // Log the first and last 32 bytes of every body read.
+    /// config.set_body_audit(32);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_body_audit(&mut self, body_audit: u64) {
+        self.body_audit = body_audit;
+    }
+
+    /// Sets the policy for bodies on `GET`, `DELETE`, and `TRACE` requests to
+    /// `strict_bodies`. The default, `StrictBodies::Off`, accepts such bodies
+    /// silently.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, StrictBodies};
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    ///
+    /// // Reject `GET`, `DELETE`, and `TRACE` requests that carry a body.
+    /// config.set_strict_bodies(StrictBodies::Reject);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_strict_bodies(&mut self, strict_bodies: StrictBodies) {
+        self.strict_bodies = strict_bodies;
+    }
+
+    /// Sets the blocks of addresses of trusted proxies to `trusted_proxies`.
+    ///
+    /// When a request arrives directly from an address in one of these blocks,
+    /// the client's address is taken from the `Forwarded` or `X-Forwarded-For`
+    /// header instead. Forwarding headers from any other address are ignored. By
+    /// default, no proxies are trusted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    ///
+    /// // Trust forwarding headers set by proxies on the private network.
+    /// config.set_trusted_proxies(vec!["10.0.0.0/8".parse().unwrap()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_trusted_proxies(&mut self, trusted_proxies: Vec<Cidr>) {
+        self.trusted_proxies = trusted_proxies;
+    }
+
+    /// Sets whether Nagle's algorithm is disabled (`TCP_NODELAY` is set) on
+    /// incoming connections to `tcp_nodelay`. Disabling it lowers the latency of
+    /// small responses at the cost of sending more packets. The option is set
+    /// when a request's body is retrieved; it cannot be set on TLS connections.
+    /// The default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_tcp_nodelay(true);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_tcp_nodelay(&mut self, tcp_nodelay: bool) {
+        self.tcp_nodelay = tcp_nodelay;
+    }
+
+    /// Sets the content types of request bodies for which the `peek` buffer
+    /// isn't filled to `skip_peek`. The bodies of requests whose `Content-Type`
+    /// matches one of these, ignoring parameters, are left entirely in the
+    /// stream: `Data::peek` returns an empty slice for them. This avoids an
+    /// unnecessary read for bodies, such as large uploads, that are always
+    /// streamed. By default, the `peek` buffer is always filled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    /// use rocket::http::ContentType;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_skip_peek(vec![ContentType::Binary]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_skip_peek(&mut self, skip_peek: Vec<ContentType>) {
+        self.skip_peek = skip_peek;
+    }
+
+    /// Sets the directory in which temporary files, such as multipart file
+    /// uploads, are created to `path`. If `path` is relative, it is made
+    /// relative to the directory of the configuration file. The default is the
+    /// system's temporary directory as returned by `std::env::temp_dir()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_temp_dir("/mnt/scratch");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_temp_dir<P: AsRef<Path>>(&mut self, path: P) {
+        self.temp_dir = self.root_relative(path);
+    }
+
+    /// Sets the capacity, in bytes, of the buffer that coalesces small reads from
+    /// request body streams to `read_buffer`. A read of fewer bytes than this
+    /// reads up to `read_buffer` bytes from the connection at once and serves
+    /// subsequent reads from the buffer, so parsers that read a few bytes at a
+    /// time don't incur a system call for each read. A value of `0` disables
+    /// coalescing. The default is `8192`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_read_buffer(16384);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_read_buffer(&mut self, read_buffer: u64) {
+        self.read_buffer = read_buffer;
+    }
+
+    /// Sets the maximum number of seconds that may elapse between the arrival of
+    /// a request's headers and the last read of its body to `max_body_duration`.
+    /// Reads from the body after the deadline fail with an error of kind
+    /// `TimedOut`, which Rocket's data guards report as `408 Request Timeout`. A
+    /// read in progress when the deadline passes is still bounded by the read
+    /// timeout. A value of `0` disables the deadline. The default is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_max_body_duration(30);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_max_body_duration(&mut self, max_body_duration: u64) {
+        self.max_body_duration = max_body_duration;
+    }
+
+    /// Sets the maximum number of multipart file fields that may be written to
+    /// temporary files at once, across all requests, to `max_spills`. A field
+    /// that would exceed the limit waits up to `spill_wait` milliseconds for
+    /// another to finish and otherwise fails with a `SpillLimitReached` error,
+    /// which Rocket reports as `503 Service Unavailable`. A value of `0` disables
+    /// the limit. The default is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_max_spills(16);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_max_spills(&mut self, max_spills: u64) {
+        self.max_spills = max_spills;
+    }
+
+    /// Sets how long, in milliseconds, a multipart file field waits for a slot
+    /// when `max_spills` fields are already being written to disk to
+    /// `spill_wait`. A value of `0` rejects such fields immediately. The default
+    /// is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_spill_wait(250);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_spill_wait(&mut self, spill_wait: u64) {
+        self.spill_wait = spill_wait;
+    }
+
+    /// Sets the content codings the `Decoded` data guard may remove from a body
+    /// to `accepted_content_encodings`. A body whose `Content-Encoding` lists a
+    /// coding not in the list, other than `identity`, is rejected with `415
+    /// Unsupported Media Type`, even if Rocket could decode it. Codings are
+    /// compared case-insensitively, and `x-gzip` is treated as `gzip`. The
+    /// default is `["gzip"]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_accepted_content_encodings(vec![]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_accepted_content_encodings(&mut self, accepted_content_encodings: Vec<String>) {
+        self.accepted_content_encodings = accepted_content_encodings;
+    }
+
+    /// Sets how long, in milliseconds, reading a request body from its first
+    /// read to its end may take before Rocket logs a warning with the number of
+    /// bytes read and the client's address to `slow_body_threshold`. A value of
+    /// `0` disables the warning. The default is `10000`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_slow_body_threshold(2000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_slow_body_threshold(&mut self, slow_body_threshold: u64) {
+        self.slow_body_threshold = slow_body_threshold;
+    }
+
+    /// Sets how long, in seconds, request bodies may continue to be read after
+    /// shutdown is requested through a
+    /// [`ShutdownHandle`](/rocket/data/struct.ShutdownHandle.html) to
+    /// `shutdown_grace`. Once the grace period passes, reads fail with a
+    /// `ShuttingDown` error. The default is `5`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_shutdown_grace(10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_shutdown_grace(&mut self, shutdown_grace: u64) {
+        self.shutdown_grace = shutdown_grace;
+    }
+
+    /// Sets the policy for requests that declare both a `Content-Length` and a
+    /// chunked `Transfer-Encoding` to `length_conflict`. The default,
+    /// `LengthConflict::Reject`, responds to such requests with `400 Bad Request`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, LengthConflict};
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    ///
+    /// // Read such bodies as chunked, ignoring their `Content-Length`.
+    /// config.set_length_conflict(LengthConflict::Chunked);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_length_conflict(&mut self, length_conflict: LengthConflict) {
+        self.length_conflict = length_conflict;
+    }
+
+    /// Sets the policy applied when reading the start of a request body into
+    /// the `peek` buffer fails to `peek_error`. The default,
+    /// `PeekErrorPolicy::LogAndEmpty`, logs the error and treats the body as empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, PeekErrorPolicy};
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    ///
+    /// // Retry a peek read that timed out once before giving up.
+    /// config.set_peek_error(PeekErrorPolicy::RetryOnce);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_peek_error(&mut self, peek_error: PeekErrorPolicy) {
+        self.peek_error = peek_error;
+    }
+
+    /// Sets the maximum number of body bytes that a single connection may send,
+    /// summed across all of the requests made on it, to `connection_body_budget`.
+    /// The read that exceeds the budget fails with an error of kind
+    /// `InvalidData`, which Rocket's data guards report as
+    /// `413 Payload Too Large`, and the connection is closed once the response
+    /// is written. A value of `0` disables the budget. The default is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_connection_body_budget(64 * (1 << 20));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_connection_body_budget(&mut self, connection_body_budget: u64) {
+        self.connection_body_budget = connection_body_budget;
+    }
+
+    /// Sets the charsets that a request body's `Content-Type` may declare to
+    /// `accepted_charsets`. A request with a body whose `Content-Type` has a
+    /// `charset` parameter not in the list is rejected with `415 Unsupported
+    /// Media Type` before it's routed; one without a `charset` parameter is always
+    /// accepted. Charsets are compared case-insensitively and are stored in
+    /// lowercase. An empty list, the default, accepts every charset.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_accepted_charsets(vec!["utf-8".to_string()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_accepted_charsets(&mut self, accepted_charsets: Vec<String>) {
+        self.accepted_charsets = accepted_charsets.into_iter()
+            .map(|charset| charset.to_ascii_lowercase())
+            .collect();
+    }
+
+    /// Sets whether the reads each request body makes from its network
+    /// connection are counted to `count_read_syscalls`. The counts are available
+    /// from `Data::read_syscalls`, `DataStream::read_syscalls`, and, summed over
+    /// every body, `DataStats`. Counting adds a little overhead to every read, so
+    /// it's meant for profiling rather than production. The default is `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_count_read_syscalls(true);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_count_read_syscalls(&mut self, count_read_syscalls: bool) {
+        self.count_read_syscalls = count_read_syscalls;
+    }
+
+    /// Sets the minimum average rate, in bytes per second, at which a request
+    /// body that's still being read `slowloris_grace` seconds after its request
+    /// arrived must have been received to `slowloris_min_rate`. Reads of a body
+    /// that is both that old and slower fail with a `BodyTooSlow` error of kind
+    /// `TimedOut`, which Rocket's data guards report as `408 Request Timeout`.
+    /// Small bodies that are slow to arrive, but finish within the grace period,
+    /// are never rejected. A value of `0` disables the check. The default is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_slowloris_min_rate(1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_slowloris_min_rate(&mut self, slowloris_min_rate: u64) {
+        self.slowloris_min_rate = slowloris_min_rate;
+    }
+
+    /// Sets how long, in seconds, a request body may be read after its request
+    /// arrives before its average rate is checked against `slowloris_min_rate`
+    /// to `slowloris_grace`. The default is `10`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_slowloris_grace(30);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_slowloris_grace(&mut self, slowloris_grace: u64) {
+        self.slowloris_grace = slowloris_grace;
+    }
+
+    /// Sets the oldest version of TLS over which request bodies are accepted to
+    /// `min_tls_version`. A request that arrives over a TLS connection that
+    /// negotiated an older version is rejected with a `426 Upgrade Required`
+    /// response before it is dispatched. Requests that don't arrive over TLS
+    /// aren't affected. By default, bodies are accepted over any version.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, TlsVersion};
+    ///
+    /// # use rocket::config::ConfigError;
+    /// # fn config_test() -> Result<(), ConfigError> {
+    /// let mut config = Config::development()?;
+    /// config.set_min_tls_version(TlsVersion::V1_2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_min_tls_version(&mut self, min_tls_version: TlsVersion) {
+        self.min_tls_version = Some(min_tls_version);
+    }
+
     /// Sets the TLS configuration in `self`.
     ///
     /// Certificates are read from `certs_path`. The certificate chain must be
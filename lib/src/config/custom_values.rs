@@ -1,9 +1,12 @@
 use std::fmt;
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+use std::net::IpAddr;
 
 #[cfg(feature = "tls")] use rustls::{Certificate, PrivateKey};
 
 use config::{Result, Config, Value, ConfigError, LoggingLevel};
-use http::Key;
+use http::{Key, ContentType};
 
 #[derive(Clone)]
 pub enum SecretKey {
@@ -197,6 +200,249 @@ impl fmt::Display for Limits {
     }
 }
 
+/// How requests that conventionally have no body but arrive with one are
+/// treated.
+///
+/// `GET`, `DELETE`, and `TRACE` requests aren't expected to carry a body. A
+/// body on one of these requests is unusual and may indicate an attempt at
+/// request smuggling. The policy is applied before the request is dispatched.
+///
+/// The policy is parsed from the strings `"off"`, `"warn"`, and `"reject"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrictBodies {
+    /// Bodies on these requests are accepted silently. This is the default.
+    Off,
+    /// Bodies on these requests are accepted, and a warning is logged.
+    Warn,
+    /// Requests with a body are rejected with a `400 Bad Request` response.
+    Reject,
+}
+
+impl FromStr for StrictBodies {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        let policy = match s {
+            "off" => StrictBodies::Off,
+            "warn" => StrictBodies::Warn,
+            "reject" => StrictBodies::Reject,
+            _ => return Err("a strict bodies policy (off, warn, reject)")
+        };
+
+        Ok(policy)
+    }
+}
+
+impl fmt::Display for StrictBodies {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StrictBodies::Off => write!(f, "off"),
+            StrictBodies::Warn => write!(f, "warn"),
+            StrictBodies::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+/// How requests that declare both a `Content-Length` and a chunked
+/// `Transfer-Encoding` are treated.
+///
+/// A request with both headers is ambiguous: a proxy that honors one header
+/// and a server that honors the other disagree on where the body ends, which
+/// is a classic request smuggling vector. RFC 7230 §3.3.3 permits a server to
+/// either reject such a request or ignore its `Content-Length`.
+///
+/// The policy is parsed from the strings `"reject"` and `"chunked"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthConflict {
+    /// Requests with both headers are rejected with a `400 Bad Request`
+    /// response. This is the default.
+    Reject,
+    /// The `Content-Length` is ignored and the body is read as chunked.
+    Chunked,
+}
+
+impl FromStr for LengthConflict {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        let policy = match s {
+            "reject" => LengthConflict::Reject,
+            "chunked" => LengthConflict::Chunked,
+            _ => return Err("a length conflict policy (reject, chunked)")
+        };
+
+        Ok(policy)
+    }
+}
+
+impl fmt::Display for LengthConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LengthConflict::Reject => write!(f, "reject"),
+            LengthConflict::Chunked => write!(f, "chunked"),
+        }
+    }
+}
+
+/// What happens when reading the start of a request body into the `peek`
+/// buffer fails.
+///
+/// The policy is parsed from the strings `"log-and-empty"`, `"fail-request"`,
+/// and `"retry-once"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeekErrorPolicy {
+    /// The error is logged and the body is treated as empty and truncated.
+    /// This is the default.
+    LogAndEmpty,
+    /// The request fails: with `408 Request Timeout` if the read timed out
+    /// and `400 Bad Request` otherwise.
+    FailRequest,
+    /// A read that timed out is retried once, continuing from the bytes
+    /// already read. If the retry fails too, or the error isn't a timeout, the
+    /// error is handled as by `LogAndEmpty`.
+    RetryOnce,
+}
+
+impl FromStr for PeekErrorPolicy {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        let policy = match s {
+            "log-and-empty" => PeekErrorPolicy::LogAndEmpty,
+            "fail-request" => PeekErrorPolicy::FailRequest,
+            "retry-once" => PeekErrorPolicy::RetryOnce,
+            _ => return Err("a peek error policy (log-and-empty, fail-request, retry-once)")
+        };
+
+        Ok(policy)
+    }
+}
+
+impl fmt::Display for PeekErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PeekErrorPolicy::LogAndEmpty => write!(f, "log-and-empty"),
+            PeekErrorPolicy::FailRequest => write!(f, "fail-request"),
+            PeekErrorPolicy::RetryOnce => write!(f, "retry-once"),
+        }
+    }
+}
+
+/// A version of the TLS protocol, as used by the `min_tls_version`
+/// configuration parameter. Versions are ordered from oldest to newest.
+///
+/// A version is parsed from the strings `"1.0"`, `"1.1"`, `"1.2"`, and
+/// `"1.3"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TlsVersion {
+    /// TLS 1.0.
+    V1_0,
+    /// TLS 1.1.
+    V1_1,
+    /// TLS 1.2.
+    V1_2,
+    /// TLS 1.3.
+    V1_3,
+}
+
+impl FromStr for TlsVersion {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        let version = match s {
+            "1.0" => TlsVersion::V1_0,
+            "1.1" => TlsVersion::V1_1,
+            "1.2" => TlsVersion::V1_2,
+            "1.3" => TlsVersion::V1_3,
+            _ => return Err("a TLS version (1.0, 1.1, 1.2, 1.3)")
+        };
+
+        Ok(version)
+    }
+}
+
+impl fmt::Display for TlsVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TlsVersion::V1_0 => write!(f, "1.0"),
+            TlsVersion::V1_1 => write!(f, "1.1"),
+            TlsVersion::V1_2 => write!(f, "1.2"),
+            TlsVersion::V1_3 => write!(f, "1.3"),
+        }
+    }
+}
+
+/// A block of IP addresses in CIDR notation, such as `10.0.0.0/8` or
+/// `fd00::/8`.
+///
+/// A bare address, such as `127.0.0.1`, denotes a block containing only that
+/// address. IPv4 blocks only contain IPv4 addresses and IPv6 blocks only
+/// contain IPv6 addresses.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::Cidr;
+///
+/// let block: Cidr = "10.0.0.0/8".parse().unwrap();
+/// assert!(block.contains(&"10.1.2.3".parse().unwrap()));
+/// assert!(!block.contains(&"192.168.0.1".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Returns `true` if `ip` is in this block.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        fn prefix_eq(a: &[u8], b: &[u8], prefix: u8) -> bool {
+            let (bytes, bits) = ((prefix / 8) as usize, prefix % 8);
+            if a[..bytes] != b[..bytes] {
+                return false;
+            }
+
+            let mask = !(0xFFu8 >> bits);
+            bits == 0 || (a[bytes] & mask) == (b[bytes] & mask)
+        }
+
+        match (self.addr, *ip) {
+            (IpAddr::V4(a), IpAddr::V4(b)) => prefix_eq(&a.octets(), &b.octets(), self.prefix),
+            (IpAddr::V6(a), IpAddr::V6(b)) => prefix_eq(&a.octets(), &b.octets(), self.prefix),
+            _ => false
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        const ERR: &'static str = "an IP address or CIDR block";
+
+        let mut parts = s.splitn(2, '/');
+        let addr: IpAddr = parts.next().unwrap_or("").parse().map_err(|_| ERR)?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match parts.next() {
+            Some(prefix) => prefix.parse().map_err(|_| ERR)?,
+            None => max_prefix
+        };
+
+        if prefix > max_prefix {
+            return Err(ERR);
+        }
+
+        Ok(Cidr { addr: addr, prefix: prefix })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
 pub fn str<'a>(conf: &Config, name: &str, v: &'a Value) -> Result<&'a str> {
     v.as_str().ok_or(conf.bad_type(name, v.type_str(), "a string"))
 }
@@ -208,6 +454,10 @@ pub fn u64(conf: &Config, name: &str, value: &Value) -> Result<u64> {
     }
 }
 
+pub fn bool(conf: &Config, name: &str, value: &Value) -> Result<bool> {
+    value.as_bool().ok_or_else(|| conf.bad_type(name, value.type_str(), "a boolean"))
+}
+
 pub fn u16(conf: &Config, name: &str, value: &Value) -> Result<u16> {
     match value.as_integer() {
         Some(x) if x >= 0 && x <= (u16::max_value() as i64) => Ok(x as u16),
@@ -223,6 +473,67 @@ pub fn log_level(conf: &Config,
         .and_then(|s| s.parse().map_err(|e| conf.bad_type(name, value.type_str(), e)))
 }
 
+pub fn strict_bodies(conf: &Config,
+                     name: &str,
+                     value: &Value
+                    ) -> Result<StrictBodies> {
+    str(conf, name, value)
+        .and_then(|s| s.parse().map_err(|e| conf.bad_type(name, value.type_str(), e)))
+}
+
+pub fn length_conflict(conf: &Config, name: &str, value: &Value) -> Result<LengthConflict> {
+    str(conf, name, value)
+        .and_then(|s| s.parse().map_err(|e| conf.bad_type(name, value.type_str(), e)))
+}
+
+pub fn peek_error(conf: &Config, name: &str, value: &Value) -> Result<PeekErrorPolicy> {
+    str(conf, name, value)
+        .and_then(|s| s.parse().map_err(|e| conf.bad_type(name, value.type_str(), e)))
+}
+
+pub fn tls_version(conf: &Config, name: &str, value: &Value) -> Result<TlsVersion> {
+    str(conf, name, value)
+        .and_then(|s| s.parse().map_err(|e| conf.bad_type(name, value.type_str(), e)))
+}
+
+pub fn trusted_proxies(conf: &Config, name: &str, value: &Value) -> Result<Vec<Cidr>> {
+    let array = value.as_array()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "an array of strings"))?;
+
+    array.iter()
+        .map(|v| str(conf, name, v)
+             .and_then(|s| s.parse().map_err(|e| conf.bad_type(name, v.type_str(), e))))
+        .collect()
+}
+
+pub fn content_types(conf: &Config, name: &str, value: &Value) -> Result<Vec<ContentType>> {
+    let array = value.as_array()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "an array of strings"))?;
+
+    array.iter()
+        .map(|v| str(conf, name, v).and_then(|s| s.parse()
+             .map_err(|_| conf.bad_type(name, v.type_str(), "a content type"))))
+        .collect()
+}
+
+pub fn content_encodings(conf: &Config, name: &str, value: &Value) -> Result<Vec<String>> {
+    let array = value.as_array()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "an array of strings"))?;
+
+    array.iter()
+        .map(|v| str(conf, name, v).map(|s| s.to_ascii_lowercase()))
+        .collect()
+}
+
+pub fn charsets(conf: &Config, name: &str, value: &Value) -> Result<Vec<String>> {
+    let array = value.as_array()
+        .ok_or_else(|| conf.bad_type(name, value.type_str(), "an array of strings"))?;
+
+    array.iter()
+        .map(|v| str(conf, name, v).map(|s| s.to_ascii_lowercase()))
+        .collect()
+}
+
 pub fn tls_config<'v>(conf: &Config,
                                name: &str,
                                value: &'v Value,
@@ -53,6 +53,93 @@
 //!   a data type and the value (_[u64]_) corresponds to the maximum size in
 //!   bytes Rocket should accept for that type.
 //!     * example: `{ forms = 65536 }` (maximum form size to 64KiB)
+//!   * **body_audit**: _[integer]_ the number of bytes at the start and end of
+//!     each request body read to log for debugging; `0`, the default,
+//!     disables auditing
+//!     * example: `64`
+//!   * **strict_bodies**: _[string]_ how a body on a `GET`, `DELETE`, or
+//!     `TRACE` request is treated: `"off"`, the default, accepts it; `"warn"`
+//!     accepts it and logs a warning; `"reject"` responds with `400 Bad Request`
+//!     * example: `"warn"`
+//!   * **trusted_proxies**: _[array]_ IP addresses or CIDR blocks of proxies
+//!     whose `Forwarded` and `X-Forwarded-For` headers are used to determine
+//!     the client's address; by default, none are trusted
+//!     * example: `["10.0.0.0/8", "::1"]`
+//!   * **tcp_nodelay**: _[bool]_ whether to disable Nagle's algorithm
+//!     (`TCP_NODELAY`) on incoming, non-TLS connections; defaults to `false`
+//!     * example: `true`
+//!   * **skip_peek**: _[array]_ content types of request bodies for which the
+//!     `peek` buffer isn't filled; by default, it is filled for every body
+//!     * example: `["application/octet-stream"]`
+//!   * **temp_dir**: _[string]_ the directory in which temporary files, such as
+//!     multipart file uploads, are created; defaults to the system's temporary
+//!     directory
+//!     * example: `"/mnt/scratch"`
+//!   * **read_buffer**: _[integer]_ the capacity, in bytes, of the buffer small
+//!     reads from request bodies are coalesced into; `0` disables coalescing;
+//!     defaults to `8192`
+//!     * example: `16384`
+//!   * **max_body_duration**: _[integer]_ the maximum number of seconds from the
+//!     arrival of a request's headers to the last read of its body; `0`
+//!     disables the limit; defaults to `0`
+//!     * example: `30`
+//!   * **max_spills**: _[integer]_ the maximum number of multipart file fields
+//!     written to temporary files at once; `0` disables the limit; defaults to
+//!     `0`
+//!     * example: `16`
+//!   * **spill_wait**: _[integer]_ how long, in milliseconds, a multipart file
+//!     field over `max_spills` waits for a slot before being rejected;
+//!     defaults to `0`
+//!     * example: `250`
+//!   * **accepted_content_encodings**: _[array]_ the content codings, such as
+//!     `"gzip"`, that the `Decoded` data guard may remove from a body; bodies
+//!     with any other coding are rejected; defaults to `["gzip"]`
+//!     * example: `[]`
+//!   * **slow_body_threshold**: _[integer]_ how long, in milliseconds, reading
+//!     a request body may take before a warning is logged; `0` disables the
+//!     warning; defaults to `10000`
+//!     * example: `2000`
+//!   * **shutdown_grace**: _[integer]_ how long, in seconds, request bodies may
+//!     continue to be read once shutdown is requested; defaults to `5`
+//!     * example: `10`
+//!   * **length_conflict**: _[string]_ how a request that declares both a
+//!     `Content-Length` and a chunked `Transfer-Encoding` is treated:
+//!     `"reject"`, the default, responds with `400 Bad Request`; `"chunked"`
+//!     ignores the `Content-Length`
+//!     * example: `"chunked"`
+//!   * **peek_error**: _[string]_ what happens when reading the start of a
+//!     request body into the `peek` buffer fails: `"log-and-empty"`, the
+//!     default, logs the error and treats the body as empty; `"fail-request"`
+//!     responds with `400 Bad Request`, or `408 Request Timeout` if the read
+//!     timed out; `"retry-once"` retries a read that timed out once
+//!     * example: `"fail-request"`
+//!   * **connection_body_budget**: _[integer]_ the maximum number of body
+//!     bytes a single keep-alive connection may send across all of its
+//!     requests before it's closed; `0` disables the budget; defaults to `0`
+//!     * example: `67108864`
+//!   * **accepted_charsets**: _[array]_ the charsets, such as `"utf-8"`, that
+//!     the `Content-Type` of a request body may declare; bodies declaring any
+//!     other charset are rejected with `415 Unsupported Media Type`; an empty
+//!     array accepts every charset; defaults to `[]`
+//!     * example: `["utf-8"]`
+//!   * **count_read_syscalls**: _[bool]_ whether the reads each request body
+//!     makes from its network connection are counted, for profiling; defaults
+//!     to `false`
+//!     * example: `true`
+//!   * **slowloris_min_rate**: _[integer]_ the minimum average rate, in bytes
+//!     per second, of a body still being read `slowloris_grace` seconds after
+//!     its request arrived; slower bodies fail with `408 Request Timeout`; `0`
+//!     disables the check; defaults to `0`
+//!     * example: `1024`
+//!   * **slowloris_grace**: _[integer]_ how long, in seconds, a body may be
+//!     read after its request arrives before `slowloris_min_rate` applies;
+//!     defaults to `10`
+//!     * example: `30`
+//!   * **min_tls_version**: _[string]_ the oldest TLS version, one of `"1.0"`,
+//!     `"1.1"`, `"1.2"`, or `"1.3"`, over which request bodies are accepted;
+//!     requests over older versions are rejected with `426 Upgrade Required`;
+//!     by default, any version is accepted
+//!     * example: `"1.2"`
 //!
 //! ### Rocket.toml
 //!
@@ -204,7 +291,8 @@ use std::env;
 
 use toml;
 
-pub use self::custom_values::Limits;
+pub use self::custom_values::{Limits, StrictBodies, LengthConflict, PeekErrorPolicy, Cidr};
+pub use self::custom_values::TlsVersion;
 pub use toml::value::{Array, Table, Value, Datetime};
 pub use self::error::ConfigError;
 pub use self::environment::Environment;
@@ -498,7 +586,7 @@ mod test {
     use std::sync::Mutex;
 
     use super::{RocketConfig, Config, ConfigError, ConfigBuilder};
-    use super::{Environment, GLOBAL_ENV_NAME};
+    use super::{Environment, GLOBAL_ENV_NAME, TlsVersion};
     use super::environment::CONFIG_ENV;
     use super::Environment::*;
     use super::Result;
@@ -1003,6 +1091,126 @@ mod test {
         "#.to_string(), TEST_CONFIG_FILENAME).is_err());
     }
 
+    #[test]
+    fn test_trusted_proxies() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "stage");
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            trusted_proxies = ["10.0.0.0/8", "::1", "192.168.1.128/25"]
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+
+        let proxies = &config.active().trusted_proxies;
+        let trusted = |ip: &str| proxies.iter().any(|p| p.contains(&ip.parse().unwrap()));
+        assert!(trusted("10.255.0.1"));
+        assert!(trusted("::1"));
+        assert!(trusted("192.168.1.200"));
+        assert!(!trusted("192.168.1.100"));
+        assert!(!trusted("11.0.0.1"));
+        assert!(!trusted("::2"));
+
+        env::remove_var(CONFIG_ENV);
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            trusted_proxies = "10.0.0.0/8"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            trusted_proxies = ["10.0.0.0/33"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            trusted_proxies = ["localhost"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_accepted_content_encodings() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "stage");
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            accepted_content_encodings = ["GZIP", "br"]
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().accepted_content_encodings, vec!["gzip", "br"]);
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            address = "localhost"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().accepted_content_encodings, vec!["gzip"]);
+
+        env::remove_var(CONFIG_ENV);
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            accepted_content_encodings = "gzip"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_accepted_charsets() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "stage");
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            accepted_charsets = ["UTF-8"]
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().accepted_charsets, vec!["utf-8"]);
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            address = "localhost"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert!(config.active().accepted_charsets.is_empty());
+
+        env::remove_var(CONFIG_ENV);
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            accepted_charsets = "utf-8"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_min_tls_version() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::set_var(CONFIG_ENV, "stage");
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            min_tls_version = "1.2"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().min_tls_version, Some(TlsVersion::V1_2));
+
+        let config = RocketConfig::parse(r#"
+            [stage]
+            address = "localhost"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().min_tls_version, None);
+
+        let config = Config::build(Environment::Staging)
+            .min_tls_version(TlsVersion::V1_3)
+            .unwrap();
+        assert_eq!(config.min_tls_version, Some(TlsVersion::V1_3));
+        assert!(TlsVersion::V1_1 < TlsVersion::V1_2);
+
+        env::remove_var(CONFIG_ENV);
+        for value in &["\"1.4\"", "\"TLSv1.2\"", "1.2"] {
+            assert!(RocketConfig::parse(format!(r#"
+                [dev]
+                min_tls_version = {}
+            "#, value), TEST_CONFIG_FILENAME).is_err());
+        }
+    }
+
     #[test]
     fn test_global_overrides() {
         // Take the lock so changing the environment doesn't cause races.
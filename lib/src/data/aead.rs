@@ -0,0 +1,217 @@
+use std::io::{self, Read, Cursor};
+
+use ring::aead::{self, OpeningKey, AES_128_GCM, AES_256_GCM};
+
+use super::DataStream;
+
+/// A stream that decrypts and authenticates an AES-GCM encrypted request body.
+///
+/// The body is expected to consist of a 12-byte nonce followed by the
+/// ciphertext and its 16-byte authentication tag. The key is 16 bytes long
+/// for AES-128-GCM or 32 bytes long for AES-256-GCM.
+///
+/// # Buffering
+///
+/// AES-GCM authenticates a message as a whole: a tampered or truncated body
+/// can only be detected once all of it has been read. To ensure that
+/// unauthenticated plaintext is _never_ returned, this stream reads and
+/// authenticates the entire body, up to a limit, on the first read and only
+/// then returns the decrypted bytes. As such, memory for the entire body is
+/// required. Bodies that must be streamed without being buffered should be
+/// encrypted in independently authenticated chunks instead.
+///
+/// # Errors
+///
+/// Reads fail with an error of kind `InvalidData` if the body is larger than
+/// the limit, is too short to contain a nonce and tag, or fails to
+/// authenticate. An error reading the body itself is returned unchanged.
+/// After a failure, every subsequent read fails with an error of the same
+/// kind.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::{self, Read};
+/// use rocket::Data;
+/// use rocket::data::AesGcmStream;
+///
+/// # #[cfg(feature = "aead")]
+/// fn handler(data: Data, session_key: &[u8]) -> io::Result<Vec<u8>> {
+///     let mut stream = data.transform(|s| AesGcmStream::new(s, session_key, 1 << 20));
+///
+///     let mut plaintext = vec![];
+///     stream.read_to_end(&mut plaintext)?;
+///     Ok(plaintext)
+/// }
+/// ```
+pub struct AesGcmStream {
+    state: State,
+}
+
+enum State {
+    Pending { stream: DataStream, key: Vec<u8>, limit: u64 },
+    Authenticated(Cursor<Vec<u8>>),
+    Failed(io::ErrorKind, String),
+}
+
+impl AesGcmStream {
+    /// Returns a stream that decrypts `stream` with `key`, buffering at most
+    /// `limit` bytes of ciphertext.
+    pub fn new(stream: DataStream, key: &[u8], limit: u64) -> AesGcmStream {
+        let pending = State::Pending { stream: stream, key: key.to_vec(), limit: limit };
+        AesGcmStream { state: pending }
+    }
+}
+
+/// Returns an error of kind `InvalidData` for `reason`.
+fn invalid(reason: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+/// Reads all of `stream` and decrypts it in place, returning the plaintext.
+fn decrypt(stream: &mut DataStream, key: &[u8], limit: u64) -> io::Result<Vec<u8>> {
+    let algorithm = match key.len() {
+        16 => &AES_128_GCM,
+        32 => &AES_256_GCM,
+        _ => return Err(invalid("AES-GCM key must be 16 or 32 bytes long"))
+    };
+
+    let key = OpeningKey::new(algorithm, key).map_err(|_| invalid("invalid AES-GCM key"))?;
+
+    let mut body = vec![];
+    let n = stream.take(limit.saturating_add(1)).read_to_end(&mut body)?;
+    if n as u64 > limit {
+        return Err(invalid("encrypted body exceeds limit"));
+    }
+
+    let nonce_len = algorithm.nonce_len();
+    if body.len() < nonce_len + algorithm.tag_len() {
+        return Err(invalid("encrypted body is too short"));
+    }
+
+    let mut ciphertext = body.split_off(nonce_len);
+    let len = aead::open_in_place(&key, &body, &[], 0, &mut ciphertext)
+        .map_err(|_| invalid("encrypted body failed to authenticate"))?
+        .len();
+
+    ciphertext.truncate(len);
+    Ok(ciphertext)
+}
+
+impl Read for AesGcmStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = match self.state {
+            State::Pending { ref mut stream, ref key, limit } => decrypt(stream, key, limit),
+            State::Authenticated(ref mut plaintext) => return plaintext.read(buf),
+            State::Failed(kind, ref reason) => return Err(io::Error::new(kind, reason.clone())),
+        };
+
+        match result {
+            Ok(plaintext) => {
+                self.state = State::Authenticated(Cursor::new(plaintext));
+                self.read(buf)
+            }
+            Err(e) => {
+                self.state = State::Failed(e.kind(), e.to_string());
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use ring::aead::{self, SealingKey, AES_128_GCM};
+
+    use super::AesGcmStream;
+    use data::{Data, ReadHook};
+
+    const KEY: &[u8] = b"0123456789abcdef";
+    const NONCE: &[u8] = b"unique nonce";
+
+    // Returns the nonce, ciphertext, and tag for `plaintext`.
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        let key = SealingKey::new(&AES_128_GCM, KEY).unwrap();
+        let mut sealed = plaintext.to_vec();
+        sealed.extend_from_slice(&[0; 16]);
+        let len = aead::seal_in_place(&key, NONCE, &[], &mut sealed, 16).unwrap();
+        sealed.truncate(len);
+
+        let mut body = NONCE.to_vec();
+        body.extend_from_slice(&sealed);
+        body
+    }
+
+    fn read(body: Vec<u8>, limit: u64) -> (io::Result<usize>, Vec<u8>) {
+        let mut stream = Data::local(body).transform(|s| AesGcmStream::new(s, KEY, limit));
+        let mut plaintext = vec![];
+        (stream.read_to_end(&mut plaintext), plaintext)
+    }
+
+    #[test]
+    fn test_decrypts() {
+        let (result, plaintext) = read(encrypt(b"attack at dawn"), 1024);
+        assert_eq!(result.unwrap(), 14);
+        assert_eq!(plaintext, b"attack at dawn");
+
+        let (result, plaintext) = read(encrypt(b""), 1024);
+        assert_eq!(result.unwrap(), 0);
+        assert!(plaintext.is_empty());
+    }
+
+    #[test]
+    fn test_tampered_tag_is_rejected() {
+        let mut body = encrypt(b"attack at dawn");
+        let last = body.len() - 1;
+        body[last] ^= 1;
+
+        let (result, plaintext) = read(body, 1024);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(plaintext.is_empty());
+    }
+
+    #[test]
+    fn test_short_body_is_rejected() {
+        let (result, plaintext) = read(NONCE.to_vec(), 1024);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(plaintext.is_empty());
+
+        let (result, _) = read(vec![0; 12 + 15], 1024);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_body_over_limit_is_rejected() {
+        let body = encrypt(b"attack at dawn");
+        let limit = body.len() as u64;
+        assert!(read(body.clone(), limit).0.is_ok());
+
+        let (result, plaintext) = read(body, limit - 1);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(plaintext.is_empty());
+    }
+
+    #[test]
+    fn test_read_error_is_preserved() {
+        struct Reset;
+
+        impl ReadHook for Reset {
+            fn before_read(&mut self) -> io::Result<()> {
+                Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset by peer"))
+            }
+        }
+
+        let data = Data::local(encrypt(b"attack at dawn")).with_hook(Reset);
+        let mut stream = data.transform(|s| AesGcmStream::new(s, KEY, 1024));
+        let mut buf = [0; 32];
+
+        let error = stream.read(&mut buf).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+        assert_eq!(error.to_string(), "reset by peer");
+
+        let error = stream.read(&mut buf).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+    }
+}
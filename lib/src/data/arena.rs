@@ -0,0 +1,133 @@
+use std::io::{self, Read};
+use std::cell::RefCell;
+use std::cmp::{min, max};
+use std::slice;
+
+/// The default size of each of an arena's allocations: 64KiB.
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// A bump allocator that packs many bodies into a few large allocations, for
+/// use with [`Data::read_into_arena`].
+///
+/// An arena allocates memory in chunks of at least `chunk_size` bytes and
+/// places each body immediately after the previous one. A body that doesn't
+/// fit in the remainder of the current chunk is moved to a new chunk large
+/// enough to hold it. Memory is only freed when the arena is dropped, so the
+/// slices returned by `read_into_arena` remain valid for as long as the arena
+/// lives.
+///
+/// [`Data::read_into_arena`]: /rocket/data/struct.Data.html#method.read_into_arena
+///
+/// # Example
+///
+/// ```rust
+/// use std::io;
+/// use rocket::Data;
+/// use rocket::data::Arena;
+///
+/// fn ingest(bodies: Vec<Data>) -> io::Result<usize> {
+///     let arena = Arena::new();
+///     let mut slices = vec![];
+///     for data in bodies {
+///         slices.push(data.read_into_arena(&arena)?);
+///     }
+///
+///     /* process `slices` as a batch */
+///     Ok(slices.iter().map(|s| s.len()).sum())
+/// }
+/// ```
+pub struct Arena {
+    chunks: RefCell<Vec<Vec<u8>>>,
+    chunk_size: usize,
+}
+
+impl Arena {
+    /// Returns an empty arena that allocates in chunks of 64KiB.
+    #[inline]
+    pub fn new() -> Arena {
+        Arena::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Returns an empty arena that allocates in chunks of at least
+    /// `chunk_size` bytes.
+    #[inline]
+    pub fn with_chunk_size(chunk_size: usize) -> Arena {
+        Arena { chunks: RefCell::new(vec![]), chunk_size: max(chunk_size, 1) }
+    }
+
+    /// Returns the number of bytes this arena has allocated.
+    pub fn allocated(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.capacity()).sum()
+    }
+
+    /// Reads `reader` to its end into the arena, returning the bytes read.
+    /// `hint`, if known, is the number of bytes `reader` will produce; it
+    /// isn't trusted beyond the size of a chunk. If the read fails, the bytes
+    /// read are discarded.
+    pub(crate) fn append<R: Read>(&self, reader: &mut R, hint: Option<u64>) -> io::Result<&[u8]> {
+        let mut chunks = self.chunks.borrow_mut();
+        let fits = |chunk: &Vec<u8>, len: usize| chunk.capacity() - chunk.len() >= len;
+        let len = hint.map_or(0, |n| min(n, self.chunk_size as u64) as usize);
+        if chunks.last().map_or(true, |chunk| !fits(chunk, len)) {
+            chunks.push(Vec::with_capacity(self.chunk_size));
+        }
+
+        let mut start = chunks.last().map_or(0, |chunk| chunk.len());
+        let mut overflow = [0; 4096];
+        loop {
+            let full = chunks.last().map_or(true, |chunk| chunk.len() == chunk.capacity());
+            if full {
+                // Only move the body if it doesn't end exactly at the chunk's end.
+                let n = match reader.read(&mut overflow) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        if let Some(chunk) = chunks.last_mut() {
+                            chunk.truncate(start);
+                        }
+
+                        return Err(e);
+                    }
+                };
+
+                // Move the body to a chunk with room for it to grow.
+                let mut body = chunks.last_mut().expect("arena has a chunk").split_off(start);
+                body.extend_from_slice(&overflow[..n]);
+                let capacity = max(self.chunk_size, body.len() * 2);
+                body.reserve_exact(capacity - body.len());
+                chunks.push(body);
+                start = 0;
+                continue;
+            }
+
+            // Neither resizing nor truncating grows the chunk, so its contents
+            // never move.
+            let chunk = chunks.last_mut().expect("arena has a chunk");
+            let (len, capacity) = (chunk.len(), chunk.capacity());
+            chunk.resize(capacity, 0);
+            match reader.read(&mut chunk[len..]) {
+                Ok(0) => { chunk.truncate(len); break; }
+                Ok(n) => chunk.truncate(len + n),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => chunk.truncate(len),
+                Err(e) => {
+                    chunk.truncate(start);
+                    return Err(e);
+                }
+            }
+        }
+
+        // The slice borrows from a chunk that is never grown, shrunk below the
+        // slice, or freed before the arena is dropped.
+        let chunk = chunks.last().expect("arena has a chunk");
+        let body = &chunk[start..];
+        Ok(unsafe { slice::from_raw_parts(body.as_ptr(), body.len()) })
+    }
+}
+
+impl Default for Arena {
+    #[inline]
+    fn default() -> Arena {
+        Arena::new()
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+
+use super::data_stream::ReadHook;
+
+/// Retains the first and last `limit` bytes read from a body and logs them
+/// once the body's stream is dropped.
+pub struct Audit {
+    limit: usize,
+    total: u64,
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+}
+
+impl Audit {
+    pub fn new(limit: usize) -> Audit {
+        Audit { limit: limit, total: 0, head: vec![], tail: VecDeque::new() }
+    }
+}
+
+/// Formats bytes as an escaped byte string.
+struct Escaped<I>(I);
+
+impl<'a, I: Iterator<Item = &'a u8> + Clone> fmt::Display for Escaped<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "b\"")?;
+        for &byte in self.0.clone() {
+            for c in ::std::ascii::escape_default(byte) {
+                write!(f, "{}", c as char)?;
+            }
+        }
+
+        write!(f, "\"")
+    }
+}
+
+impl ReadHook for Audit {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.total += bytes.len() as u64;
+
+        let head_room = self.limit - self.head.len();
+        let (head, rest) = bytes.split_at(::std::cmp::min(head_room, bytes.len()));
+        self.head.extend_from_slice(head);
+
+        // Only keep bytes in the tail that aren't already in the head.
+        let skip = rest.len().saturating_sub(self.limit);
+        for &byte in &rest[skip..] {
+            if self.tail.len() == self.limit {
+                self.tail.pop_front();
+            }
+
+            self.tail.push_back(byte);
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        info_!("Body audit: {} bytes read.", self.total);
+        info_!("First {} bytes: {}", self.head.len(), Escaped(self.head.iter()));
+        if !self.tail.is_empty() {
+            info_!("Last {} bytes: {}", self.tail.len(), Escaped(self.tail.iter()));
+        }
+    }
+}
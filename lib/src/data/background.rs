@@ -0,0 +1,63 @@
+use std::cmp::min;
+use std::io::{self, Read};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::thread::{self, JoinHandle};
+
+use super::Data;
+
+/// A reader over a request body that's read from the network by a separate
+/// thread.
+///
+/// This reader can only be obtained by calling
+/// [Data::read_in_background](/rocket/data/struct.Data.html#method.read_in_background).
+/// The thread reads the body into a bounded channel of chunks while this
+/// reader consumes them, so processing the body overlaps with receiving it.
+/// An error reading the body is returned by the read that reaches it, once
+/// the chunks before it have been consumed.
+///
+/// If a `BackgroundReader` is dropped before the body ends, the thread stops
+/// after its next read from the network.
+pub struct BackgroundReader {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    reader: Option<JoinHandle<io::Result<u64>>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl BackgroundReader {
+    pub(crate) fn spawn(data: Data, chunk: usize, capacity: usize) -> io::Result<Self> {
+        let (tx, rx) = sync_channel(capacity);
+        let reader = thread::Builder::new().name("rocket-body-reader".into())
+            .spawn(move || data.stream_to_channel(tx, chunk))?;
+
+        Ok(BackgroundReader { chunks: rx, reader: Some(reader), chunk: vec![], pos: 0 })
+    }
+
+    // Called once the channel is closed: the body either ended, failed with
+    // an error that was already returned, or the thread panicked.
+    fn join(&mut self) -> io::Result<usize> {
+        match self.reader.take().map(|reader| reader.join()) {
+            Some(Err(_)) => {
+                Err(io::Error::new(io::ErrorKind::Other, "body reader thread panicked"))
+            }
+            _ => Ok(0)
+        }
+    }
+}
+
+impl Read for BackgroundReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos >= self.chunk.len() {
+            match self.chunks.recv() {
+                Ok(Ok(chunk)) => { self.chunk = chunk; self.pos = 0; }
+                Ok(Err(e)) => return Err(e),
+                Err(_) => return self.join()
+            }
+        }
+
+        let n = min(buf.len(), self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..(self.pos + n)]);
+        self.pos += n;
+        Ok(n)
+    }
+}
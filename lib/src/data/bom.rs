@@ -0,0 +1,190 @@
+use std::io::{self, Read};
+use std::char::decode_utf16;
+
+use super::DataStream;
+
+/// A byte order mark that may begin a text body, as detected by
+/// [`Data::open_without_bom`].
+///
+/// [`Data::open_without_bom`]: /rocket/data/struct.Data.html#method.open_without_bom
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bom {
+    /// The UTF-8 byte order mark, `EF BB BF`.
+    Utf8,
+    /// The little-endian UTF-16 byte order mark, `FF FE`.
+    Utf16Le,
+    /// The big-endian UTF-16 byte order mark, `FE FF`.
+    Utf16Be,
+}
+
+impl Bom {
+    /// Returns the byte order mark that `bytes`, the first bytes of a body,
+    /// begin with, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::data::Bom;
+    ///
+    /// assert_eq!(Bom::detect(b"\xEF\xBB\xBFhi"), Some(Bom::Utf8));
+    /// assert_eq!(Bom::detect(b"\xFF\xFEh\x00"), Some(Bom::Utf16Le));
+    /// assert_eq!(Bom::detect(b"hi"), None);
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Option<Bom> {
+        if bytes.starts_with(b"\xEF\xBB\xBF") {
+            Some(Bom::Utf8)
+        } else if bytes.starts_with(b"\xFF\xFE") {
+            Some(Bom::Utf16Le)
+        } else if bytes.starts_with(b"\xFE\xFF") {
+            Some(Bom::Utf16Be)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the length of this byte order mark in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match *self {
+            Bom::Utf8 => 3,
+            Bom::Utf16Le | Bom::Utf16Be => 2,
+        }
+    }
+}
+
+fn invalid_utf16() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "body is not valid UTF-16")
+}
+
+/// Appends the UTF-8 encoding of the UTF-16 code units in `input` to `out`,
+/// removing the bytes decoded from `input`. A trailing odd byte or high
+/// surrogate is left in `input` to be completed by the next read.
+pub(crate) fn transcode(
+    input: &mut Vec<u8>,
+    big_endian: bool,
+    out: &mut Vec<u8>
+) -> io::Result<()> {
+    let mut units: Vec<u16> = input.chunks(2).filter(|pair| pair.len() == 2).map(|pair| {
+        let (hi, lo) = if big_endian { (pair[0], pair[1]) } else { (pair[1], pair[0]) };
+        ((hi as u16) << 8) | lo as u16
+    }).collect();
+
+    if units.last().map_or(false, |&unit| unit >= 0xD800 && unit < 0xDC00) {
+        units.pop();
+    }
+
+    let mut encoded = [0; 4];
+    for c in decode_utf16(units.iter().cloned()) {
+        let c = c.map_err(|_| invalid_utf16())?;
+        out.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+    }
+
+    input.drain(..(units.len() * 2));
+    Ok(())
+}
+
+/// A stream over a text body without its leading byte order mark. A body that
+/// began with a UTF-16 byte order mark is transcoded to UTF-8.
+///
+/// This stream can only be obtained by calling
+/// [Data::open_without_bom](/rocket/data/struct.Data.html#method.open_without_bom).
+/// Bodies without a byte order mark, or with a UTF-8 one, are passed through
+/// untouched and aren't validated; reading into a `String` does so.
+pub struct BomStripped {
+    stream: DataStream,
+    bom: Option<Bom>,
+    // Whether the byte order mark has yet to be read from `stream`.
+    at_start: bool,
+    // The UTF-16 bytes not yet transcoded.
+    pending: Vec<u8>,
+    // The transcoded bytes not yet returned and the position of the next.
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl BomStripped {
+    pub(crate) fn new(stream: DataStream, bom: Option<Bom>) -> BomStripped {
+        BomStripped {
+            stream: stream,
+            bom: bom,
+            at_start: bom.is_some(),
+            pending: vec![],
+            out: vec![],
+            pos: 0
+        }
+    }
+
+    /// Returns the byte order mark that the body began with, if any.
+    #[inline]
+    pub fn bom(&self) -> Option<Bom> {
+        self.bom
+    }
+}
+
+impl Read for BomStripped {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.at_start {
+            let mut bom = [0; 3];
+            let len = self.bom.map_or(0, |bom| bom.len());
+            self.stream.read_exact(&mut bom[..len])?;
+            self.at_start = false;
+        }
+
+        let big_endian = match self.bom {
+            Some(Bom::Utf16Le) => false,
+            Some(Bom::Utf16Be) => true,
+            _ => return self.stream.read(buf)
+        };
+
+        while self.pos >= self.out.len() {
+            let mut chunk = [0; 4096];
+            let n = self.stream.read(&mut chunk)?;
+            if n == 0 {
+                if !self.pending.is_empty() {
+                    return Err(invalid_utf16());
+                }
+
+                return Ok(0);
+            }
+
+            self.out.clear();
+            self.pos = 0;
+            self.pending.extend_from_slice(&chunk[..n]);
+            transcode(&mut self.pending, big_endian, &mut self.out)?;
+        }
+
+        let n = ::std::cmp::min(buf.len(), self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..(self.pos + n)]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transcode;
+
+    fn transcode_split(pieces: &[&[u8]], big_endian: bool) -> Vec<u8> {
+        let (mut pending, mut out) = (vec![], vec![]);
+        for piece in pieces {
+            pending.extend_from_slice(piece);
+            transcode(&mut pending, big_endian, &mut out).unwrap();
+        }
+
+        assert!(pending.is_empty());
+        out
+    }
+
+    #[test]
+    fn test_transcode() {
+        assert_eq!(transcode_split(&[&b"h\x00i\x00"[..]], false), b"hi");
+        assert_eq!(transcode_split(&[&b"\x00h\x00i"[..]], true), b"hi");
+
+        // An odd byte and a surrogate pair split across reads.
+        let input: &[&[u8]] = &[b"h", b"\x00\x3D\xD8", b"\x00\xDE"];
+        assert_eq!(transcode_split(input, false), "h\u{1F600}".as_bytes());
+
+        let mut lone_low = b"\x00\xDC".to_vec();
+        assert!(transcode(&mut lone_low, false, &mut vec![]).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::{self, Write};
+
+use ring::digest;
+
+/// A hash algorithm used to name the files written by
+/// [`Data::stream_to_cas`](/rocket/data/struct.Data.html#method.stream_to_cas).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// SHA-256, producing 64 hexadecimal digits.
+    Sha256,
+    /// SHA-384, producing 96 hexadecimal digits.
+    Sha384,
+    /// SHA-512, producing 128 hexadecimal digits.
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn ring(&self) -> &'static digest::Algorithm {
+        match *self {
+            HashAlgorithm::Sha256 => &digest::SHA256,
+            HashAlgorithm::Sha384 => &digest::SHA384,
+            HashAlgorithm::Sha512 => &digest::SHA512,
+        }
+    }
+}
+
+/// A writer that hashes every byte written through it to `inner`.
+pub(crate) struct HashingWriter<W> {
+    inner: W,
+    context: digest::Context,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub(crate) fn new(inner: W, algorithm: HashAlgorithm) -> HashingWriter<W> {
+        HashingWriter { inner: inner, context: digest::Context::new(algorithm.ring()) }
+    }
+
+    /// Returns the inner writer and the lowercase hexadecimal digest of the
+    /// bytes written.
+    pub(crate) fn finish(self) -> (W, String) {
+        let digest = self.context.finish();
+        let hex = digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+        (self.inner, hex)
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.context.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
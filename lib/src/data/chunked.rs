@@ -0,0 +1,84 @@
+use std::io::{self, Read};
+
+use super::data::BodyReader;
+use super::error::DataError;
+use http::hyper::h1::HttpReader::ChunkedReader;
+
+/// Tracks the index of the chunk being read from a chunked body so that the
+/// framing errors Hyper reports can be converted into a
+/// `DataError::MalformedChunk` naming the offending chunk.
+///
+/// Hyper can't resynchronize with a body whose framing it failed to parse, so
+/// once a framing error is found, every later read fails with the same error
+/// instead of reading on from wherever Hyper stopped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChunkTracker {
+    index: u64,
+    malformed: bool,
+}
+
+impl ChunkTracker {
+    /// Fails if an earlier read found the body malformed.
+    pub fn check(&self) -> io::Result<()> {
+        match self.malformed {
+            true => Err(self.error()),
+            false => Ok(())
+        }
+    }
+
+    /// Records `result`, the result of a read from `body`, returning it or
+    /// the framing error it contains as a `MalformedChunk` error. Results of
+    /// reads from bodies that aren't chunked are returned as-is.
+    pub fn record(&mut self, body: &BodyReader, result: io::Result<usize>) -> io::Result<usize> {
+        let state = match *body {
+            ChunkedReader(_, state) => state,
+            _ => return result
+        };
+
+        match result {
+            Ok(n) => {
+                // Hyper forgets a chunk's size once its trailing CRLF is read.
+                if n > 0 && state.is_none() {
+                    self.index += 1;
+                }
+
+                Ok(n)
+            }
+            Err(ref e) if is_framing_error(e) => {
+                self.malformed = true;
+                Err(self.error())
+            }
+            Err(e) => Err(e)
+        }
+    }
+
+    fn error(&self) -> io::Error {
+        let error = DataError::MalformedChunk { index: self.index };
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Hyper reports an invalid chunk size or missing CRLF as `InvalidInput` and
+/// a body that ends inside a chunk as `UnexpectedEof` or an `Other` "early
+/// eof" error.
+fn is_framing_error(e: &io::Error) -> bool {
+    match e.kind() {
+        io::ErrorKind::InvalidInput | io::ErrorKind::UnexpectedEof => true,
+        io::ErrorKind::Other => e.to_string() == "early eof",
+        _ => false
+    }
+}
+
+/// A reader of `body` that records every read in `tracker`.
+pub struct Tracked<'a> {
+    pub body: &'a mut BodyReader,
+    pub tracker: &'a mut ChunkTracker,
+}
+
+impl<'a> Read for Tracked<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.tracker.check()?;
+        let result = self.body.read(buf);
+        self.tracker.record(self.body, result)
+    }
+}
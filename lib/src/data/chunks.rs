@@ -0,0 +1,55 @@
+use std::io;
+
+use ext::ReadExt;
+use super::DataStream;
+
+/// An iterator over the body of a request in fixed-size chunks, each paired
+/// with the offset of its first byte in the body.
+///
+/// Returned by [`Data::offset_chunks`]. Every chunk but the last is exactly
+/// the requested size; the last holds whatever remains and is never empty.
+/// Offsets count from the first byte of the body, including the bytes in the
+/// peek buffer. After an error is yielded, the iterator is exhausted.
+///
+/// [`Data::offset_chunks`]: /rocket/data/struct.Data.html#method.offset_chunks
+pub struct OffsetChunks {
+    stream: DataStream,
+    size: usize,
+    offset: u64,
+    done: bool,
+}
+
+impl OffsetChunks {
+    pub(crate) fn new(stream: DataStream, size: usize) -> OffsetChunks {
+        OffsetChunks { stream: stream, size: ::std::cmp::max(size, 1), offset: 0, done: false }
+    }
+}
+
+impl Iterator for OffsetChunks {
+    type Item = io::Result<(u64, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut chunk = vec![0; self.size];
+        match self.stream.read_max(&mut chunk) {
+            Ok(0) => {
+                self.done = true;
+                None
+            }
+            Ok(n) => {
+                chunk.truncate(n);
+                self.done = n < self.size;
+                let offset = self.offset;
+                self.offset += n as u64;
+                Some(Ok((offset, chunk)))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
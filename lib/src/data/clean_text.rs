@@ -0,0 +1,160 @@
+use std::io;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use outcome::Outcome::*;
+use http::Status;
+use request::Request;
+use super::{Data, FromData, Outcome};
+use super::data_stream::ReadHook;
+
+/// A policy deciding which bytes a [`CleanText`] body may contain.
+///
+/// [`CleanText`]: /rocket/data/struct.CleanText.html
+pub trait TextPolicy {
+    /// Returns `true` if `byte` may appear in the body.
+    fn allows(byte: u8) -> bool;
+}
+
+#[inline(always)]
+fn is_control(byte: u8) -> bool {
+    byte < 0x20 || byte == 0x7F
+}
+
+/// The default [`TextPolicy`]: rejects NUL and every other ASCII control
+/// byte except tab, line feed, and carriage return.
+///
+/// [`TextPolicy`]: /rocket/data/trait.TextPolicy.html
+#[derive(Debug)]
+pub struct Multiline;
+
+impl TextPolicy for Multiline {
+    #[inline]
+    fn allows(byte: u8) -> bool {
+        !is_control(byte) || byte == b'\t' || byte == b'\n' || byte == b'\r'
+    }
+}
+
+/// A [`TextPolicy`] that rejects every ASCII control byte except tab, so that
+/// the body is a single line.
+///
+/// [`TextPolicy`]: /rocket/data/trait.TextPolicy.html
+#[derive(Debug)]
+pub struct SingleLine;
+
+impl TextPolicy for SingleLine {
+    #[inline]
+    fn allows(byte: u8) -> bool {
+        !is_control(byte) || byte == b'\t'
+    }
+}
+
+/// A data guard that wraps another data guard `T`, rejecting bodies containing
+/// bytes that the policy `P` disallows, NUL and other control bytes by
+/// default.
+///
+/// Every byte that `T` reads is checked as it's read, so the body is never
+/// buffered on `CleanText`'s account and reading stops at the first disallowed
+/// byte: the read that returns it fails instead. A body with a disallowed byte
+/// fails with `400 Bad Request` even if `T` ignored the failed read. Bytes
+/// that `T` doesn't read aren't checked.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rocket::data::{CleanText, SingleLine};
+///
+/// #[post("/notes", data = "<note>")]
+/// fn note(note: CleanText<String>) { ... }
+///
+/// #[post("/title", data = "<title>")]
+/// fn title(title: CleanText<String, SingleLine>) { ... }
+/// ```
+#[derive(Debug)]
+pub struct CleanText<T, P: TextPolicy = Multiline> {
+    value: T,
+    _policy: PhantomData<P>,
+}
+
+impl<T, P: TextPolicy> CleanText<T, P> {
+    /// Consumes `self` and returns the value produced by the inner guard.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, P: TextPolicy> Deref for CleanText<T, P> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+/// The error returned by the `CleanText` data guard.
+#[derive(Debug)]
+pub enum CleanTextError<E> {
+    /// The body contained `byte` at `offset`, which the policy disallows.
+    Disallowed {
+        /// The disallowed byte.
+        byte: u8,
+        /// The offset of the byte from the start of the body.
+        offset: u64,
+    },
+    /// The inner data guard failed.
+    Inner(E),
+}
+
+/// A read hook that fails the read returning the first disallowed byte.
+struct Checker<P> {
+    offset: u64,
+    violation: Arc<Mutex<Option<(u8, u64)>>>,
+    _policy: PhantomData<fn(P)>,
+}
+
+impl<P: TextPolicy> ReadHook for Checker<P> {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if let Some(i) = bytes.iter().position(|&b| !P::allows(b)) {
+            let found = (bytes[i], self.offset + i as u64);
+            if let Ok(mut violation) = self.violation.lock() {
+                *violation = Some(found);
+            }
+
+            let msg = format!("disallowed byte 0x{:02x} at offset {}", found.0, found.1);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+impl<T: FromData, P: TextPolicy + 'static> FromData for CleanText<T, P> {
+    type Error = CleanTextError<T::Error>;
+
+    fn from_data(request: &Request, data: Data) -> Outcome<Self, Self::Error> {
+        let violation = Arc::new(Mutex::new(None));
+        let checker: Checker<P> = Checker {
+            offset: 0,
+            violation: violation.clone(),
+            _policy: PhantomData
+        };
+
+        let outcome = T::from_data(request, data.with_hook(checker));
+        let found = violation.lock().ok().and_then(|v| *v);
+        if let Some((byte, offset)) = found {
+            warn_!("Rejecting body with disallowed byte 0x{:02x} at offset {}.", byte, offset);
+            let error = CleanTextError::Disallowed { byte: byte, offset: offset };
+            return Failure((Status::BadRequest, error));
+        }
+
+        match outcome {
+            Success(value) => Success(CleanText { value: value, _policy: PhantomData }),
+            Failure((status, e)) => Failure((status, CleanTextError::Inner(e))),
+            Forward(data) => Forward(data),
+        }
+    }
+}
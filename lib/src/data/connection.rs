@@ -0,0 +1,192 @@
+use std::{io, fmt};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::data_stream::ReadHook;
+
+/// The maximum number of request bodies that may be open at once on a single
+/// connection. HTTP/1.1 requests on a connection are handled one at a time, so
+/// a second open body means the first was leaked by its handler.
+pub(crate) const MAX_OPEN_STREAMS: usize = 1;
+
+/// How long a connection's spent body budget is remembered after its last
+/// body was read. Hyper closes keep-alive connections that are idle for far
+/// less, so a connection that's been quiet this long is gone.
+const BUDGET_IDLE_SECS: u64 = 60;
+
+type Counts = Arc<Mutex<HashMap<SocketAddr, usize>>>;
+
+// A poisoned lock only means another request panicked mid-update; the map is
+// still usable, and panicking here would fail this request.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner()
+    }
+}
+
+/// The number of request bodies open on each connection, keyed by the peer
+/// address of the connection.
+#[derive(Default)]
+pub(crate) struct OpenStreams {
+    counts: Counts,
+}
+
+impl OpenStreams {
+    /// Records that a body was opened on the connection to `peer`, returning a
+    /// guard that records its closing when dropped. Returns `None` if `max`
+    /// bodies are already open on the connection.
+    pub(crate) fn acquire(&self, peer: SocketAddr, max: usize) -> Option<StreamGuard> {
+        let mut counts = lock(&self.counts);
+        let count = counts.entry(peer).or_insert(0);
+        if *count >= max {
+            return None;
+        }
+
+        *count += 1;
+        Some(StreamGuard { counts: self.counts.clone(), peer: peer })
+    }
+}
+
+/// Marks a request body as open on a connection for as long as it is alive.
+pub(crate) struct StreamGuard {
+    counts: Counts,
+    peer: SocketAddr,
+}
+
+// The guard rides along with the `DataStream` once the body is opened.
+impl ReadHook for StreamGuard {  }
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        let mut counts = lock(&self.counts);
+        let remaining = match counts.get_mut(&self.peer) {
+            Some(count) => { *count = count.saturating_sub(1); *count }
+            None => return
+        };
+
+        if remaining == 0 {
+            counts.remove(&self.peer);
+        }
+    }
+}
+
+/// The error returned by reads of a request body once its connection has sent
+/// more body bytes, summed across all of its requests, than the configured
+/// `connection_body_budget`. The connection is closed once the response to
+/// the request is written.
+///
+/// This error is returned as the inner error of an `io::Error` of kind
+/// `InvalidData` and can be recovered with `io::Error::get_ref` and
+/// `downcast_ref`. [`read_error_status`](/rocket/data/fn.read_error_status.html)
+/// maps it to `413 Payload Too Large`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionBudgetExceeded {
+    /// The budget, in bytes, that the connection exceeded.
+    pub budget: u64,
+}
+
+impl fmt::Display for ConnectionBudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "connection exceeds its body budget of {} bytes", self.budget)
+    }
+}
+
+impl ::std::error::Error for ConnectionBudgetExceeded {
+    fn description(&self) -> &str {
+        "connection exceeds its body budget"
+    }
+}
+
+struct Spent {
+    bytes: u64,
+    seen: Instant,
+}
+
+type Budgets = Arc<Mutex<HashMap<SocketAddr, Spent>>>;
+
+/// The number of body bytes each connection has sent across its requests,
+/// keyed by the peer address of the connection.
+#[derive(Default)]
+pub(crate) struct BodyBudgets {
+    spent: Budgets,
+}
+
+impl BodyBudgets {
+    /// Returns a hook that charges the bytes read from a body on the
+    /// connection to `peer` against its `budget`. Connections that haven't
+    /// had a body read in a while are forgotten.
+    pub(crate) fn charge(&self, peer: SocketAddr, budget: u64) -> BudgetHook {
+        let idle = Duration::from_secs(BUDGET_IDLE_SECS);
+        let mut spent = lock(&self.spent);
+        spent.retain(|_, spent| spent.seen.elapsed() < idle);
+        let start = spent.get(&peer).map_or(0, |spent| spent.bytes);
+        BudgetHook {
+            spent: self.spent.clone(),
+            peer: peer,
+            budget: budget,
+            start: start,
+            read: 0,
+        }
+    }
+
+    /// Returns `true` if the connection to `peer` has sent more than `budget`
+    /// body bytes, forgetting it if so: the connection is about to be closed.
+    pub(crate) fn take_exhausted(&self, peer: SocketAddr, budget: u64) -> bool {
+        let mut spent = lock(&self.spent);
+        let exhausted = spent.get(&peer).map_or(false, |spent| spent.bytes > budget);
+        if exhausted {
+            spent.remove(&peer);
+        }
+
+        exhausted
+    }
+}
+
+/// A read hook that fails the read that takes a connection past its body
+/// budget. Only one body is open on a connection at a time, so the bytes
+/// read are only recorded once the body is dropped.
+pub(crate) struct BudgetHook {
+    spent: Budgets,
+    peer: SocketAddr,
+    budget: u64,
+    start: u64,
+    read: u64,
+}
+
+impl ReadHook for BudgetHook {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.after_splice(bytes.len())
+    }
+
+    fn observes_bytes(&self) -> bool {
+        false
+    }
+
+    fn after_splice(&mut self, n: usize) -> io::Result<()> {
+        let was_over = self.start + self.read > self.budget;
+        self.read += n as u64;
+        if self.start + self.read <= self.budget {
+            return Ok(());
+        }
+
+        if !was_over {
+            warn_!("Connection from {} exceeded its body budget of {} bytes.",
+                   self.peer, self.budget);
+        }
+
+        let error = ConnectionBudgetExceeded { budget: self.budget };
+        Err(io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl Drop for BudgetHook {
+    fn drop(&mut self) {
+        let mut spent = lock(&self.spent);
+        let entry = spent.entry(self.peer).or_insert(Spent { bytes: 0, seen: Instant::now() });
+        entry.bytes = self.start + self.read;
+        entry.seen = Instant::now();
+    }
+}
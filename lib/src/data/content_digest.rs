@@ -0,0 +1,157 @@
+use std::{io, fmt};
+
+use base64;
+use ring::{digest, constant_time};
+
+/// The error returned when the digest of a body doesn't match the digest
+/// declared in its `Content-Digest` header.
+///
+/// This error is returned as the inner error of an `io::Error` of kind
+/// `InvalidData` and can be recovered with `io::Error::get_ref` and
+/// `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentDigestMismatch {
+    /// The key of the algorithm whose digest didn't match, such as `sha-256`.
+    pub algorithm: &'static str,
+}
+
+impl fmt::Display for ContentDigestMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "body does not match its {} content digest", self.algorithm)
+    }
+}
+
+impl ::std::error::Error for ContentDigestMismatch {
+    fn description(&self) -> &str {
+        "content digest mismatch"
+    }
+}
+
+/// The keys of the supported algorithms, strongest first.
+const ALGORITHMS: &[&str] = &["sha-512", "sha-256"];
+
+fn algorithm(key: &str) -> &'static digest::Algorithm {
+    match key {
+        "sha-512" => &digest::SHA512,
+        _ => &digest::SHA256,
+    }
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "malformed Content-Digest header")
+}
+
+/// Splits `input` at every `separator` that isn't inside a quoted string.
+fn split_unquoted(input: &str, separator: char) -> Vec<&str> {
+    let (mut parts, mut start) = (vec![], 0);
+    let (mut quoted, mut escaped) = (false, false);
+    for (i, c) in input.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            parts.push(&input[start..i]);
+            start = i + 1;
+        }
+    }
+
+    parts.push(&input[start..]);
+    parts
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some('a'...'z') | Some('*') => {}
+        _ => return false
+    }
+
+    chars.all(|c| match c {
+        'a'...'z' | '0'...'9' | '_' | '-' | '.' | '*' => true,
+        _ => false
+    })
+}
+
+/// Parses a `Content-Digest` header, a structured field dictionary (RFC 8941),
+/// into the keys and decoded values of its byte sequence members. Members
+/// whose values aren't byte sequences are skipped, as are any parameters. A
+/// key that appears more than once takes its last value.
+pub(crate) fn parse(header: &str) -> io::Result<Vec<(&str, Vec<u8>)>> {
+    let mut members: Vec<(&str, Vec<u8>)> = vec![];
+    for member in split_unquoted(header, ',') {
+        let member = member.trim_matches(|c: char| c == ' ' || c == '\t');
+        let item = split_unquoted(member, ';')[0];
+        let (key, value) = match item.find('=') {
+            Some(i) => (&item[..i], Some(&item[(i + 1)..])),
+            None => (item, None)
+        };
+
+        if !is_valid_key(key) {
+            return Err(malformed());
+        }
+
+        members.retain(|&(k, _)| k != key);
+        let value = match value {
+            Some(v) if v.len() >= 2 && v.starts_with(':') && v.ends_with(':') => v,
+            _ => continue
+        };
+
+        let bytes = base64::decode(&value[1..(value.len() - 1)]).map_err(|_| malformed())?;
+        members.push((key, bytes));
+    }
+
+    Ok(members)
+}
+
+/// Checks `body` against the strongest supported digest in `header`.
+pub(crate) fn verify(header: &str, body: &[u8]) -> io::Result<()> {
+    let members = parse(header)?;
+    let chosen = ALGORITHMS.iter().filter_map(|&name| {
+        members.iter().find(|&&(key, _)| key == name).map(|m| (name, &m.1))
+    }).next();
+
+    let (name, expected) = match chosen {
+        Some(chosen) => chosen,
+        None => {
+            let msg = "Content-Digest has no supported algorithm";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+        }
+    };
+
+    let actual = digest::digest(algorithm(name), body);
+    match constant_time::verify_slices_are_equal(actual.as_ref(), expected) {
+        Ok(()) => Ok(()),
+        Err(_) => {
+            let error = ContentDigestMismatch { algorithm: name };
+            Err(io::Error::new(io::ErrorKind::InvalidData, error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn test_parse() {
+        let members = parse("sha-256=:aGVsbG8=:, id-sha-512=:d29ybGQ=:;p=1").unwrap();
+        let expected = vec![("sha-256", b"hello".to_vec()), ("id-sha-512", b"world".to_vec())];
+        assert_eq!(members, expected);
+
+        let members = parse("sha-256=:aGVsbG8=:, sha-256=:d29ybGQ=:, flag, n=1").unwrap();
+        assert_eq!(members, vec![("sha-256", b"world".to_vec())]);
+
+        let members = parse(r#"a=:aGVsbG8=:;note="x, y""#).unwrap();
+        assert_eq!(members, vec![("a", b"hello".to_vec())]);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(parse("SHA-256=:aGVsbG8=:").is_err());
+        assert!(parse("sha-256=:not base64:").is_err());
+        assert!(parse("=:aGVsbG8=:").is_err());
+    }
+}
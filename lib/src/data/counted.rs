@@ -0,0 +1,97 @@
+use std::io;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use outcome::Outcome::*;
+use request::Request;
+use super::{Data, FromData, Outcome};
+use super::data_stream::ReadHook;
+
+/// A data guard that wraps another data guard `T`, counting the bytes and
+/// lines of the body that `T` reads.
+///
+/// The counts include every byte read through the body stream, whether the
+/// byte was part of the peek buffer or was read from the network afterwards.
+/// Bytes that `T` inspects only via [peek](/rocket/data/struct.Data.html#method.peek)
+/// without opening the stream are not counted. Lines are counted by the number
+/// of `\n` bytes read.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/upload", data = "<upload>")]
+/// fn upload(upload: Counted<String>) -> String {
+///     format!("{} bytes in {} lines", upload.bytes(), upload.lines())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Counted<T> {
+    value: T,
+    bytes: usize,
+    lines: usize,
+}
+
+impl<T> Counted<T> {
+    /// Returns the number of body bytes read by the inner guard.
+    #[inline(always)]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the number of `\n` bytes read by the inner guard.
+    #[inline(always)]
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Consumes `self` and returns the value produced by the inner guard.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Counted<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+#[derive(Default)]
+struct Counts {
+    bytes: AtomicUsize,
+    lines: AtomicUsize,
+}
+
+/// A read hook that tallies the bytes and newlines passing through a stream.
+struct Counter(Arc<Counts>);
+
+impl ReadHook for Counter {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let lines = bytes.iter().filter(|&&b| b == b'\n').count();
+        self.0.bytes.fetch_add(bytes.len(), Ordering::Relaxed);
+        self.0.lines.fetch_add(lines, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl<T: FromData> FromData for Counted<T> {
+    type Error = T::Error;
+
+    fn from_data(request: &Request, data: Data) -> Outcome<Self, Self::Error> {
+        let counts = Arc::new(Counts::default());
+        match T::from_data(request, data.with_hook(Counter(counts.clone()))) {
+            Success(value) => Success(Counted {
+                value: value,
+                bytes: counts.bytes.load(Ordering::Relaxed),
+                lines: counts.lines.load(Ordering::Relaxed),
+            }),
+            Failure(e) => Failure(e),
+            Forward(data) => Forward(data),
+        }
+    }
+}
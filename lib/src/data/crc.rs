@@ -0,0 +1,34 @@
+use std::io::{self, Write};
+
+use crc32c::crc32c_append;
+
+/// A writer that computes the CRC32C (Castagnoli) checksum of every byte
+/// written through it to `inner`. The checksum is hardware accelerated where
+/// the CPU supports it.
+pub(crate) struct Crc32cWriter<W> {
+    inner: W,
+    crc: u32,
+}
+
+impl<W: Write> Crc32cWriter<W> {
+    pub(crate) fn new(inner: W) -> Crc32cWriter<W> {
+        Crc32cWriter { inner: inner, crc: 0 }
+    }
+
+    /// Returns the CRC32C of the bytes written.
+    pub(crate) fn crc(&self) -> u32 {
+        self.crc
+    }
+}
+
+impl<W: Write> Write for Crc32cWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc = crc32c_append(self.crc, &buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -1,18 +1,54 @@
-use std::io::{self, Read, Write, Cursor, Chain};
+use std::io::{self, Read, Write, Seek, SeekFrom, Cursor, Chain};
+use std::ascii::AsciiExt;
 use std::path::Path;
-use std::fs::File;
-use std::time::Duration;
+use std::process::Command;
+use std::fs::{File, OpenOptions};
+use std::time::{Duration, Instant};
+use std::cmp::min;
+use std::net::{SocketAddr, TcpStream};
+use std::sync::mpsc::SyncSender;
 
-#[cfg(feature = "tls")] use super::net_stream::HttpsStream;
-
-use super::data_stream::{DataStream, kill_stream};
+use super::data_stream::{DataStream, ReadHook, Observed, Hooked, kill_stream};
+use super::arena::Arena;
+use super::background::BackgroundReader;
+use super::bom::{Bom, BomStripped};
+use super::chunked::{ChunkTracker, Tracked};
+use super::chunks::OffsetChunks;
 use super::net_stream::NetStream;
+use super::sniff::sniff;
+use super::throttle::Throttle;
+use super::scan::{Scanner, ScanVerdict};
+#[cfg(feature = "gzip")] use super::gzip::GzipStream;
+#[cfg(feature = "tls")] use super::tls::TlsInfo;
+#[cfg(feature = "bytes")] use bytes::Bytes;
+#[cfg(feature = "cas")] use super::cas::{HashAlgorithm, HashingWriter};
+#[cfg(feature = "crc")] use super::crc::Crc32cWriter;
+#[cfg(feature = "digest")] use super::content_digest;
+use super::audit::Audit;
+use super::deadline::Deadline;
+use super::limit::Limit;
+use super::redact::RedactingTee;
+use super::slow::{SlowBodyLog, SlowlorisGuard};
+use super::snapshot::{self, SnapshotMeta, SnapshotWriter};
+use super::shutdown::ShutdownDeadline;
+use super::error::{DataError, BodyTooLarge};
+use super::info::{DataInfo, Framing};
+use super::line_ending::{LineEnding, NormalizedStream};
+use super::pipe::ChildOutput;
+use super::probe::{Probe, Format};
+use super::stats::{self, AllocStats, TrackedVec};
+use super::type_map::TypeMap;
+use super::connection::{StreamGuard, MAX_OPEN_STREAMS};
 use ext::ReadExt;
+use rocket::Rocket;
+use config::{LengthConflict, PeekErrorPolicy, TlsVersion};
+use request::Request;
 
-use http::hyper;
+use http::{hyper, ContentType, Method};
+use http::uri::Uri;
 use http::hyper::h1::HttpReader;
 use http::hyper::h1::HttpReader::*;
-use http::hyper::net::{HttpStream, NetworkStream};
+use http::hyper::net::{NetworkStream, HttpStream};
 
 pub type HyperBodyReader<'a, 'b> =
     self::HttpReader<&'a mut hyper::buffer::BufReader<&'b mut NetworkStream>>;
@@ -23,6 +59,153 @@ pub type BodyReader = HttpReader<Chain<Cursor<Vec<u8>>, NetStream>>;
 /// The number of bytes to read into the "peek" buffer.
 const PEEK_BYTES: usize = 512;
 
+/// The capacity of the buffer small stream reads are coalesced into when no
+/// configuration applies, as for local requests.
+const DEFAULT_READ_BUFFER: usize = 8192;
+
+/// The framing of a request body, mirroring the variants of Hyper's
+/// `HttpReader` without the reader itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BodyKind {
+    /// The body has a known length, with this many bytes remaining.
+    Sized(u64),
+    /// The body is delimited by the end of the connection.
+    Eof,
+    /// The request has no body.
+    Empty,
+    /// The body uses chunked transfer encoding, with this many bytes
+    /// remaining in the current chunk, if known.
+    Chunked(Option<u64>),
+}
+
+impl BodyKind {
+    #[inline]
+    pub(crate) fn of<R>(reader: &HttpReader<R>) -> BodyKind {
+        match *reader {
+            SizedReader(_, n) => BodyKind::Sized(n),
+            EofReader(_) => BodyKind::Eof,
+            EmptyReader(_) => BodyKind::Empty,
+            ChunkedReader(_, n) => BodyKind::Chunked(n),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn reader<R>(self, inner: R) -> HttpReader<R> {
+        match self {
+            BodyKind::Sized(n) => SizedReader(inner, n),
+            BodyKind::Eof => EofReader(inner),
+            BodyKind::Empty => EmptyReader(inner),
+            BodyKind::Chunked(n) => ChunkedReader(inner, n),
+        }
+    }
+}
+
+/// The state of a chunked request body after the `peek` buffer was filled.
+///
+/// Returned by [`Data::chunked_peek`].
+///
+/// [`Data::chunked_peek`]: /rocket/data/struct.Data.html#method.chunked_peek
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkedPeek {
+    decoded: usize,
+    state: Option<u64>,
+}
+
+impl ChunkedPeek {
+    /// Returns the number of decoded body bytes in the `peek` buffer.
+    #[inline(always)]
+    pub fn decoded_len(&self) -> usize {
+        self.decoded
+    }
+
+    /// Returns `Some(true)` if more body data is known to remain, `Some(false)`
+    /// if the terminating chunk has been read, and `None` if the `peek` buffer
+    /// was filled at a chunk boundary so that it is unknown whether more
+    /// chunks follow.
+    #[inline]
+    pub fn has_more(&self) -> Option<bool> {
+        match self.state {
+            Some(0) => Some(false),
+            Some(_) => Some(true),
+            None => None,
+        }
+    }
+
+    /// Returns the number of bytes remaining in the chunk that was being read
+    /// when the `peek` buffer was filled, if any.
+    #[inline]
+    pub fn current_chunk_remaining(&self) -> Option<u64> {
+        match self.state {
+            Some(0) | None => None,
+            Some(n) => Some(n),
+        }
+    }
+}
+
+/// How [`Data::buffer_fully`] handles bodies that exceed the buffering limit.
+///
+/// [`Data::buffer_fully`]: /rocket/data/struct.Data.html#method.buffer_fully
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Keep the buffered prefix and stream the remainder of the body.
+    Stream,
+    /// Fail with an error.
+    Error,
+}
+
+/// How [`Data::stream_to_all`] handles a writer that fails.
+///
+/// [`Data::stream_to_all`]: /rocket/data/struct.Data.html#method.stream_to_all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkFailure {
+    /// Stop streaming to every writer and return the error.
+    Abort,
+    /// Stop streaming to the failed writer but continue with the others. An
+    /// error is returned only if every writer fails.
+    Continue,
+}
+
+/// A writer that writes everything written to it to all of `writers`.
+struct FanOut<'a, 'w: 'a> {
+    writers: &'a mut [&'w mut Write],
+    failed: Vec<bool>,
+    on_failure: SinkFailure,
+}
+
+impl<'a, 'w> Write for FanOut<'a, 'w> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for (i, writer) in self.writers.iter_mut().enumerate() {
+            if self.failed[i] {
+                continue;
+            }
+
+            if let Err(e) = writer.write_all(buf) {
+                if self.on_failure == SinkFailure::Abort {
+                    return Err(e);
+                }
+
+                warn_!("Writer {} failed; continuing with the others: {}", i, e);
+                self.failed[i] = true;
+                if self.failed.iter().all(|&failed| failed) {
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for (i, writer) in self.writers.iter_mut().enumerate() {
+            if !self.failed[i] {
+                writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Type representing the data in the body of an incoming request.
 ///
 /// This type is the only means by which the body of a request can be retrieved.
@@ -54,6 +237,18 @@ const PEEK_BYTES: usize = 512;
 pub struct Data {
     buffer: Vec<u8>,
     is_complete: bool,
+    is_prebuffered: bool,
+    is_truncated: bool,
+    has_declared_length: bool,
+    method: Option<Method>,
+    uri: Option<Uri<'static>>,
+    audit: Option<usize>,
+    guard: Option<StreamGuard>,
+    hooks: Vec<Observed>,
+    peer: Option<SocketAddr>,
+    read_buffer: usize,
+    extensions: TypeMap,
+    chunks: ChunkTracker,
     stream: BodyReader,
 }
 
@@ -76,74 +271,77 @@ impl Data {
     /// ```
     pub fn open(mut self) -> DataStream {
         let buffer = ::std::mem::replace(&mut self.buffer, vec![]);
-        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
-
-        // FIXME: Insert a `BufReader` in front of the `NetStream` with capacity
-        // 4096. We need the new `Chain` methods to get the inner reader to
-        // actually do this, however.
-        let empty_http_stream = HttpReader::SizedReader(empty_stream, 0);
-        let stream = ::std::mem::replace(&mut self.stream, empty_http_stream);
-        DataStream(Cursor::new(buffer).chain(stream))
+        self.into_stream(buffer)
     }
 
-    // FIXME: This is absolutely terrible (downcasting!), thanks to Hyper.
-    pub(crate) fn from_hyp(mut body: HyperBodyReader) -> Result<Data, &'static str> {
-        // Steal the internal, undecoded data buffer and net stream from Hyper.
-        let (mut hyper_buf, pos, cap) = body.get_mut().take_buf();
-        unsafe { hyper_buf.set_len(cap); }
-        let hyper_net_stream = body.get_ref().get_ref();
+    /// Splits the body into the bytes in the `peek` buffer and a stream of the
+    /// remainder of the body.
+    ///
+    /// Unlike [`open`](#method.open), the returned stream does _not_ include
+    /// the peeked bytes; it begins with the first byte following them. This is
+    /// useful when the peeked bytes have already been handled, perhaps via
+    /// [`peek`](#method.peek), and only the remainder needs to be streamed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let (header, mut rest) = data.split();
+    ///     println!("header: {:?}", header);
+    ///
+    ///     let mut payload = vec![];
+    ///     rest.read_to_end(&mut payload)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn split(mut self) -> (Vec<u8>, DataStream) {
+        let buffer = ::std::mem::replace(&mut self.buffer, vec![]);
+        (buffer, self.into_stream(vec![]))
+    }
 
-        #[cfg(feature = "tls")]
-        #[inline(always)]
-        fn concrete_stream(stream: &&mut NetworkStream) -> Option<NetStream> {
-            stream.downcast_ref::<HttpsStream>()
-                .map(|s| NetStream::Https(s.clone()))
-                .or_else(|| {
-                    stream.downcast_ref::<HttpStream>()
-                        .map(|s| NetStream::Http(s.clone()))
-                })
+    /// Returns a stream of `prefix` followed by the unread remainder of the
+    /// body.
+    fn into_stream(mut self, prefix: Vec<u8>) -> DataStream {
+        let prefix_len = prefix.len();
+        if !self.is_complete {
+            stats::record_peek_insufficient();
         }
 
-        #[cfg(not(feature = "tls"))]
-        #[inline(always)]
-        fn concrete_stream(stream: &&mut NetworkStream) -> Option<NetStream> {
-            stream.downcast_ref::<HttpStream>()
-                .map(|s| NetStream::Http(s.clone()))
+        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
+        let empty_http_stream = HttpReader::SizedReader(empty_stream, 0);
+        let stream = ::std::mem::replace(&mut self.stream, empty_http_stream);
+        let mut data_stream = DataStream::new(Cursor::new(prefix).chain(stream))
+            .with_read_buffer(self.read_buffer)
+            .with_chunks(self.chunks);
+        if let Some(guard) = self.guard.take() {
+            data_stream = data_stream.with_hook(guard);
         }
 
-        // Retrieve the underlying Http(s)Stream from Hyper.
-        let net_stream = match concrete_stream(hyper_net_stream) {
-            Some(net_stream) => net_stream,
-            None => return Err("Stream is not an HTTP(s) stream!")
-        };
-
-        // Set the read timeout to 5 seconds.
-        net_stream.set_read_timeout(Some(Duration::from_secs(5))).expect("timeout set");
-
-        // TODO: Explain this.
-        trace_!("Hyper buffer: [{}..{}] ({} bytes).", pos, cap, cap - pos);
-
-        let mut cursor = Cursor::new(hyper_buf);
-        cursor.set_position(pos as u64);
-        let inner_data = cursor.chain(net_stream);
-
-        // Create an HTTP reader from the stream.
-        let http_stream = match body {
-            SizedReader(_, n) => SizedReader(inner_data, n),
-            EofReader(_) => EofReader(inner_data),
-            EmptyReader(_) => EmptyReader(inner_data),
-            ChunkedReader(_, n) => ChunkedReader(inner_data, n)
-        };
+        for observed in self.hooks.drain(..) {
+            data_stream = data_stream.with_boxed_hook(observed.into_hook(prefix_len));
+        }
 
-        Ok(Data::new(http_stream))
+        match self.audit {
+            Some(limit) => data_stream.with_hook(Audit::new(limit)),
+            None => data_stream
+        }
     }
 
-    /// Retrieve the `peek` buffer.
+    /// Returns the raw data stream, limiting the rate at which it can be read
+    /// to `bytes_per_sec` bytes per second.
     ///
-    /// The peek buffer contains at most 512 bytes of the body of the request.
-    /// The actual size of the returned buffer varies by web request. The
-    /// [`peek_complete`](#method.peek_complete) can be used to determine if
-    /// this buffer contains _all_ of the data in the body of the request.
+    /// The limit is enforced with a token bucket holding up to one second's
+    /// worth of bytes: reads proceed unimpeded while tokens remain, and a read
+    /// that exceeds the available tokens blocks until the deficit is repaid.
+    /// The rate is accurate at the granularity of the reads made on the
+    /// stream; small reads are throttled more smoothly than large ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_sec` is `0`.
     ///
     /// # Example
     ///
@@ -151,41 +349,76 @@ impl Data {
     /// use rocket::Data;
     ///
     /// fn handler(data: Data) {
-    ///     let peek = data.peek();
+    ///     // Read the body at no more than 1MiB/s.
+    ///     let stream = data.open_throttled(1 << 20);
     /// }
     /// ```
-    #[inline(always)]
-    pub fn peek(&self) -> &[u8] {
-        if self.buffer.len() > PEEK_BYTES {
-            &self.buffer[..PEEK_BYTES]
-        } else {
-            &self.buffer
-        }
+    pub fn open_throttled(self, bytes_per_sec: u64) -> DataStream {
+        self.open().with_hook(Throttle::new(bytes_per_sec))
     }
 
-    /// Returns true if the `peek` buffer contains all of the data in the body
-    /// of the request. Returns `false` if it does not or if it is not known if
-    /// it does.
+    /// Returns a stream over the body with every line ending, whether `\r\n`,
+    /// `\r`, or `\n`, rewritten to `target`.
+    ///
+    /// A `\r\n` is always rewritten as a single line ending, even if its `\r`
+    /// and `\n` arrive in different reads.
     ///
     /// # Example
     ///
     /// ```rust
+    /// use std::io::{self, Read};
     /// use rocket::Data;
+    /// use rocket::data::LineEnding;
     ///
-    /// fn handler(data: Data) {
-    ///     if data.peek_complete() {
-    ///         println!("All of the data: {:?}", data.peek());
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let mut config = String::new();
+    ///     data.open_normalized(LineEnding::Lf).read_to_string(&mut config)?;
+    ///     Ok(config)
+    /// }
+    /// ```
+    #[inline]
+    pub fn open_normalized(self, target: LineEnding) -> NormalizedStream {
+        NormalizedStream::new(self.open(), target)
+    }
+
+    /// Returns a stream over the body without its leading byte order mark, if
+    /// any. A body that begins with a UTF-16 byte order mark is transcoded to
+    /// UTF-8 as it's read. The byte order mark is detected in the `peek`
+    /// buffer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let mut stream = data.open_without_bom();
+    ///     if let Some(bom) = stream.bom() {
+    ///         println!("The upload began with a {:?} byte order mark.", bom);
     ///     }
+    ///
+    ///     let mut text = String::new();
+    ///     stream.read_to_string(&mut text)?;
+    ///     Ok(text)
     /// }
     /// ```
-    #[inline(always)]
-    pub fn peek_complete(&self) -> bool {
-        self.is_complete
+    #[inline]
+    pub fn open_without_bom(self) -> BomStripped {
+        let bom = Bom::detect(self.peek());
+        BomStripped::new(self.open(), bom)
     }
 
-    /// A helper method to write the body of the request to any `Write` type.
+    /// Reads the body, which must be at most `limit` bytes long, into a
+    /// `String` without its leading byte order mark, as read by
+    /// [`open_without_bom`](#method.open_without_bom). A body that consists of
+    /// only a byte order mark yields an empty string.
+    ///
+    /// # Errors
     ///
-    /// This method is identical to `io::copy(&mut data.open(), writer)`.
+    /// Fails with an error of kind `InvalidData` if the body isn't valid UTF-8
+    /// or UTF-16 or if it exceeds `limit`, in which case the error wraps a
+    /// [`BodyTooLarge`].
     ///
     /// # Example
     ///
@@ -193,84 +426,2222 @@ impl Data {
     /// use std::io;
     /// use rocket::Data;
     ///
-    /// fn handler(mut data: Data) -> io::Result<String> {
-    ///     // write all of the data to stdout
-    ///     data.stream_to(&mut io::stdout())
-    ///         .map(|n| format!("Wrote {} bytes.", n))
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     data.string_without_bom(64 * 1024)
     /// }
     /// ```
-    #[inline(always)]
-    pub fn stream_to<W: Write>(self, writer: &mut W) -> io::Result<u64> {
-        io::copy(&mut self.open(), writer)
+    ///
+    /// [`BodyTooLarge`]: /rocket/data/struct.BodyTooLarge.html
+    pub fn string_without_bom(self, limit: usize) -> io::Result<String> {
+        let data = self.limit(limit).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut string = String::new();
+        data.open_without_bom().read_to_string(&mut string)?;
+        Ok(string)
     }
 
-    /// A helper method to write the body of the request to a file at the path
-    /// determined by `path`.
+    /// Returns the result of applying `f` to the raw data stream.
     ///
-    /// This method is identical to
-    /// `io::copy(&mut self.open(), &mut File::create(path)?)`.
+    /// This is a convenience for wrapping the stream in an adapter, such as a
+    /// decoder, that itself implements `Read`.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use std::io;
+    /// use std::io::{self, Read, BufReader};
     /// use rocket::Data;
     ///
-    /// fn handler(mut data: Data) -> io::Result<String> {
-    ///     data.stream_to_file("/static/file")
-    ///         .map(|n| format!("Wrote {} bytes to /static/file", n))
+    /// fn handler(data: Data) -> io::Result<Vec<u8>> {
+    ///     let mut stream = data.transform(|stream| BufReader::new(stream));
+    ///
+    ///     let mut contents = vec![];
+    ///     stream.read_to_end(&mut contents)?;
+    ///     Ok(contents)
     /// }
     /// ```
     #[inline(always)]
-    pub fn stream_to_file<P: AsRef<Path>>(self, path: P) -> io::Result<u64> {
-        io::copy(&mut self.open(), &mut File::create(path)?)
+    pub fn transform<R, F: FnOnce(DataStream) -> R>(self, f: F) -> R {
+        f(self.open())
     }
 
-    // Creates a new data object with an internal buffer `buf`, where the cursor
-    // in the buffer is at `pos` and the buffer has `cap` valid bytes. Thus, the
-    // bytes `vec[pos..cap]` are buffered and unread. The remainder of the data
-    // bytes can be read from `stream`.
-    #[inline(always)]
-    pub(crate) fn new(mut stream: BodyReader) -> Data {
-        trace_!("Date::new({:?})", stream);
-        let mut peek_buf = vec![0; PEEK_BYTES];
+    /// Returns the raw data stream, running `scanner` over each chunk of the
+    /// body as it is read.
+    ///
+    /// The scanner is called with every non-empty chunk read from the body, in
+    /// order, before the chunk is returned to the reader. Once the scanner
+    /// returns `ScanVerdict::Reject`, the read of the offending chunk and every
+    /// subsequent read fail with an error of kind `InvalidData`. This allows a
+    /// body to be rejected by a content policy, such as a malware or data-loss
+    /// scanner, before it has been accepted in its entirety.
+    ///
+    /// Chunk boundaries depend on how the stream is read; a scanner looking for
+    /// a pattern must account for matches that span chunks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::data::ScanVerdict;
+    ///
+    /// fn handler(data: Data) -> io::Result<u64> {
+    ///     let mut stream = data.open_scanned(|chunk| {
+    ///         if chunk.windows(4).any(|w| w == b"EVIL") {
+    ///             ScanVerdict::Reject
+    ///         } else {
+    ///             ScanVerdict::Continue
+    ///         }
+    ///     });
+    ///
+    ///     io::copy(&mut stream, &mut io::sink())
+    /// }
+    /// ```
+    #[inline]
+    pub fn open_scanned<F>(self, scanner: F) -> DataStream
+        where F: FnMut(&[u8]) -> ScanVerdict + Send + 'static
+    {
+        self.open().with_hook(Scanner::new(scanner))
+    }
 
-        // Fill the buffer with as many bytes as possible. If we read less than
-        // that buffer's length, we know we reached the EOF. Otherwise, it's
-        // unclear, so we just say we didn't reach EOF.
-        let eof = match stream.read_max(&mut peek_buf[..]) {
-            Ok(n) => {
-                trace_!("Filled peek buf with {} bytes.", n);
-                // TODO: Explain this.
-                unsafe { peek_buf.set_len(n); }
-                n < PEEK_BYTES
-            }
-            Err(e) => {
-                error_!("Failed to read into peek buffer: {:?}.", e);
-                unsafe { peek_buf.set_len(0); }
-                false
-            },
-        };
+    /// Returns a stream that decompresses the gzip-encoded body.
+    ///
+    /// When the end of the compressed data is reached, the CRC32 and length in
+    /// the gzip trailer are checked against the decompressed data. If they
+    /// don't match, the read that reaches the end returns an error of kind
+    /// `InvalidData` wrapping a
+    /// [`GzipChecksumError`](/rocket/data/struct.GzipChecksumError.html), so
+    /// a corrupted body is never mistaken for a complete one.
+    ///
+    /// This method is only available when the `gzip` feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the body doesn't begin with a
+    /// valid gzip header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// # #[cfg(feature = "gzip")]
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let mut body = String::new();
+    ///     data.open_gzip()?.read_to_string(&mut body)?;
+    ///     Ok(body)
+    /// }
+    /// ```
+    #[cfg(feature = "gzip")]
+    #[inline]
+    pub fn open_gzip(self) -> io::Result<GzipStream> {
+        GzipStream::new(self.open())
+    }
 
-        trace_!("Peek bytes: {}/{} bytes.", peek_buf.len(), PEEK_BYTES);
-        Data {
-            buffer: peek_buf,
-            stream: stream,
-            is_complete: eof,
+    /// Reads the entire body into a [`Bytes`] buffer.
+    ///
+    /// When the body fits in the `peek` buffer and was read in full, the
+    /// buffer is handed off without copying it. Otherwise, the body is read to
+    /// its end first. This method is only available when the `bytes` feature
+    /// is enabled.
+    ///
+    /// [`Bytes`]: https://docs.rs/bytes/0.4/bytes/struct.Bytes.html
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let body = data.bytes_buf()?;
+    ///     println!("read {} bytes", body.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "bytes")]
+    pub fn bytes_buf(mut self) -> io::Result<Bytes> {
+        // Hooks must still see the bytes, so only skip the stream without any.
+        if self.is_complete && self.hooks.is_empty() && self.audit.is_none() {
+            let buffer = ::std::mem::replace(&mut self.buffer, vec![]);
+            return Ok(Bytes::from(buffer));
         }
+
+        let mut buffer = Vec::with_capacity(self.buffer.len());
+        self.open().read_to_end(&mut buffer)?;
+        Ok(Bytes::from(buffer))
     }
 
-    /// This creates a `data` object from a local data source `data`.
-    #[inline]
-    pub(crate) fn local(data: Vec<u8>) -> Data {
-        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
+    /// Reads the entire body into memory if it is at most `max` bytes long.
+    ///
+    /// A body whose declared length exceeds `max` is rejected without being
+    /// read. Otherwise, at most `max + 1` bytes are read, so a body of any
+    /// size can safely be passed to this method. Prefer this method over
+    /// reading the stream to its end whenever the body is held in memory.
+    ///
+    /// # Errors
+    ///
+    /// I/O errors are returned in the outer `Result`. If the body exceeds
+    /// `max`, the inner `Result` is a [`BodyTooLarge`], which handlers
+    /// typically turn into a `413 Payload Too Large` response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::http::Status;
+    ///
+    /// fn handler(data: Data) -> io::Result<Result<String, Status>> {
+    ///     match data.bytes_limited(64 * 1024)? {
+    ///         Ok(body) => Ok(Ok(format!("read {} bytes", body.len()))),
+    ///         Err(_) => Ok(Err(Status::PayloadTooLarge)),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`BodyTooLarge`]: /rocket/data/struct.BodyTooLarge.html
+    pub fn bytes_limited(self, max: usize) -> io::Result<Result<Vec<u8>, BodyTooLarge>> {
+        let declared = self.len();
+        let too_large = |read| BodyTooLarge { limit: max, read: read, declared: declared };
+        if declared.map_or(false, |len| len > max as u64) {
+            return Ok(Err(too_large(0)));
+        }
 
-        Data {
-            buffer: data,
-            stream: HttpReader::SizedReader(empty_stream, 0),
-            is_complete: true,
+        let capacity = declared.map_or(self.buffer.len(), |len| len as usize);
+        let mut body = Vec::with_capacity(capacity);
+        let n = self.open().take((max as u64).saturating_add(1)).read_to_end(&mut body)?;
+        if n > max {
+            return Ok(Err(too_large(n as u64)));
+        }
+
+        Ok(Ok(body))
+    }
+
+    /// Limits the body to `max` bytes for every consumer of this `Data`,
+    /// including data guards that read it in full. If the body's declared
+    /// length exceeds `max`, returns a [`BodyTooLarge`] error immediately.
+    /// Otherwise, the read that takes the body past `max` bytes fails with an
+    /// `io::Error` of kind `InvalidData` wrapping a `BodyTooLarge`.
+    ///
+    /// This is the method applied by the `limit` route attribute parameter,
+    /// which responds with `413 Payload Too Large` when it fails:
+    ///
+    /// ```rust,ignore
+    /// #[post("/upload", data = "<upload>", limit = "100MiB")]
+    /// fn upload(upload: Data) -> io::Result<String> { .. }
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    /// use rocket::http::Status;
+    ///
+    /// fn handler(data: Data) -> io::Result<Result<String, Status>> {
+    ///     let data = match data.limit(64 * 1024) {
+    ///         Ok(data) => data,
+    ///         Err(_) => return Ok(Err(Status::PayloadTooLarge)),
+    ///     };
+    ///
+    ///     let mut body = String::new();
+    ///     data.open().read_to_string(&mut body)?;
+    ///     Ok(Ok(body))
+    /// }
+    /// ```
+    ///
+    /// [`BodyTooLarge`]: /rocket/data/struct.BodyTooLarge.html
+    pub fn limit(self, max: usize) -> Result<Data, BodyTooLarge> {
+        let declared = self.len();
+        if declared.map_or(false, |len| len > max as u64) {
+            return Err(BodyTooLarge { limit: max, read: 0, declared: declared });
         }
+
+        Ok(self.with_hook(Limit::new(max, declared)))
+    }
+
+    /// Tees the body to `sink` as it's read, passing each line through `redact`
+    /// first, as for an audit log that mustn't record secrets. The body read
+    /// from the returned `Data` is unchanged; only the copy written to `sink`
+    /// is redacted.
+    ///
+    /// `redact` is called with each line of the body, including its trailing
+    /// `\n`, and returns the bytes to log in its place. A line longer than
+    /// 8KiB is passed in 8KiB pieces, as is a final line without a `\n` once
+    /// the body's stream is dropped. Only bytes the handler reads are logged.
+    /// If writing to `sink` fails, the error is logged and teeing stops;
+    /// reading the body never fails because of `sink`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn mask_password(line: &[u8]) -> Vec<u8> {
+    ///     match line.starts_with(b"password=") {
+    ///         true => b"password=<redacted>\n".to_vec(),
+    ///         false => line.to_vec(),
+    ///     }
+    /// }
+    ///
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let data = data.tee_redacted(io::stderr(), mask_password);
+    ///     let mut body = String::new();
+    ///     io::Read::read_to_string(&mut data.open(), &mut body)?;
+    ///     Ok(body)
+    /// }
+    /// ```
+    pub fn tee_redacted<W, F>(self, sink: W, redact: F) -> Data
+        where W: Write + Send + 'static, F: FnMut(&[u8]) -> Vec<u8> + Send + 'static
+    {
+        self.with_hook(RedactingTee::new(sink, redact))
     }
+
+    /// Like [`bytes_limited`](#method.bytes_limited), but also records how the
+    /// buffer holding the body was allocated in `stats`: its final capacity
+    /// and how many times it was reallocated as it grew. `stats` is reset
+    /// before the body is read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::data::AllocStats;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let mut stats = AllocStats::default();
+    ///     if let Ok(body) = data.bytes_limited_with_stats(1 << 20, &mut stats)? {
+    ///         println!("{} bytes in {} reserved after {} reallocations",
+    ///                  body.len(), stats.capacity, stats.reallocations);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn bytes_limited_with_stats(
+        self,
+        max: usize,
+        stats: &mut AllocStats
+    ) -> io::Result<Result<Vec<u8>, BodyTooLarge>> {
+        *stats = AllocStats::default();
+        let declared = self.len();
+        let too_large = |read| BodyTooLarge { limit: max, read: read, declared: declared };
+        if declared.map_or(false, |len| len > max as u64) {
+            return Ok(Err(too_large(0)));
+        }
+
+        let capacity = declared.map_or(self.buffer.len(), |len| len as usize);
+        let mut body = Vec::with_capacity(capacity);
+        stats.capacity = body.capacity();
+        let n = {
+            let mut tracked = TrackedVec { vec: &mut body, stats: stats };
+            self.open().take((max as u64).saturating_add(1)).copy_to(&mut tracked)?
+        };
+
+        if n > max as u64 {
+            return Ok(Err(too_large(n)));
+        }
+
+        Ok(Ok(body))
+    }
+
+    /// Reads the entire body, returning only its last `n` bytes, or the entire
+    /// body if it is shorter than `n` bytes.
+    ///
+    /// At most `n` bytes of the body are held in memory at once, no matter its
+    /// length, so this is suitable for retaining the trailer of a large upload
+    /// for logging.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let tail = data.read_tail(64)?;
+    ///     println!("upload ended with {:?}", String::from_utf8_lossy(&tail));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_tail(self, n: usize) -> io::Result<Vec<u8>> {
+        // `ring` is filled to `len` and, once full, wraps at `pos`.
+        let mut ring = vec![0; n];
+        let (mut pos, mut len) = (0, 0);
+        let mut stream = self.open();
+        let mut buf = [0; 8192];
+        loop {
+            let read = match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            // Only the last `n` bytes of a read can survive it.
+            let chunk = &buf[read.saturating_sub(n)..read];
+            for &byte in chunk {
+                ring[pos] = byte;
+                pos = (pos + 1) % n;
+            }
+
+            len = ::std::cmp::min(n, len + chunk.len());
+        }
+
+        if len < n {
+            ring.truncate(len);
+            return Ok(ring);
+        }
+
+        let mut tail = ring.split_off(pos);
+        tail.extend_from_slice(&ring);
+        Ok(tail)
+    }
+
+    /// Reads the entire body, up to `limit` bytes, and verifies it against
+    /// `content_digest`, the value of the request's `Content-Digest` header
+    /// (RFC 9530). Returns the body if it matches.
+    ///
+    /// The header is parsed as a structured field dictionary. Of the
+    /// algorithms it lists, the strongest supported one is verified;
+    /// `sha-512` and `sha-256` are supported, and other algorithms are
+    /// ignored. This method is only available when the `digest` feature is
+    /// enabled.
+    ///
+    /// # Errors
+    ///
+    /// If the header is malformed or lists no supported algorithm, returns an
+    /// error of kind `InvalidInput`. If the body exceeds `limit`, returns an
+    /// error of kind `InvalidData`. If the body doesn't match the digest,
+    /// returns an error of kind `InvalidData` wrapping a
+    /// [`ContentDigestMismatch`](/rocket/data/struct.ContentDigestMismatch.html).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// # #[cfg(feature = "digest")]
+    /// fn handler(data: Data, content_digest: &str) -> io::Result<Vec<u8>> {
+    ///     data.verify_content_digest(content_digest, 1 << 20)
+    /// }
+    /// ```
+    #[cfg(feature = "digest")]
+    pub fn verify_content_digest(self, content_digest: &str, limit: u64) -> io::Result<Vec<u8>> {
+        let mut body = vec![];
+        let n = self.open().take(limit.saturating_add(1)).read_to_end(&mut body)?;
+        if n as u64 > limit {
+            let msg = "body exceeds the digest verification limit";
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+
+        content_digest::verify(content_digest, &body)?;
+        Ok(body)
+    }
+
+    // FIXME: This is absolutely terrible (downcasting!), thanks to Hyper.
+    pub(crate) fn from_hyp(
+        rocket: &Rocket,
+        req: &Request,
+        mut body: HyperBodyReader,
+        arrived: Instant
+    ) -> Result<Data, DataError> {
+        // Steal the internal, undecoded data buffer and net stream from Hyper.
+        let (mut hyper_buf, pos, cap) = body.get_mut().take_buf();
+        unsafe { hyper_buf.set_len(cap); }
+        if pos > cap {
+            return Err(DataError::Underflow { pos: pos, len: cap });
+        }
+
+        let hyper_net_stream = body.get_ref().get_ref();
+
+        // Retrieve the underlying Http(s)Stream from Hyper. Each recognizer, in
+        // the order registered, is given a chance to downcast the stream.
+        let net_stream = rocket.stream_recognizers.iter()
+            .filter_map(|recognize| recognize(&**hyper_net_stream))
+            .next();
+
+        let mut net_stream = match net_stream {
+            Some(net_stream) => net_stream,
+            None => return Err(DataError::NotHttpStream)
+        };
+
+        // Reject bodies sent over TLS versions older than the configured one.
+        if let Some(min) = rocket.config.min_tls_version {
+            check_tls_version(&net_stream, min)?;
+        }
+
+        if rocket.config.count_read_syscalls {
+            net_stream = net_stream.counted();
+        }
+
+        let peer = net_stream.peer_addr().ok();
+
+        // A body with both a length and chunked framing is ambiguous. Hyper
+        // reads it by its length, so it's either rejected or read as chunked.
+        let mut kind = BodyKind::of(&body);
+        if has_conflicting_length(req) {
+            match rocket.config.length_conflict {
+                LengthConflict::Reject => return Err(DataError::ConflictingLength { peer: peer }),
+                LengthConflict::Chunked => kind = BodyKind::Chunked(None),
+            }
+        }
+
+        // Mark the body as open on its connection. A body that's still open
+        // from a previous request would otherwise compete for the same bytes.
+        let guard = match peer {
+            Some(peer) => match rocket.open_streams.acquire(peer, MAX_OPEN_STREAMS) {
+                Some(guard) => Some(guard),
+                None => return Err(DataError::TooManyOpenStreams(peer))
+            },
+            None => None
+        };
+
+        // Disable Nagle's algorithm if configured to. This is best-effort: not
+        // every stream exposes its socket.
+        if rocket.config.tcp_nodelay {
+            if let Err(e) = net_stream.set_nodelay(true) {
+                debug_!("Failed to set TCP_NODELAY: {}", e);
+            }
+        }
+
+        // Set the read timeout to 5 seconds. Without a timeout, a stalled
+        // client could hold this worker forever, so a failure to set it fails
+        // the request rather than continuing (or panicking).
+        net_stream.set_read_timeout(Some(Duration::from_secs(5)))
+            .map_err(DataError::TimeoutSetFailed)?;
+
+        // The hooks run over every read of the body, starting with the reads
+        // that fill the peek buffer. The deadline is anchored to the arrival of
+        // the request's headers.
+        let mut hooks: Vec<Box<ReadHook>> = vec![];
+        if rocket.config.max_body_duration > 0 {
+            let limit = Duration::from_secs(rocket.config.max_body_duration);
+            hooks.push(Box::new(Deadline::new(arrived + limit)));
+        }
+
+        if rocket.config.slow_body_threshold > 0 {
+            let threshold = Duration::from_millis(rocket.config.slow_body_threshold);
+            hooks.push(Box::new(SlowBodyLog::new(threshold, peer)));
+        }
+
+        // Reject bodies that are both old and slow, if configured to.
+        if rocket.config.slowloris_min_rate > 0 {
+            let grace = Duration::from_secs(rocket.config.slowloris_grace);
+            let min_rate = rocket.config.slowloris_min_rate;
+            hooks.push(Box::new(SlowlorisGuard::new(arrived, grace, min_rate, peer)));
+        }
+
+        // Charge the body against its connection's budget, if there is one.
+        let budget = rocket.config.connection_body_budget;
+        if let (Some(peer), true) = (peer, budget > 0) {
+            hooks.push(Box::new(rocket.body_budgets.charge(peer, budget)));
+        }
+
+        let grace = Duration::from_secs(rocket.config.shutdown_grace);
+        hooks.push(Box::new(ShutdownDeadline::new(rocket.shutdown.clone(), grace)));
+
+        // Fill the peek buffer unless the body's content type is configured
+        // to skip it.
+        let reader = body_reader(hyper_buf, pos, net_stream, kind);
+        let skip_peek = req.content_type()
+            .map_or(false, |ct| rocket.config.skip_peek.iter().any(|skip| skip == ct));
+
+        let mut data = match skip_peek {
+            true => hooks.into_iter().fold(Data::unpeeked(reader), Data::with_boxed_hook),
+            false => Data::peeked(reader, rocket.config.peek_error, hooks)?,
+        };
+        data = data.with_request_line(req.method(), req.uri());
+        data.guard = guard;
+        data.peer = peer;
+        data.read_buffer = rocket.config.read_buffer as usize;
+        if rocket.config.body_audit > 0 {
+            data.audit = Some(rocket.config.body_audit as usize);
+        }
+
+        Ok(data)
+    }
+
+    /// Creates a `Data` for a request received on `stream` outside of Rocket's
+    /// own server, as by an application that runs its own accept loop and
+    /// parses request headers itself.
+    ///
+    /// `prefix` holds the bytes of the body that were read from `stream`
+    /// along with the headers, as they were received: for a chunked body,
+    /// this includes the chunks' framing. They are read before any bytes from
+    /// `stream`.
+    /// `framing` determines where the body ends. A `Sized` body ends after
+    /// `content_length` bytes, counting those in `prefix`; for every other
+    /// framing, `content_length` must be `None`. The `peek` buffer is filled
+    /// before this method returns, which may read from `stream`.
+    ///
+    /// Unlike bodies received by Rocket's server, the body is read without a
+    /// read timeout or any of the configured limits: those are up to the
+    /// caller to set on `stream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput` if `content_length` is `None`
+    /// for a `Sized` body or `Some` for any other.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use std::net::TcpStream;
+    /// use rocket::Data;
+    /// use rocket::data::Framing;
+    ///
+    /// fn body(stream: TcpStream, prefix: Vec<u8>, len: u64) -> io::Result<Data> {
+    ///     Data::from_tcp_stream(stream, prefix, Framing::Sized, Some(len))
+    /// }
+    /// ```
+    pub fn from_tcp_stream(
+        stream: TcpStream,
+        prefix: Vec<u8>,
+        framing: Framing,
+        content_length: Option<u64>
+    ) -> io::Result<Data> {
+        let kind = match (framing, content_length) {
+            (Framing::Sized, Some(n)) => BodyKind::Sized(n),
+            (Framing::Eof, None) => BodyKind::Eof,
+            (Framing::Empty, None) => BodyKind::Empty,
+            (Framing::Chunked, None) => BodyKind::Chunked(None),
+            _ => {
+                let msg = "a content length must be given for, and only for, sized bodies";
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+            }
+        };
+
+        let peer = stream.peer_addr().ok();
+        let mut data = Data::from_parts(prefix, 0, NetStream::Http(HttpStream(stream)), kind);
+        data.peer = peer;
+        Ok(data)
+    }
+
+    // Creates a new data object from the constituent parts of a request body:
+    // `buf` contains bytes read from the network ahead of time, of which
+    // `buf[pos..]` have yet to be consumed and belong to the body. The
+    // remainder of the body is read from `net_stream` and framed as `kind`.
+    pub(crate) fn from_parts(
+        buf: Vec<u8>,
+        pos: usize,
+        net_stream: NetStream,
+        kind: BodyKind
+    ) -> Data {
+        Data::new(body_reader(buf, pos, net_stream, kind))
+    }
+
+    /// Retrieve the `peek` buffer.
+    ///
+    /// The peek buffer contains at most 512 bytes of the body of the request.
+    /// The actual size of the returned buffer varies by web request. The
+    /// [`peek_complete`](#method.peek_complete) can be used to determine if
+    /// this buffer contains _all_ of the data in the body of the request.
+    ///
+    /// The buffer is filled when the `Data` is created. Neither this method
+    /// nor any other method taking `&self` reads from the underlying stream,
+    /// so probing the start of a body with `peek` never consumes any of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     let peek = data.peek();
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn peek(&self) -> &[u8] {
+        if self.buffer.len() > PEEK_BYTES {
+            &self.buffer[..PEEK_BYTES]
+        } else {
+            &self.buffer
+        }
+    }
+
+    /// Calls `f` with the buffered prefix of the body, whose start is returned
+    /// by [`peek`](#method.peek), so that it can be rewritten in place. The
+    /// bytes `f` leaves in the buffer take the place of the original prefix:
+    /// streams later returned by [`open`](#method.open) and the other stream
+    /// accessors yield them followed by the untouched remainder of the body,
+    /// and [`len`](#method.len) accounts for any change in length.
+    ///
+    /// This lets a [`DataFairing`] strip or replace a header block at the start
+    /// of the body without reading the rest of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Request, Data};
+    /// use rocket::http::Status;
+    ///
+    /// fn strip_signature(_: &Request, mut data: Data) -> Result<Data, Status> {
+    ///     if !data.peek().starts_with(b"SIG ") {
+    ///         return Err(Status::BadRequest);
+    ///     }
+    ///
+    ///     data.edit_peek(|prefix| {
+    ///         let end = prefix.iter().position(|&b| b == b'\n').map_or(prefix.len(), |i| i + 1);
+    ///         prefix.drain(..end);
+    ///     });
+    ///
+    ///     Ok(data)
+    /// }
+    /// ```
+    ///
+    /// [`DataFairing`]: /rocket/fairing/trait.DataFairing.html
+    #[inline]
+    pub fn edit_peek<F: FnOnce(&mut Vec<u8>)>(&mut self, f: F) {
+        f(&mut self.buffer)
+    }
+
+    /// Runs `probes` against the `peek` buffer and returns the format of the
+    /// first probe that matches, if any. The stream is never read, so a probe
+    /// whose signature extends past the `peek` buffer doesn't match.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    /// use rocket::data::{Probe, Format};
+    ///
+    /// fn handler(data: Data) {
+    ///     match data.probe(Probe::DEFAULTS) {
+    ///         Some(Format(name)) => println!("Looks like a {} file.", name),
+    ///         None => println!("Unknown format."),
+    ///     }
+    /// }
+    /// ```
+    pub fn probe(&self, probes: &[Probe]) -> Option<Format> {
+        probes.iter().find(|probe| probe.matches(self.peek())).map(|probe| probe.format)
+    }
+
+    /// Returns the typed values attached to this body, typically by request
+    /// fairings for the handler. See [`TypeMap`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// struct Sanitized;
+    ///
+    /// fn handler(data: Data) {
+    ///     if data.extensions().get::<Sanitized>().is_none() {
+    ///         println!("body was not sanitized");
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`TypeMap`]: /rocket/data/struct.TypeMap.html
+    #[inline(always)]
+    pub fn extensions(&self) -> &TypeMap {
+        &self.extensions
+    }
+
+    /// Returns true if the `peek` buffer contains all of the data in the body
+    /// of the request. Returns `false` if it does not or if it is not known if
+    /// it does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if data.peek_complete() {
+    ///         println!("All of the data: {:?}", data.peek());
+    ///     }
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn peek_complete(&self) -> bool {
+        self.is_complete
+    }
+
+    /// Returns `true` if the entire body is held in memory, so that reading it
+    /// won't touch the network and it can be read again from a copy. Returns
+    /// `false` if any of the body remains to be streamed or if it isn't known
+    /// whether any does.
+    ///
+    /// This is the case when the `peek` buffer holds the entire body, as for
+    /// small bodies and local requests, and after a successful
+    /// [`buffer_fully`](#method.buffer_fully) of the entire body. Unlike
+    /// [`peek_complete`](#method.peek_complete), which speaks only to the
+    /// `peek` buffer, this method is the one to consult when choosing between
+    /// a parser that needs the whole body and one that streams it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if data.is_buffered() {
+    ///         /* parse the body in memory */
+    ///     } else {
+    ///         /* parse the body as it streams in */
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_buffered(&self) -> bool {
+        let nothing_pending = match self.stream {
+            SizedReader(_, n) => n == 0,
+            EmptyReader(_) | EofReader(_) | ChunkedReader(..) => true,
+        };
+
+        self.is_complete && nothing_pending
+    }
+
+    /// Returns a fresh stream over a copy of the body if the entire body is
+    /// held in memory, as reported by [`is_buffered`](#method.is_buffered),
+    /// and `None` otherwise. Unlike [`open`](#method.open), this doesn't
+    /// consume the `Data`, so a guard can attempt to parse a small body one
+    /// way and, if that fails, try another before opening it for good.
+    ///
+    /// The returned stream reads only from memory. It isn't subject to the
+    /// hooks, such as throttling or limits, applied to streams from `open`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Read;
+    /// use rocket::Data;
+    ///
+    /// fn parse(data: Data) -> Option<u64> {
+    ///     let mut text = String::new();
+    ///     data.reread()?.read_to_string(&mut text).ok()?;
+    ///     if let Ok(n) = text.trim().parse() {
+    ///         return Some(n);
+    ///     }
+    ///
+    ///     // Fall back to a hexadecimal number, reading from a fresh stream.
+    ///     let mut hex = String::new();
+    ///     data.open().read_to_string(&mut hex).ok()?;
+    ///     u64::from_str_radix(hex.trim().trim_left_matches("0x"), 16).ok()
+    /// }
+    /// ```
+    pub fn reread(&self) -> Option<DataStream> {
+        if !self.is_buffered() {
+            return None;
+        }
+
+        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
+        let empty_http_stream = HttpReader::SizedReader(empty_stream, 0);
+        Some(DataStream::new(Cursor::new(self.buffer.clone()).chain(empty_http_stream)))
+    }
+
+    /// Returns the length of the body in bytes, if it is known.
+    ///
+    /// The length is known when the request declares it via `Content-Length`,
+    /// when the request carries no body at all (in which case the length is
+    /// `Some(0)`), and for locally dispatched requests. For bodies using
+    /// `Transfer-Encoding: chunked` or delimited by the end of the connection,
+    /// the length is not known, and `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if let Some(len) = data.len() {
+    ///         println!("The body is {} bytes long.", len);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> Option<u64> {
+        match self.stream {
+            EmptyReader(_) => Some(self.buffer.len() as u64),
+            SizedReader(_, n) => Some(self.buffer.len() as u64 + n),
+            EofReader(_) | ChunkedReader(..) if self.is_complete => {
+                Some(self.buffer.len() as u64)
+            }
+            EofReader(_) | ChunkedReader(..) => None,
+        }
+    }
+
+    /// Returns the method of the request this body belongs to, as received,
+    /// before any `_method` form field rewrites it. Returns `None` if the body
+    /// wasn't received with a request, as for `Data::from_bytes`.
+    ///
+    /// This lets data guards and helpers that only have the `Data`, such as
+    /// method-aware limits, see the request they're reading for.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    /// use rocket::http::Method;
+    ///
+    /// fn handler(data: Data) {
+    ///     if data.method() == Some(Method::Get) && data.len() != Some(0) {
+    ///         println!("A GET request with a body!");
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn method(&self) -> Option<Method> {
+        self.method
+    }
+
+    /// Returns the URI of the request this body belongs to, including its
+    /// query, if any. Returns `None` if the body wasn't received with a
+    /// request, as for `Data::from_bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if let Some(uri) = data.uri() {
+    ///         println!("Reading the body sent to {}.", uri);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn uri(&self) -> Option<&Uri> {
+        self.uri.as_ref()
+    }
+
+    /// Returns the address of the client that sent this body, if it is known.
+    /// When the request came through a proxy listed in the `trusted_proxies`
+    /// configuration parameter, this is the client address the proxy reported
+    /// rather than the address of the proxy itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if let Some(peer) = data.peer_addr() {
+    ///         println!("Reading a body sent by {}.", peer);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn peer_addr(&self) -> Option<SocketAddr> {
+        self.peer
+    }
+
+    /// Returns the protocol version and cipher suite negotiated for the TLS
+    /// connection this body arrived over, or `None` if it didn't arrive over
+    /// TLS.
+    ///
+    /// This method is only available when the `tls` feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if let Some(tls) = data.tls_info() {
+    ///         println!("Body sent over {:?} with {:?}.", tls.version, tls.cipher_suite);
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "tls")]
+    #[inline]
+    pub fn tls_info(&self) -> Option<TlsInfo> {
+        self.stream.get_ref().get_ref().1.tls_info()
+    }
+
+    /// Returns `true` if the request declared the length of its body with a
+    /// `Content-Length` header, even if the declared length is `0`.
+    ///
+    /// [`len`](#method.len) returns `Some(0)` both for a request with
+    /// `Content-Length: 0` and for one without a body at all; this method
+    /// tells them apart for protocols that require an explicitly empty body.
+    /// A body whose `Content-Length` was ignored because it was also chunked
+    /// has no declared length. A locally dispatched request declares its
+    /// length if it has a non-empty body or a `Content-Length` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if data.len() == Some(0) && !data.has_declared_length() {
+    ///         println!("The request has no body at all.");
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn has_declared_length(&self) -> bool {
+        self.has_declared_length
+    }
+
+    /// If the body uses `Transfer-Encoding: chunked`, returns the state of the
+    /// chunked body after the `peek` buffer was filled. Returns `None` for all
+    /// other bodies.
+    ///
+    /// The length of a chunked body isn't known ahead of time, so
+    /// [`len`](#method.len) returns `None` for chunked bodies that aren't
+    /// entirely contained in the `peek` buffer. This method instead reports
+    /// how many decoded bytes were peeked and whether more chunks remain.
+    ///
+    /// Chunk extensions (`<size>;name=value`) are accepted and skipped while
+    /// decoding; they are not exposed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if let Some(chunked) = data.chunked_peek() {
+    ///         println!("peeked {} bytes", chunked.decoded_len());
+    ///         if chunked.has_more() == Some(false) {
+    ///             println!("and that's the whole body!");
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn chunked_peek(&self) -> Option<ChunkedPeek> {
+        match self.stream {
+            ChunkedReader(_, state) => Some(ChunkedPeek {
+                decoded: self.peek().len(),
+                state: state,
+            }),
+            _ => None
+        }
+    }
+
+    /// Returns `false` if the client is known to have disconnected and `true`
+    /// otherwise.
+    ///
+    /// The check peeks at the connection without blocking or consuming any
+    /// bytes. A client that has closed only its writing half of the connection
+    /// is indistinguishable from one that has disconnected, so this should
+    /// only be used to abandon work whose response would be discarded anyway.
+    /// Connections that can't be inspected, such as those of local requests
+    /// and TLS connections, are always reported as connected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     for step in 0..100 {
+    ///         if !data.is_peer_connected() {
+    ///             println!("client went away after {} steps", step);
+    ///             return;
+    ///         }
+    ///
+    ///         /* expensive work */
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn is_peer_connected(&self) -> bool {
+        self.stream.get_ref().get_ref().1.is_peer_connected()
+    }
+
+    /// Returns the number of reads made from the network connection for this
+    /// body so far, including those made to fill the `peek` buffer, if reads
+    /// are being counted: that is, if the `count_read_syscalls` configuration
+    /// parameter is enabled. Locally dispatched bodies are never counted.
+    ///
+    /// A body that took many more reads than its length divided by the read
+    /// buffer size suggests a buffer that's too small for the workload.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if let Some(reads) = data.read_syscalls() {
+    ///         println!("{} reads so far", reads);
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn read_syscalls(&self) -> Option<usize> {
+        self.stream.get_ref().get_ref().1.read_syscalls()
+    }
+
+    /// Returns a snapshot of what is known about the body without reading any
+    /// more of it: its framing, declared length, how much of it is in the
+    /// `peek` buffer, whether that is all of it, whether it arrived over TLS,
+    /// and the address of the peer sending it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> String {
+    ///     format!("{:?}", data.info())
+    /// }
+    /// ```
+    pub fn info(&self) -> DataInfo {
+        let framing = match self.stream {
+            SizedReader(..) => Framing::Sized,
+            EofReader(_) => Framing::Eof,
+            EmptyReader(_) => Framing::Empty,
+            ChunkedReader(..) => Framing::Chunked,
+        };
+
+        DataInfo {
+            framing: framing,
+            declared_len: self.len(),
+            peek_len: self.peek().len(),
+            is_complete: self.peek_complete(),
+            is_secure: self.stream.get_ref().get_ref().1.is_secure(),
+            peer: self.peer,
+        }
+    }
+
+    /// Returns `true` if the entire body of the request was received along
+    /// with the request's headers. When this is the case, reading the body
+    /// never requires reading from the network. Returns `false` if reading the
+    /// body required or may require additional reads from the network.
+    ///
+    /// For bodies of unknown length, the body is only known to have been
+    /// received with the headers if its end was found in the bytes received
+    /// with the headers; if the body ends exactly at the end of those bytes,
+    /// this method conservatively returns `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     if !data.is_prebuffered() {
+    ///         println!("The body required additional network reads.");
+    ///     }
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn is_prebuffered(&self) -> bool {
+        self.is_prebuffered
+    }
+
+    /// Returns the number of bytes that were read from the network ahead of
+    /// the body's stream and are still waiting to be read from it, not
+    /// counting the bytes in the `peek` buffer.
+    ///
+    /// Hyper reads the start of the body along with the request's headers
+    /// into a buffer that the body's stream is served from first. Once that
+    /// buffer is drained, every read of the body reads from the connection
+    /// directly: there is no further buffering between the two. A parser that
+    /// makes many small reads past this point makes as many reads from the
+    /// socket. The buffer may also hold bytes of a pipelined request that
+    /// follows the body.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) {
+    ///     println!("{} bytes peeked, {} more buffered",
+    ///              data.peek().len(), data.buffered_stream_bytes());
+    /// }
+    /// ```
+    #[inline]
+    pub fn buffered_stream_bytes(&self) -> usize {
+        let (pos, len) = prebuffered_range(&self.stream);
+        len.saturating_sub(pos) as usize
+    }
+
+    /// Returns `true` if the body is known to have been cut short: the
+    /// connection ended, or failed, before the number of bytes declared by
+    /// the request's `Content-Length` header was received. A truncated body
+    /// is never reported as complete by
+    /// [`peek_complete`](#method.peek_complete), and reading it is likely to
+    /// fail or yield fewer bytes than declared.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> Result<(), &'static str> {
+    ///     if data.is_truncated() {
+    ///         return Err("incomplete upload");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn is_truncated(&self) -> bool {
+        self.is_truncated
+    }
+
+    /// Returns a best guess of the content type of the body based on the
+    /// `peek` buffer.
+    ///
+    /// This is intended as a fallback for requests that don't declare a
+    /// `Content-Type`. The guess is conservative: only well-known signatures
+    /// (PNG, GIF, JPEG, PDF, WOFF, XML, and HTML) are recognized, and a body is
+    /// only guessed to be JSON when it is entirely contained in the `peek`
+    /// buffer. If the content type cannot be determined with confidence,
+    /// returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Request, Data};
+    /// use rocket::http::ContentType;
+    ///
+    /// fn handler(request: &Request, data: Data) {
+    ///     let content_type = request.content_type()
+    ///         .cloned()
+    ///         .or_else(|| data.inferred_content_type());
+    /// }
+    /// ```
+    pub fn inferred_content_type(&self) -> Option<ContentType> {
+        sniff(self.peek(), self.peek_complete())
+    }
+
+    /// Reads the body of the request into memory, up to `limit` bytes, and
+    /// returns a `Data` whose internal buffer holds the bytes read.
+    ///
+    /// If the entire body fits within `limit` bytes, the returned `Data` holds
+    /// the entire body in memory and [`peek_complete`](#method.peek_complete)
+    /// returns `true`. Otherwise, the behavior is determined by `overflow`:
+    ///
+    ///   * [`Overflow::Stream`]: the buffered prefix is retained and the
+    ///     remainder of the body is streamed from the network when the returned
+    ///     `Data` is [`open`](#method.open)ed. No data is lost.
+    ///   * [`Overflow::Error`]: an error of kind `InvalidData` is returned. The
+    ///     body is discarded.
+    ///
+    /// Regardless of the outcome, [`peek`](#method.peek) continues to return at
+    /// most 512 bytes.
+    ///
+    /// [`Overflow::Stream`]: /rocket/data/enum.Overflow.html#variant.Stream
+    /// [`Overflow::Error`]: /rocket/data/enum.Overflow.html#variant.Error
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the body fails or if `overflow` is
+    /// `Overflow::Error` and the body exceeds `limit` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::data::Overflow;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     // Buffer up to 64KiB of the body, streaming anything beyond that.
+    ///     let data = data.buffer_fully(64 * 1024, Overflow::Stream)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn buffer_fully(mut self, limit: usize, overflow: Overflow) -> io::Result<Data> {
+        let msg = "body exceeds the buffering limit";
+        let len = self.len();
+        let too_long = len.map_or(false, |len| len > limit as u64);
+        if too_long && overflow == Overflow::Error {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+
+        let mut buffer = ::std::mem::replace(&mut self.buffer, vec![]);
+        if !self.is_complete && buffer.len() <= limit {
+            // Reading one byte past the limit tells us if there's more data.
+            let target = limit.saturating_add(1);
+            let want = len.map_or(target, |len| min(target as u64, len + 1) as usize);
+            let start = buffer.len();
+            if want > start {
+                buffer.resize(want, 0);
+                let mut body = Hooked {
+                    body: Tracked { body: &mut self.stream, tracker: &mut self.chunks },
+                    hooks: &mut self.hooks,
+                };
+
+                let n = body.read_max(&mut buffer[start..])?;
+                buffer.truncate(start + n);
+                self.is_complete = start + n < want;
+            }
+        }
+
+        let overflowed = !self.is_complete || buffer.len() > limit;
+        self.buffer = buffer;
+        match (overflowed, overflow) {
+            (false, _) | (true, Overflow::Stream) => Ok(self),
+            (true, Overflow::Error) => {
+                Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+            }
+        }
+    }
+
+    /// A helper method to write the body of the request to any `Write` type.
+    ///
+    /// This method is equivalent to `io::copy(&mut data.open(), writer)`: reads
+    /// and writes that are interrupted are retried, and any other error ends
+    /// the copy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(mut data: Data) -> io::Result<String> {
+    ///     // write all of the data to stdout
+    ///     data.stream_to(&mut io::stdout())
+    ///         .map(|n| format!("Wrote {} bytes.", n))
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn stream_to<W: Write>(self, writer: &mut W) -> io::Result<u64> {
+        self.open().copy_to(writer)
+    }
+
+    /// Writes the body to every writer in `writers` in a single pass,
+    /// returning the number of bytes read from the body.
+    ///
+    /// Each chunk read from the body is written to every writer before the
+    /// next chunk is read, so the body is never buffered in its entirety.
+    /// What happens when a writer fails is determined by `on_failure`; see
+    /// [`SinkFailure`](/rocket/data/enum.SinkFailure.html). Every writer that
+    /// hasn't failed is flushed once the body has been written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Write};
+    /// use std::fs::File;
+    /// use rocket::Data;
+    /// use rocket::data::SinkFailure;
+    ///
+    /// fn handler(data: Data) -> io::Result<u64> {
+    ///     let mut primary = File::create("/primary/upload")?;
+    ///     let mut replica = File::create("/replica/upload")?;
+    ///     let writers: &mut [&mut Write] = &mut [&mut primary, &mut replica];
+    ///     data.stream_to_all(writers, SinkFailure::Abort)
+    /// }
+    /// ```
+    pub fn stream_to_all(
+        self,
+        writers: &mut [&mut Write],
+        on_failure: SinkFailure
+    ) -> io::Result<u64> {
+        let mut fan_out = FanOut {
+            failed: vec![false; writers.len()],
+            writers: writers,
+            on_failure: on_failure,
+        };
+
+        let n = self.open().copy_to(&mut fan_out)?;
+        fan_out.flush()?;
+        Ok(n)
+    }
+
+    /// Reads the body in chunks of `chunk` bytes and sends each chunk on `tx`,
+    /// returning the number of bytes sent.
+    ///
+    /// Because `tx` is bounded, reading blocks whenever the channel is full,
+    /// so a slow consumer never causes more than the channel's capacity worth
+    /// of chunks to be buffered. Every chunk but the last is exactly `chunk`
+    /// bytes long. If reading the body fails, the error is returned as-is, so
+    /// its cause can still be inspected, and a copy with the same kind and
+    /// message is sent on the channel as its final item. If the receiver hangs
+    /// up, reading stops and an error of kind `BrokenPipe` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use std::thread;
+    /// use std::sync::mpsc::sync_channel;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<u64> {
+    ///     let (tx, rx) = sync_channel(4);
+    ///     let worker = thread::spawn(move || {
+    ///         for chunk in rx {
+    ///             match chunk {
+    ///                 Ok(bytes) => println!("processing {} bytes", bytes.len()),
+    ///                 Err(e) => println!("body failed: {}", e),
+    ///             }
+    ///         }
+    ///     });
+    ///
+    ///     let sent = data.stream_to_channel(tx, 4096)?;
+    ///     worker.join().expect("worker panicked");
+    ///     Ok(sent)
+    /// }
+    /// ```
+    pub fn stream_to_channel(
+        self,
+        tx: SyncSender<io::Result<Vec<u8>>>,
+        chunk: usize
+    ) -> io::Result<u64> {
+        let hung_up = || io::Error::new(io::ErrorKind::BrokenPipe, "body receiver hung up");
+        let chunk = ::std::cmp::max(chunk, 1);
+        let mut stream = self.open();
+        let mut sent = 0;
+        loop {
+            let mut buf = vec![0; chunk];
+            match stream.read_max(&mut buf) {
+                Ok(0) => return Ok(sent),
+                Ok(n) => {
+                    buf.truncate(n);
+                    tx.send(Ok(buf)).map_err(|_| hung_up())?;
+                    sent += n as u64;
+                    if n < chunk {
+                        return Ok(sent);
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(io::Error::new(e.kind(), e.to_string())));
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Streams the body to `f` in parts of `part_size` bytes, as for a
+    /// multipart upload to cloud storage, returning the number of bytes
+    /// streamed. `f` is called with each part and whether it's the last.
+    ///
+    /// Every part but the last is exactly `part_size` bytes; the last is at
+    /// most as long and is only empty if the body is. `f` is always called at
+    /// least once. To tell whether a part is the last, the following part is
+    /// read before `f` is called, so up to two parts are held in memory at
+    /// once. A `part_size` of `0` is treated as `1`.
+    ///
+    /// # Errors
+    ///
+    /// Stops at, and returns, the first error from reading the body or from
+    /// `f`; parts after the failed one are neither read nor passed to `f`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// # fn upload_part(number: usize, part: Vec<u8>) -> io::Result<()> { Ok(()) }
+    /// fn handler(data: Data) -> io::Result<u64> {
+    ///     let mut number = 0;
+    ///     data.stream_in_parts(5 * (1 << 20), |part, is_last| {
+    ///         number += 1;
+    ///         println!("part {} (last: {})", number, is_last);
+    ///         upload_part(number, part)
+    ///     })
+    /// }
+    /// ```
+    pub fn stream_in_parts<F>(self, part_size: usize, mut f: F) -> io::Result<u64>
+        where F: FnMut(Vec<u8>, bool) -> io::Result<()>
+    {
+        fn read_part(stream: &mut DataStream, size: usize) -> io::Result<Vec<u8>> {
+            let mut part = vec![0; size];
+            let n = stream.read_max(&mut part)?;
+            part.truncate(n);
+            Ok(part)
+        }
+
+        let part_size = ::std::cmp::max(part_size, 1);
+        let mut stream = self.open();
+        let mut part = read_part(&mut stream, part_size)?;
+        let mut streamed = 0;
+        loop {
+            streamed += part.len() as u64;
+            let next = match part.len() < part_size {
+                true => vec![],
+                false => read_part(&mut stream, part_size)?,
+            };
+
+            if next.is_empty() {
+                f(part, true)?;
+                return Ok(streamed);
+            }
+
+            f(part, false)?;
+            part = next;
+        }
+    }
+
+    /// Reads the body from the network on a separate thread, returning a reader
+    /// over the body that can be handed to an existing parser, so that
+    /// processing the body overlaps with receiving it.
+    ///
+    /// The thread reads the body in chunks of `chunk` bytes into a channel
+    /// that holds at most `capacity` of them; once it's full, the thread waits
+    /// for the returned reader to catch up. Errors reading the body are
+    /// returned by the reader in the order they occurred. See
+    /// [`stream_to_channel`](#method.stream_to_channel) to consume the chunks
+    /// directly instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the thread can't be spawned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<usize> {
+    ///     let mut body = data.read_in_background(64 * 1024, 4)?;
+    ///     let mut contents = vec![];
+    ///     body.read_to_end(&mut contents)?;
+    ///     Ok(contents.len())
+    /// }
+    /// ```
+    pub fn read_in_background(self, chunk: usize, capacity: usize) -> io::Result<BackgroundReader> {
+        BackgroundReader::spawn(self, chunk, capacity)
+    }
+
+    /// Spawns `command` and pipes the body through it, returning a reader over
+    /// the process's standard output.
+    ///
+    /// The body is written to the process's standard input from a separate
+    /// thread, so the process may produce output before it has consumed all
+    /// of its input without deadlocking. The process's standard input and
+    /// output are replaced by pipes; its standard error is left as configured
+    /// in `command`. Call [`ChildOutput::finish`] after reading the output to
+    /// wait for the process and check that the body was written to it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use std::process::Command;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let mut output = data.pipe_through(Command::new("wc"))?;
+    ///     let mut counts = String::new();
+    ///     output.read_to_string(&mut counts)?;
+    ///
+    ///     let (_, status) = output.finish()?;
+    ///     match status.success() {
+    ///         true => Ok(counts),
+    ///         false => Err(io::Error::new(io::ErrorKind::Other, "wc failed")),
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`ChildOutput::finish`]: /rocket/data/struct.ChildOutput.html#method.finish
+    pub fn pipe_through(self, command: Command) -> io::Result<ChildOutput> {
+        ChildOutput::spawn(self.open(), command)
+    }
+
+    /// Returns an iterator over the body in chunks of `size` bytes, each paired
+    /// with the offset of its first byte in the body. Offsets include the
+    /// bytes in the peek buffer, so the first chunk is always at offset `0`.
+    /// The last chunk may be shorter than `size`. A `size` of `0` is treated
+    /// as `1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     for chunk in data.offset_chunks(4096) {
+    ///         let (offset, block) = chunk?;
+    ///         println!("block at {}: {} bytes", offset, block.len());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn offset_chunks(self, size: usize) -> OffsetChunks {
+        OffsetChunks::new(self.open(), size)
+    }
+
+    /// A helper method to write the body of the request to a file at the path
+    /// determined by `path`.
+    ///
+    /// This method is equivalent to
+    /// `io::copy(&mut self.open(), &mut File::create(path)?)`: reads and writes
+    /// that are interrupted are retried, and any other error ends the copy.
+    ///
+    /// On Linux, a sized body received over plaintext TCP is moved from the
+    /// socket to the file with `splice`, without being copied through
+    /// userspace, unless a hook must observe its bytes, as when the body is
+    /// audited or scanned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(mut data: Data) -> io::Result<String> {
+    ///     data.stream_to_file("/static/file")
+    ///         .map(|n| format!("Wrote {} bytes to /static/file", n))
+    /// }
+    /// ```
+    pub fn stream_to_file<P: AsRef<Path>>(self, path: P) -> io::Result<u64> {
+        let mut file = File::create(path)?;
+        let mut stream = self.open();
+
+        #[cfg(target_os = "linux")] {
+            if let Some(result) = stream.splice_to(&file) {
+                return result;
+            }
+        }
+
+        stream.copy_to(&mut file)
+    }
+
+    /// Writes the body of the request into the file at `path`, starting at
+    /// byte `offset`, and returns the number of bytes written. The file is
+    /// created if it doesn't exist.
+    ///
+    /// Unlike [`stream_to_file`](#method.stream_to_file), the file isn't
+    /// truncated: bytes before `offset` are left untouched and bytes after
+    /// it are overwritten. This is the primitive for resumable uploads; see
+    /// [`ResumableUpload`] for one that validates the offset.
+    ///
+    /// [`ResumableUpload`]: /rocket/data/struct.ResumableUpload.html
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data, offset: u64) -> io::Result<u64> {
+    ///     data.stream_to_file_at("/uploads/file", offset)
+    /// }
+    /// ```
+    pub fn stream_to_file_at<P: AsRef<Path>>(self, path: P, offset: u64) -> io::Result<u64> {
+        let mut file = OpenOptions::new().write(true).create(true).open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut stream = self.open();
+
+        #[cfg(target_os = "linux")] {
+            if let Some(result) = stream.splice_to(&file) {
+                return result;
+            }
+        }
+
+        stream.copy_to(&mut file)
+    }
+
+    /// A helper method to gzip compress the body of the request into a file at
+    /// the path determined by `path` using the compression `level`, which
+    /// ranges from `0` (no compression) to `9` (best compression).
+    ///
+    /// The body is compressed as it is read; it is never buffered in memory in
+    /// its entirety. The compressed data is first written to a temporary file
+    /// in the same directory as `path` which is then renamed to `path` once
+    /// compression has completed successfully. As a result, a file at `path`
+    /// is either absent or contains a complete gzip stream. On success, returns
+    /// the number of uncompressed bytes read from the body.
+    ///
+    /// This method is only available when the `gzip` feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// # #[cfg(feature = "gzip")]
+    /// fn handler(mut data: Data) -> io::Result<String> {
+    ///     data.stream_to_gzip_file("/uploads/file.gz", 6)
+    ///         .map(|n| format!("Compressed {} bytes into /uploads/file.gz", n))
+    /// }
+    /// ```
+    #[cfg(feature = "gzip")]
+    pub fn stream_to_gzip_file<P: AsRef<Path>>(self, path: P, level: u32) -> io::Result<u64> {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let path = path.as_ref();
+        let tmp_path = temporary_sibling(path)?;
+        let result = File::create(&tmp_path).and_then(|file| {
+            let mut encoder = GzEncoder::new(file, Compression::new(level));
+            let n = self.open().copy_to(&mut encoder)?;
+            encoder.finish()?.sync_all()?;
+            Ok(n)
+        });
+
+        match result.and_then(|n| ::std::fs::rename(&tmp_path, path).map(|_| n)) {
+            Ok(n) => Ok(n),
+            Err(e) => {
+                let _ = ::std::fs::remove_file(&tmp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Streams the body to a file in `dir` named by the hexadecimal digest of
+    /// its contents under `algorithm`, returning the file's path and the
+    /// number of bytes read from the body.
+    ///
+    /// The body is hashed as it is written to a temporary file in `dir`, which
+    /// is then renamed to its final name; a file named by a digest is thus
+    /// always complete. If a file with the same digest already exists, the
+    /// body is a duplicate: the temporary file is removed and the path of the
+    /// existing file is returned.
+    ///
+    /// This method is only available when the `cas` feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// # #[cfg(feature = "cas")]
+    /// use rocket::data::HashAlgorithm;
+    ///
+    /// # #[cfg(feature = "cas")]
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let (path, n) = data.stream_to_cas("/blobs", HashAlgorithm::Sha256)?;
+    ///     Ok(format!("Stored {} bytes at {}", n, path.display()))
+    /// }
+    /// ```
+    #[cfg(feature = "cas")]
+    pub fn stream_to_cas<P: AsRef<Path>>(
+        self,
+        dir: P,
+        algorithm: HashAlgorithm
+    ) -> io::Result<(::std::path::PathBuf, u64)> {
+        let dir = dir.as_ref();
+        let tmp_path = temporary_sibling(&dir.join("upload"))?;
+        let result = File::create(&tmp_path).and_then(|file| {
+            let mut writer = HashingWriter::new(file, algorithm);
+            let n = self.open().copy_to(&mut writer)?;
+            let (file, digest) = writer.finish();
+            file.sync_all()?;
+            Ok((dir.join(digest), n))
+        });
+
+        let result = result.and_then(|(path, n)| {
+            if path.exists() {
+                ::std::fs::remove_file(&tmp_path)?;
+            } else {
+                ::std::fs::rename(&tmp_path, &path)?;
+            }
+
+            Ok((path, n))
+        });
+
+        if result.is_err() {
+            let _ = ::std::fs::remove_file(&tmp_path);
+        }
+
+        result
+    }
+
+    /// Reads the body into `arena`, returning a slice of the bytes read that
+    /// lives as long as `arena`.
+    ///
+    /// Many bodies read into the same arena share a few large allocations,
+    /// reducing allocator pressure and keeping the bodies close in memory.
+    /// See [`Arena`](/rocket/data/struct.Arena.html). If reading fails, the
+    /// bytes read are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::data::Arena;
+    ///
+    /// fn handler(data: Data, arena: &Arena) -> io::Result<usize> {
+    ///     let body = data.read_into_arena(arena)?;
+    ///     Ok(body.len())
+    /// }
+    /// ```
+    pub fn read_into_arena(self, arena: &Arena) -> io::Result<&[u8]> {
+        let hint = self.len();
+        arena.append(&mut self.open(), hint)
+    }
+
+    /// Writes the body to `writer`, returning the number of bytes written and
+    /// the CRC32C (Castagnoli) checksum of the body, as required by object
+    /// stores such as Google Cloud Storage.
+    ///
+    /// The checksum is computed as the body is written and uses hardware
+    /// acceleration where the CPU supports it. It covers the bytes read from
+    /// the body, even if `writer` fails partway through.
+    ///
+    /// This method is only available when the `crc` feature is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use std::fs::File;
+    /// use rocket::Data;
+    ///
+    /// # #[cfg(feature = "crc")]
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let mut file = File::create("/tmp/object")?;
+    ///     let (n, crc) = data.stream_with_crc32c(&mut file)?;
+    ///     Ok(format!("Stored {} bytes with CRC32C {:08x}", n, crc))
+    /// }
+    /// ```
+    #[cfg(feature = "crc")]
+    pub fn stream_with_crc32c<W: Write>(self, writer: W) -> io::Result<(u64, u32)> {
+        let mut writer = Crc32cWriter::new(writer);
+        let n = self.open().copy_to(&mut writer)?;
+        Ok((n, writer.crc()))
+    }
+
+    // Creates a new data object with an internal buffer `buf`, where the cursor
+    // in the buffer is at `pos` and the buffer has `cap` valid bytes. Thus, the
+    // bytes `vec[pos..cap]` are buffered and unread. The remainder of the data
+    // bytes can be read from `stream`.
+    #[inline(always)]
+    pub(crate) fn new(stream: BodyReader) -> Data {
+        match Data::peeked(stream, PeekErrorPolicy::LogAndEmpty, vec![]) {
+            Ok(data) => data,
+            Err(_) => unreachable!("peek errors are logged, not returned")
+        }
+    }
+
+    // Like `new`, but applies `policy` if reading into the peek buffer fails
+    // and runs `hooks` over the reads that fill it and every later read.
+    pub(crate) fn peeked(
+        mut stream: BodyReader,
+        policy: PeekErrorPolicy,
+        hooks: Vec<Box<ReadHook>>
+    ) -> Result<Data, DataError> {
+        trace_!("Date::new({:?})", stream);
+        let mut peek_buf = vec![0; PEEK_BYTES];
+        let (pre_pos, pre_len) = prebuffered_range(&stream);
+        let declared_len = match stream {
+            SizedReader(_, n) => Some(n),
+            _ => None
+        };
+
+        // Fill the buffer with as many bytes as possible. If we read less than
+        // that buffer's length, we know we reached the EOF. Otherwise, it's
+        // unclear, so we just say we didn't reach EOF. A request without a body
+        // has nothing to read: it is trivially empty and complete.
+        // A sized body that ends before both its declared length and the end
+        // of the buffer was cut short by the client: it's truncated, not
+        // complete.
+        let mut is_truncated = false;
+        let mut chunks = ChunkTracker::default();
+        let mut hooks: Vec<_> = hooks.into_iter().map(|hook| Observed::new(hook, 0)).collect();
+        let eof = if let EmptyReader(_) = stream {
+            unsafe { peek_buf.set_len(0); }
+            true
+        } else {
+            let mut body = Hooked {
+                body: Tracked { body: &mut stream, tracker: &mut chunks },
+                hooks: &mut hooks,
+            };
+
+            match fill_peek(&mut body, &mut peek_buf, policy) {
+                Ok(n) => {
+                    trace_!("Filled peek buf with {} bytes.", n);
+                    // TODO: Explain this.
+                    unsafe { peek_buf.set_len(n); }
+                    let expected = declared_len.map_or(0, |len| min(len, PEEK_BYTES as u64));
+                    if (n as u64) < expected {
+                        warn_!("Body ended after {} of {} declared bytes.",
+                               n, declared_len.unwrap_or(0));
+                        is_truncated = true;
+                    }
+
+                    n < PEEK_BYTES && !is_truncated
+                }
+                Err(e) => {
+                    error_!("Failed to read into peek buffer: {:?}.", e);
+                    if policy == PeekErrorPolicy::FailRequest {
+                        return Err(DataError::PeekFailed(e));
+                    }
+
+                    unsafe { peek_buf.set_len(0); }
+                    is_truncated = true;
+                    false
+                },
+            }
+        };
+
+        // If the body's length is known, it was prebuffered if all of it fit
+        // in the prebuffered bytes. Otherwise, it was prebuffered only if we
+        // found its end without exhausting the prebuffered bytes.
+        let is_prebuffered = match (&stream, declared_len) {
+            (&EmptyReader(_), _) => true,
+            (_, Some(n)) => n <= pre_len.saturating_sub(pre_pos),
+            (_, None) => eof && prebuffered_range(&stream).0 < pre_len,
+        };
+
+        trace_!("Peek bytes: {}/{} bytes.", peek_buf.len(), PEEK_BYTES);
+        Ok(Data {
+            buffer: peek_buf,
+            stream: stream,
+            is_complete: eof,
+            is_prebuffered: is_prebuffered,
+            is_truncated: is_truncated,
+            has_declared_length: declared_len.is_some(),
+            method: None,
+            uri: None,
+            audit: None,
+            guard: None,
+            hooks: hooks,
+            peer: None,
+            read_buffer: DEFAULT_READ_BUFFER,
+            extensions: TypeMap::new(),
+            chunks: chunks,
+        })
+    }
+
+    // Creates a new data object from `stream` without filling the peek buffer:
+    // every byte of the body remains in the stream.
+    pub(crate) fn unpeeked(stream: BodyReader) -> Data {
+        let (pre_pos, pre_len) = prebuffered_range(&stream);
+        let (is_complete, is_prebuffered) = match stream {
+            EmptyReader(_) | SizedReader(_, 0) => (true, true),
+            SizedReader(_, n) => (false, n <= pre_len.saturating_sub(pre_pos)),
+            _ => (false, false)
+        };
+
+        let has_declared_length = match stream {
+            SizedReader(..) => true,
+            _ => false
+        };
+
+        Data {
+            buffer: vec![],
+            stream: stream,
+            is_complete: is_complete,
+            is_prebuffered: is_prebuffered,
+            is_truncated: false,
+            has_declared_length: has_declared_length,
+            method: None,
+            uri: None,
+            audit: None,
+            guard: None,
+            hooks: vec![],
+            peer: None,
+            read_buffer: DEFAULT_READ_BUFFER,
+            extensions: TypeMap::new(),
+            chunks: ChunkTracker::default(),
+        }
+    }
+
+    /// Adds `hook` to the hooks run on every later read of the body: from the
+    /// stream returned by `open` and the other stream accessors, and into the
+    /// buffer by `buffer_fully`.
+    #[inline]
+    pub(crate) fn with_hook<H: ReadHook + 'static>(self, hook: H) -> Data {
+        self.with_boxed_hook(Box::new(hook))
+    }
+
+    /// Adds the already boxed `hook` to the hooks run on every later read.
+    #[inline]
+    pub(crate) fn with_boxed_hook(mut self, hook: Box<ReadHook>) -> Data {
+        let from = self.buffer.len();
+        self.hooks.push(Observed::new(hook, from));
+        self
+    }
+
+    /// Records the method and URI of the request the body belongs to.
+    #[inline]
+    pub(crate) fn with_request_line(mut self, method: Method, uri: &Uri) -> Data {
+        self.method = Some(method);
+        self.uri = Some(Uri::new(uri.as_str().to_string()));
+        self
+    }
+
+    /// Sets the address of the client that sent the body, as resolved from
+    /// forwarding headers set by a trusted proxy.
+    #[inline]
+    pub(crate) fn set_peer(&mut self, peer: SocketAddr) {
+        self.peer = Some(peer);
+    }
+
+    /// Sets whether the body's length was declared, as by a `Content-Length`
+    /// header. See [`has_declared_length`](#method.has_declared_length).
+    #[inline]
+    pub(crate) fn with_declared_length(mut self, declared: bool) -> Data {
+        self.has_declared_length = declared;
+        self
+    }
+
+    /// Sets the capacity of the buffer small stream reads are coalesced into.
+    /// A `capacity` of `0` disables coalescing so that hooks observe exactly
+    /// the reads made by the stream's reader.
+    #[inline]
+    pub(crate) fn with_read_buffer(mut self, capacity: usize) -> Data {
+        self.read_buffer = capacity;
+        self
+    }
+
+    /// This creates a `data` object from a local data source `data`.
+    #[inline]
+    pub(crate) fn local(data: Vec<u8>) -> Data {
+        Data::with_buffer(data, true)
+    }
+
+    /// Creates a `Data` from the snapshot at `path`, previously written by
+    /// [`record_snapshot`](#method.record_snapshot), so that a recorded body
+    /// can be replayed in a test. The snapshot's metadata is available as a
+    /// [`SnapshotMeta`](/rocket/data/struct.SnapshotMeta.html) in the
+    /// returned `Data`'s [`extensions`](#method.extensions).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the file isn't a snapshot.
+    /// Other I/O errors are returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::data::SnapshotMeta;
+    ///
+    /// fn replay() -> io::Result<()> {
+    ///     let data = Data::from_snapshot("tests/snapshots/issue-812.body")?;
+    ///     let meta = data.extensions().get::<SnapshotMeta>().cloned().unwrap_or_default();
+    ///     println!("replaying a {:?} body", meta.content_type);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_snapshot<P: AsRef<Path>>(path: P) -> io::Result<Data> {
+        let (meta, body) = snapshot::read(path)?;
+        let data = Data::local(body);
+        data.extensions().insert(meta);
+        Ok(data)
+    }
+
+    /// Records a snapshot of the body to a new file at `path` as it is read,
+    /// along with the `Content-Type` and `Content-Encoding` of `request`. The
+    /// snapshot can be replayed with [`from_snapshot`](#method.from_snapshot).
+    ///
+    /// Only the bytes read from the body's stream are recorded, so a handler
+    /// that stops reading early leaves a partial snapshot. The snapshot is
+    /// complete once the stream is dropped. A failure to write the snapshot
+    /// after the file is created is logged; it doesn't fail reads.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file couldn't be created.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::{Request, Data};
+    ///
+    /// fn handler(request: &Request, data: Data) -> io::Result<String> {
+    ///     let data = data.record_snapshot(request, "/var/snapshots/last.body")?;
+    ///     let mut body = String::new();
+    ///     io::Read::read_to_string(&mut data.open(), &mut body)?;
+    ///     Ok(body)
+    /// }
+    /// ```
+    pub fn record_snapshot<P: AsRef<Path>>(self, request: &Request, path: P) -> io::Result<Data> {
+        let meta = SnapshotMeta {
+            content_type: request.headers().get_one("Content-Type").map(|v| v.to_string()),
+            content_encoding: request.headers().get_one("Content-Encoding").map(|v| v.to_string()),
+        };
+
+        Ok(self.with_hook(SnapshotWriter::create(path, &meta)?))
+    }
+
+    /// This creates a `data` object from `data`, a chunked body as it would be
+    /// received from the network, including its framing.
+    #[cfg(test)]
+    pub(crate) fn local_chunked(data: Vec<u8>) -> Data {
+        Data::from_parts(data, 0, NetStream::Empty, BodyKind::Chunked(None))
+    }
+
+    /// Creates a `Data` whose body is exactly `bytes`. This is useful to a
+    /// [`DataFairing`](/rocket/fairing/trait.DataFairing.html) that replaces a
+    /// request's body, for instance with a decoded version of it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Data;
+    ///
+    /// let data = Data::from_bytes(b"hello".to_vec());
+    /// assert_eq!(data.peek(), b"hello");
+    /// assert!(data.peek_complete());
+    /// ```
+    #[inline]
+    pub fn from_bytes(bytes: Vec<u8>) -> Data {
+        Data::local(bytes)
+    }
+
+    /// Creates a `Data` whose body is exactly `buffer`, reporting
+    /// `is_complete` from `peek_complete` instead of inferring it. Nothing
+    /// remains to be read past `buffer`.
+    #[inline]
+    pub(crate) fn with_buffer(buffer: Vec<u8>, is_complete: bool) -> Data {
+        let empty_stream = Cursor::new(vec![]).chain(NetStream::Empty);
+
+        Data {
+            buffer: buffer,
+            stream: HttpReader::SizedReader(empty_stream, 0),
+            is_complete: is_complete,
+            is_prebuffered: true,
+            is_truncated: false,
+            has_declared_length: true,
+            method: None,
+            uri: None,
+            audit: None,
+            guard: None,
+            hooks: vec![],
+            peer: None,
+            read_buffer: DEFAULT_READ_BUFFER,
+            extensions: TypeMap::new(),
+            chunks: ChunkTracker::default(),
+        }
+    }
+
+    /// Creates a `Data` from `bytes` through the same peek and streaming
+    /// machinery used for bodies read from the network. See
+    /// [`fuzz_data`](/rocket/data/fn.fuzz_data.html).
+    #[cfg(feature = "fuzz")]
+    pub(crate) fn fuzz(bytes: &[u8]) -> Data {
+        let kind = BodyKind::Sized(bytes.len() as u64);
+        Data::from_parts(bytes.to_vec(), 0, NetStream::Empty, kind)
+    }
+
+    /// Reads `bytes` as a request body through the same peek and streaming
+    /// machinery used for bodies read from the network and returns the bytes
+    /// read. Unlike `Data::local`, this includes filling the `peek` buffer.
+    /// This exists to benchmark that machinery without a socket.
+    #[doc(hidden)]
+    pub fn bench_roundtrip(bytes: Vec<u8>) -> Vec<u8> {
+        let len = bytes.len();
+        let data = Data::from_parts(bytes, 0, NetStream::Empty, BodyKind::Sized(len as u64));
+        let mut body = Vec::with_capacity(len);
+        data.open().read_to_end(&mut body).expect("in-memory body read");
+        body
+    }
+}
+
+/// Builds the reader for a request body from its constituent parts, as
+/// described in `Data::from_parts`.
+fn body_reader(buf: Vec<u8>, pos: usize, net_stream: NetStream, kind: BodyKind) -> BodyReader {
+    // `from_hyp` rejects a `pos` beyond the buffer, but other callers don't,
+    // so never let the subtraction wrap. A cursor past the end reads nothing.
+    debug_assert!(pos <= buf.len(), "body position {} exceeds buffer length {}", pos, buf.len());
+    let remaining = buf.len().saturating_sub(pos);
+    if pos > buf.len() {
+        trace_!("Hyper buffer position {} exceeds its length {}.", pos, buf.len());
+    }
+
+    trace_!("Hyper buffer: [{}..{}] ({} bytes).", pos, buf.len(), remaining);
+
+    let mut cursor = Cursor::new(buf);
+    cursor.set_position(pos as u64);
+    kind.reader(cursor.chain(net_stream))
+}
+
+/// Reads as many bytes of `body` as fit into `buf`, returning how many were
+/// read. Under the `RetryOnce` policy, the first read that fails because it
+/// timed out is retried, continuing from the bytes already read.
+pub(crate) fn fill_peek<R: Read>(
+    body: &mut R,
+    buf: &mut [u8],
+    policy: PeekErrorPolicy
+) -> io::Result<usize> {
+    let (mut filled, mut retried) = (0, false);
+    while filled < buf.len() {
+        match body.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(ref e) if policy == PeekErrorPolicy::RetryOnce && is_transient(e) && !retried => {
+                warn_!("Reading into peek buffer failed: {}. Retrying.", e);
+                retried = true;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(filled)
+}
+
+fn is_transient(e: &io::Error) -> bool {
+    match e.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => true,
+        _ => false
+    }
+}
+
+/// Returns `true` if `req` declares both a `Content-Length` and a chunked
+/// `Transfer-Encoding`.
+pub(crate) fn has_conflicting_length(req: &Request) -> bool {
+    let headers = req.headers();
+    let chunked = headers.get("Transfer-Encoding")
+        .flat_map(|value| value.split(','))
+        .any(|coding| coding.trim().eq_ignore_ascii_case("chunked"));
+
+    chunked && headers.contains("Content-Length")
+}
+
+/// Returns an error if `stream` is a TLS stream that negotiated a version of
+/// TLS older than `min`.
+#[cfg(feature = "tls")]
+fn check_tls_version(stream: &NetStream, min: TlsVersion) -> Result<(), DataError> {
+    match stream.tls_info() {
+        Some(info) => check_negotiated_version(info.tls_version(), min),
+        None => Ok(())
+    }
+}
+
+#[cfg(not(feature = "tls"))]
+fn check_tls_version(_: &NetStream, _: TlsVersion) -> Result<(), DataError> {
+    Ok(())
+}
+
+/// Returns an error if `negotiated`, a TLS version or `None` for one older
+/// than TLS 1.0, is older than `min`.
+pub(crate) fn check_negotiated_version(
+    negotiated: Option<TlsVersion>,
+    min: TlsVersion
+) -> Result<(), DataError> {
+    match negotiated {
+        Some(version) if version >= min => Ok(()),
+        _ => Err(DataError::TlsVersionTooOld { negotiated: negotiated, min: min })
+    }
+}
+
+/// Returns the position and length of the cursor over the bytes Hyper read
+/// from the network along with the request headers.
+#[inline]
+fn prebuffered_range(stream: &BodyReader) -> (u64, u64) {
+    let cursor = stream.get_ref().get_ref().0;
+    (cursor.position(), cursor.get_ref().len() as u64)
+}
+
+/// Returns a path, in the same directory as `path`, suitable for writing
+/// temporary data that will later be renamed to `path`.
+#[cfg(any(feature = "gzip", feature = "cas"))]
+fn temporary_sibling(path: &Path) -> io::Result<::std::path::PathBuf> {
+    use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+    static COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+
+    let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut tmp_name = ::std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(".{}.{}.tmp", ::std::process::id(), id));
+    Ok(path.with_file_name(tmp_name))
 }
 
 impl Drop for Data {
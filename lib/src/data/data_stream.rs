@@ -1,20 +1,960 @@
 use std::io::{self, Read, Cursor, Chain};
+use std::cmp::min;
 use std::net::Shutdown;
+use std::time::Duration;
+#[cfg(target_os = "linux")] use std::io::Write;
+#[cfg(target_os = "linux")] use std::fs::File;
 
-use super::data::BodyReader;
+use super::data::{BodyReader, BodyKind};
+use super::chunked::ChunkTracker;
+use super::error::ClientGone;
+use super::from_bytes::FromBytes;
+use super::net_stream::NetStream;
+#[cfg(target_os = "linux")] use super::splice::{self, Pipe, SPLICE_LEN};
+use ext::ReadExt;
 use http::hyper::net::NetworkStream;
 use http::hyper::h1::HttpReader;
 
 //                          |-- peek buf --|
 pub type InnerStream = Chain<Cursor<Vec<u8>>, BodyReader>;
 
+/// An observer of the reads made through a `DataStream`.
+///
+/// `before_read` is called before every read from the underlying stream and
+/// `after_read` is called with the bytes every successful read returned. An
+/// error returned from either is returned from the read in place of the data.
+/// `finish` is called once when the stream is dropped.
+///
+/// Bytes may be moved from the connection without being read into memory, as
+/// by `DataStream::splice_to`, only if no hook `observes_bytes`. Such moves
+/// call `after_splice` with the number of bytes moved in place of
+/// `after_read`, and once with `0` when the body ends.
+///
+/// Hooks must be `Send` so that `DataStream` remains `Send`.
+pub(crate) trait ReadHook: Send {
+    fn before_read(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn after_read(&mut self, _bytes: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn observes_bytes(&self) -> bool {
+        true
+    }
+
+    fn after_splice(&mut self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self) {  }
+}
+
+/// A hook attached to a `Data` along with the bytes of its `peek` buffer the
+/// hook has observed: those from `from`, the length of the buffer when the
+/// hook was attached, to `from + seen`.
+pub(crate) struct Observed {
+    hook: Box<ReadHook>,
+    from: usize,
+    seen: usize,
+}
+
+impl Observed {
+    #[inline]
+    pub(crate) fn new(hook: Box<ReadHook>, from: usize) -> Observed {
+        Observed { hook: hook, from: from, seen: 0 }
+    }
+
+    /// Returns the hook to run on a stream that begins with the first `prefix`
+    /// bytes of the `peek` buffer. The hook isn't shown the bytes it has
+    /// already observed again.
+    pub(crate) fn into_hook(self, prefix: usize) -> Box<ReadHook> {
+        let (from, to) = (min(self.from, prefix), min(self.from + self.seen, prefix));
+        match from < to {
+            true => Box::new(Skip { hook: self.hook, pos: 0, from: from, to: to }),
+            false => self.hook
+        }
+    }
+}
+
+/// Runs `hook` on every byte read except those at positions `from..to`.
+struct Skip {
+    hook: Box<ReadHook>,
+    pos: usize,
+    from: usize,
+    to: usize,
+}
+
+impl Skip {
+    // Advances past the next `n` bytes read, returning the range of them the
+    // hook doesn't see.
+    fn skip(&mut self, n: usize) -> (usize, usize) {
+        let start = self.pos;
+        self.pos += n;
+        (min(self.from.saturating_sub(start), n), min(self.to.saturating_sub(start), n))
+    }
+}
+
+impl ReadHook for Skip {
+    fn before_read(&mut self) -> io::Result<()> {
+        self.hook.before_read()
+    }
+
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let (lo, hi) = self.skip(bytes.len());
+        if bytes.is_empty() {
+            return self.hook.after_read(bytes);
+        }
+
+        if lo > 0 {
+            self.hook.after_read(&bytes[..lo])?;
+        }
+
+        match hi < bytes.len() {
+            true => self.hook.after_read(&bytes[hi..]),
+            false => Ok(())
+        }
+    }
+
+    fn observes_bytes(&self) -> bool {
+        self.hook.observes_bytes()
+    }
+
+    fn after_splice(&mut self, n: usize) -> io::Result<()> {
+        let (lo, hi) = self.skip(n);
+        match lo + (n - hi) {
+            0 if n > 0 => Ok(()),
+            shown => self.hook.after_splice(shown)
+        }
+    }
+
+    fn finish(&mut self) {
+        self.hook.finish()
+    }
+}
+
+/// A reader of `body` that runs `hooks` around every read, as a `DataStream`
+/// would, for reads made before the body's stream is opened.
+pub(crate) struct Hooked<'a, R> {
+    pub body: R,
+    pub hooks: &'a mut [Observed],
+}
+
+impl<'a, R: Read> Read for Hooked<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        for observed in self.hooks.iter_mut() {
+            observed.hook.before_read()?;
+        }
+
+        let n = self.body.read(buf)?;
+        for observed in self.hooks.iter_mut() {
+            observed.hook.after_read(&buf[..n])?;
+            observed.seen += n;
+        }
+
+        Ok(n)
+    }
+}
+
 /// Raw data stream of a request body.
 ///
 /// This stream can only be obtained by calling
 /// [Data::open](/rocket/data/struct.Data.html#method.open). The stream contains
-/// all of the data in the body of the request. It exposes no methods directly.
-/// Instead, it must be used as an opaque `Read` structure.
-pub struct DataStream(pub(crate) InnerStream);
+/// all of the data in the body of the request. It is primarily used as a
+/// `Read` structure, though it also exposes a few helper methods for common
+/// reading patterns.
+///
+/// `DataStream` is `Send`, for every kind of connection, so it can be handed
+/// to another thread, such as a worker in a thread pool, to be read there.
+///
+/// # Cancelled Uploads
+///
+/// If the client closes the connection before sending all of a body whose
+/// length it declared, reads fail with an error of kind `ConnectionAborted`
+/// wrapping a [`ClientGone`](/rocket/data/struct.ClientGone.html). A handler
+/// that commits work as it reads can match on it to roll back:
+///
+/// ```rust
+/// use std::io::{self, Read};
+/// use rocket::Data;
+/// use rocket::data::ClientGone;
+///
+/// fn handler(data: Data) -> io::Result<()> {
+///     let mut stream = data.open();
+///     let mut buf = [0; 4096];
+///     loop {
+///         match stream.read(&mut buf) {
+///             Ok(0) => return Ok(()),
+///             Ok(n) => { /* store `buf[..n]` */ },
+///             Err(ref e) if e.get_ref().map_or(false, |e| e.is::<ClientGone>()) => {
+///                 /* discard what was stored */
+///                 return Ok(());
+///             }
+///             Err(e) => return Err(e),
+///         }
+///     }
+/// }
+/// ```
+pub struct DataStream {
+    pub(crate) inner: InnerStream,
+    hooks: Vec<Box<ReadHook>>,
+    // Bytes that were read from `inner` but handed back to be read again or
+    // read ahead of the caller while coalescing reads.
+    pending: Cursor<Vec<u8>>,
+    // Reads smaller than this are served from a buffer of this size.
+    read_buffer: usize,
+    // The framing state of a chunked body.
+    chunks: ChunkTracker,
+    // The number of bytes read from `inner` and the number it should yield
+    // in total, if its body is sized.
+    consumed: u64,
+    expected: Option<u64>,
+}
+
+impl DataStream {
+    #[inline(always)]
+    pub(crate) fn new(inner: InnerStream) -> DataStream {
+        let expected = match BodyKind::of(inner.get_ref().1) {
+            BodyKind::Sized(n) => Some(inner.get_ref().0.get_ref().len() as u64 + n),
+            _ => None
+        };
+
+        DataStream {
+            inner: inner,
+            hooks: vec![],
+            pending: Cursor::new(vec![]),
+            read_buffer: 0,
+            chunks: ChunkTracker::default(),
+            consumed: 0,
+            expected: expected,
+        }
+    }
+
+    /// Coalesces reads of fewer than `capacity` bytes: such a read instead
+    /// reads up to `capacity` bytes from the underlying stream, and subsequent
+    /// reads are served from those bytes until they run out. Hooks observe
+    /// bytes as they are read from the underlying stream. A `capacity` of `0`
+    /// disables coalescing.
+    #[inline]
+    pub(crate) fn with_read_buffer(mut self, capacity: usize) -> DataStream {
+        self.read_buffer = capacity;
+        self
+    }
+
+    /// Continues tracking the framing of a chunked body from `chunks`, the
+    /// state left by reads made before this stream was created.
+    #[inline]
+    pub(crate) fn with_chunks(mut self, chunks: ChunkTracker) -> DataStream {
+        self.chunks = chunks;
+        self
+    }
+
+    /// Reads from the stream into `out` until the delimiter `delim` is found
+    /// or the end of the stream is reached. Returns `true` if the delimiter
+    /// was found and `false` otherwise.
+    ///
+    /// The bytes preceding the delimiter are appended to `out`. The delimiter
+    /// itself is consumed from the stream but is not appended to `out`. Bytes
+    /// following the delimiter remain in the stream and can be read normally.
+    /// A delimiter that spans multiple reads from the underlying stream is
+    /// found as if it had been read all at once. An empty `delim` is found
+    /// immediately.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let mut stream = data.open();
+    ///
+    ///     // Read the header, which is terminated by a blank line.
+    ///     let mut header = vec![];
+    ///     if stream.read_until_delim(b"\r\n\r\n", &mut header)? {
+    ///         // Read the remainder of the body.
+    ///         let mut rest = vec![];
+    ///         stream.read_to_end(&mut rest)?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read_until_delim(&mut self, delim: &[u8], out: &mut Vec<u8>) -> io::Result<bool> {
+        if delim.is_empty() {
+            return Ok(true);
+        }
+
+        let start = out.len();
+        let mut buf = [0; 4096];
+        loop {
+            let n = match self.read(&mut buf) {
+                Ok(0) => return Ok(false),
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            // The delimiter may begin in bytes we appended in a previous read.
+            let search_start = ::std::cmp::max(start, out.len().saturating_sub(delim.len() - 1));
+            out.extend_from_slice(&buf[..n]);
+            let found = out[search_start..].windows(delim.len()).position(|w| w == delim);
+            if let Some(i) = found {
+                let delim_start = search_start + i;
+                let rest = out.split_off(delim_start + delim.len());
+                out.truncate(delim_start);
+                self.unread(rest);
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Fills `buf` completely with bytes from the stream, returning `Ok(true)`.
+    /// If the stream has ended before any bytes are read, returns `Ok(false)`.
+    ///
+    /// # Errors
+    ///
+    /// If the stream ends after `buf` has been partially filled, returns an
+    /// error of kind `UnexpectedEof`. Other I/O errors are returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let mut stream = data.open();
+    ///     let mut record = [0; 16];
+    ///     while stream.read_exact_or_eof(&mut record)? {
+    ///         println!("record: {:?}", record);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn read_exact_or_eof(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+        ReadExt::read_exact_or_eof(self, buf)
+    }
+
+    /// Reads exactly `T::SIZE` bytes from the stream and parses them as a `T`.
+    /// See [`FromBytes`](/rocket/data/trait.FromBytes.html).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `UnexpectedEof` if the stream ends before
+    /// `T::SIZE` bytes are read, even if it ends before any are. Errors
+    /// returned by `T::from_bytes` and other I/O errors are returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    /// use rocket::data::BigEndian;
+    ///
+    /// fn handler(data: Data) -> io::Result<Vec<u8>> {
+    ///     let mut stream = data.open();
+    ///     let (kind, BigEndian(len)): (u8, BigEndian<u32>) = stream.read_struct()?;
+    ///
+    ///     let mut payload = vec![];
+    ///     stream.take(len as u64).read_to_end(&mut payload)?;
+    ///     Ok(payload)
+    /// }
+    /// ```
+    pub fn read_struct<T: FromBytes>(&mut self) -> io::Result<T> {
+        let mut bytes = vec![0; T::SIZE];
+        if !self.read_exact_or_eof(&mut bytes)? {
+            let msg = "stream ended before a structure could be read";
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, msg));
+        }
+
+        T::from_bytes(&bytes)
+    }
+
+    /// Reads the remainder of the stream into a `String`, reading at most
+    /// `limit` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the remainder of the stream is
+    /// longer than `limit` bytes or isn't valid UTF-8. Other I/O errors are
+    /// returned as-is. In either case, the bytes read are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<String> {
+    ///     let mut stream = data.open();
+    ///
+    ///     // Skip a 4-byte header, then read the rest as text.
+    ///     stream.read_exact(&mut [0; 4])?;
+    ///     stream.read_string_limited(1024)
+    /// }
+    /// ```
+    pub fn read_string_limited(&mut self, limit: u64) -> io::Result<String> {
+        let mut bytes = vec![];
+        self.take(limit.saturating_add(1)).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > limit {
+            let msg = "stream exceeds the string limit";
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Returns an iterator over the frames in this stream, where each frame is
+    /// a 4-byte, big-endian length `n` followed by `n` bytes of payload. Each
+    /// item is the payload of one frame.
+    ///
+    /// The iterator ends cleanly if the stream ends between frames. If the
+    /// stream ends in the middle of a length prefix or a payload, the iterator
+    /// yields an error of kind `UnexpectedEof` and then ends. The iterator also
+    /// ends after yielding any other I/O error.
+    ///
+    /// Payload buffers grow as data is received; a large length prefix alone
+    /// does not cause a large allocation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     for frame in data.open().frames_u32_be() {
+    ///         println!("frame: {:?}", frame?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn frames_u32_be(self) -> impl Iterator<Item = io::Result<Vec<u8>>> {
+        U32Frames { stream: self, done: false }
+    }
+
+    /// Returns an iterator over the frames in this stream, where each frame is
+    /// a header of a variable length followed by a payload whose length the
+    /// header declares. Each item is the header and payload of one frame.
+    ///
+    /// The header is read one byte at a time. After each byte, `parse` is
+    /// called with the header read so far and returns `Ok(None)` if the header
+    /// is incomplete or `Ok(Some(n))` if it declares a payload of `n` bytes.
+    /// An error returned by `parse` is yielded as-is. A header longer than
+    /// 64 bytes yields an error of kind `InvalidData`.
+    ///
+    /// As with [`frames_u32_be`](#method.frames_u32_be), the iterator ends
+    /// cleanly if the stream ends between frames and yields an error of kind
+    /// `UnexpectedEof` if it ends in the middle of one. The iterator ends
+    /// after yielding any error.
+    ///
+    /// # Example
+    ///
+    /// Reading frames made of a 1-byte type and a LEB128 varint length:
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// fn varint_header(header: &[u8]) -> io::Result<Option<u64>> {
+    ///     let last = header[header.len() - 1];
+    ///     if header.len() == 1 || last & 0x80 != 0 {
+    ///         return Ok(None);
+    ///     }
+    ///
+    ///     let len = header[1..].iter().enumerate()
+    ///         .fold(0, |len, (i, byte)| len | ((byte & 0x7f) as u64) << (7 * i));
+    ///
+    ///     Ok(Some(len))
+    /// }
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     for frame in data.open().frames(varint_header) {
+    ///         let (header, payload) = frame?;
+    ///         println!("type {}: {:?}", header[0], payload);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn frames<F>(self, parse: F) -> impl Iterator<Item = io::Result<(Vec<u8>, Vec<u8>)>>
+        where F: FnMut(&[u8]) -> io::Result<Option<u64>>
+    {
+        Frames { stream: self, parse: parse, done: false }
+    }
+
+    /// Sets the read timeout of the network connection underlying this stream
+    /// to `timeout`. A `None` value means reads block indefinitely.
+    ///
+    /// Rocket sets a read timeout of 5 seconds when a request is received.
+    /// This method allows the timeout to be adjusted while the body is being
+    /// read: for instance, to wait generously for the first bytes of a body,
+    /// then strictly for the remainder. For locally dispatched requests, this
+    /// method has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the timeout could not be set on the connection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use std::time::Duration;
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<()> {
+    ///     let mut stream = data.open();
+    ///     stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+    ///
+    ///     let mut first = [0; 1024];
+    ///     let n = stream.read(&mut first)?;
+    ///
+    ///     stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    ///     let mut rest = vec![];
+    ///     stream.read_to_end(&mut rest)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[inline]
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.net_stream().set_read_timeout(timeout)
+    }
+
+    /// Enables or disables Nagle's algorithm (`TCP_NODELAY`) on the connection
+    /// underlying this stream, overriding the `tcp_nodelay` configuration
+    /// parameter for the remainder of the connection. For locally dispatched
+    /// requests, this method has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the option could not be set. This is always the
+    /// case for TLS connections, whose socket isn't accessible.
+    #[inline]
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.net_stream().set_nodelay(nodelay)
+    }
+
+    /// Returns `false` if the client is known to have disconnected and `true`
+    /// otherwise. See
+    /// [`Data::is_peer_connected`](/rocket/data/struct.Data.html#method.is_peer_connected)
+    /// for details.
+    #[inline]
+    pub fn is_peer_connected(&self) -> bool {
+        self.net_stream().is_peer_connected()
+    }
+
+    /// Returns the number of reads made from the network connection for this
+    /// body so far, if reads are being counted. See
+    /// [`Data::read_syscalls`](/rocket/data/struct.Data.html#method.read_syscalls)
+    /// for details.
+    #[inline]
+    pub fn read_syscalls(&self) -> Option<usize> {
+        self.net_stream().read_syscalls()
+    }
+
+    /// Reads and discards the remainder of the stream, then checks that the
+    /// stream yielded exactly as many bytes as its body declared. Returns the
+    /// total number of bytes the stream yielded, including those read before
+    /// this method was called.
+    ///
+    /// This gives a handler that must consume a body completely a single call
+    /// to both drain and validate it. Bytes that were pushed back into the
+    /// stream internally, as by a streaming parser, are counted once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the body is sized and the
+    /// stream yielded a different number of bytes than declared by its
+    /// `Content-Length`. Errors that end the body early, such as a
+    /// [`ClientGone`](/rocket/data/struct.ClientGone.html) error for a sized
+    /// body or a `MalformedChunk` error for a chunked one, are returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{self, Read};
+    /// use rocket::Data;
+    ///
+    /// fn handler(data: Data) -> io::Result<u64> {
+    ///     let mut stream = data.open();
+    ///     let mut header = [0; 8];
+    ///     stream.read_exact(&mut header)?;
+    ///
+    ///     // Ignore the rest of the body, but insist that it was all sent.
+    ///     stream.finish()
+    /// }
+    /// ```
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.copy_to(&mut io::sink())?;
+        match self.expected {
+            Some(expected) if expected != self.consumed => {
+                let msg = format!("body yielded {} bytes but declared {}", self.consumed, expected);
+                Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+            }
+            _ => Ok(self.consumed)
+        }
+    }
+
+    /// Reads from the stream into `buf` without blocking, returning the number
+    /// of bytes read or `None` if no bytes were available yet. As with `read`,
+    /// `Some(0)` signals the end of the stream.
+    ///
+    /// This allows a handler to interleave reading the body with other work,
+    /// returning to the body when `None` is returned. Bytes that were already
+    /// received are always returned. The connection is returned to blocking
+    /// mode before this method returns, so `read` blocks as usual.
+    ///
+    /// Chunked bodies can't be read without blocking: reads from them that
+    /// require the connection block as `read` does. For locally dispatched
+    /// requests, reads never block.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection couldn't be made non-blocking. This
+    /// is always the case for TLS connections, whose socket isn't accessible.
+    /// Other I/O errors are returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    ///
+    /// # fn do_other_work() {  }
+    /// fn handler(data: Data) -> io::Result<Vec<u8>> {
+    ///     let (mut stream, mut body, mut buf) = (data.open(), vec![], [0; 4096]);
+    ///     loop {
+    ///         match stream.try_read(&mut buf)? {
+    ///             Some(0) => return Ok(body),
+    ///             Some(n) => body.extend_from_slice(&buf[..n]),
+    ///             None => do_other_work(),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn try_read(&mut self, buf: &mut [u8]) -> io::Result<Option<usize>> {
+        let chunked = match *self.inner.get_ref().1 {
+            HttpReader::ChunkedReader(..) => true,
+            _ => false
+        };
+
+        // A chunked body interrupted in the middle of a chunk's size can't be
+        // resumed by Hyper, so its reads aren't made non-blocking.
+        if buf.is_empty() || chunked || self.has_buffered() {
+            return self.read(buf).map(Some);
+        }
+
+        self.net_stream().set_nonblocking(true)?;
+        let result = self.read(buf);
+        if let Err(e) = self.net_stream().set_nonblocking(false) {
+            error_!("Failed to restore stream to blocking: {:?}", e);
+        }
+
+        match result {
+            Ok(n) => Ok(Some(n)),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns the network stream underlying this stream.
+    #[inline(always)]
+    pub(crate) fn net_stream(&self) -> &NetStream {
+        self.inner.get_ref().1.get_ref().get_ref().1
+    }
+
+    /// Pushes `bytes` back into the stream so that they are the next bytes
+    /// read, ahead of any bytes that were previously pushed back.
+    pub(crate) fn unread(&mut self, mut bytes: Vec<u8>) {
+        let pos = self.pending.position() as usize;
+        bytes.extend_from_slice(&self.pending.get_ref()[pos..]);
+        self.pending = Cursor::new(bytes);
+    }
+
+    #[inline(always)]
+    fn has_pending(&self) -> bool {
+        (self.pending.position() as usize) < self.pending.get_ref().len()
+    }
+
+    /// Returns `true` if bytes of the body have already been read into memory
+    /// from the connection but not yet out of this stream.
+    ///
+    /// Hyper's buffer may hold bytes past the end of the body, such as a
+    /// trailing CRLF or a pipelined request, so it only counts while a sized
+    /// body has bytes remaining.
+    fn has_buffered(&self) -> bool {
+        fn unread(cursor: &Cursor<Vec<u8>>) -> bool {
+            (cursor.position() as usize) < cursor.get_ref().len()
+        }
+
+        let (peeked, body) = self.inner.get_ref();
+        let body_unread = match *body {
+            HttpReader::SizedReader(_, 0) => false,
+            _ => unread(body.get_ref().get_ref().0)
+        };
+
+        self.has_pending() || unread(peeked) || body_unread
+    }
+
+    /// Writes the remainder of the stream to `file`, returning the number of
+    /// bytes written. Bytes still on the connection are moved to `file` with
+    /// `splice`, without being copied through userspace. Returns `None`,
+    /// having read nothing, if the stream can't be spliced: if the body isn't
+    /// sized, the connection isn't plaintext TCP, or a hook observes bytes.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn splice_to(&mut self, file: &File) -> Option<io::Result<u64>> {
+        let socket = match *self.inner.get_ref().1 {
+            HttpReader::SizedReader(ref stream, _) => stream.get_ref().1.raw_fd(),
+            _ => None
+        };
+
+        match socket {
+            Some(fd) if !self.hooks.iter().any(|hook| hook.observes_bytes()) => {
+                Some(self.splice_from(fd, file))
+            }
+            _ => None
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn splice_from(&mut self, socket: ::std::os::unix::io::RawFd, file: &File) -> io::Result<u64> {
+        // Bytes that were already read from the socket are written normally.
+        let (mut written, mut buf) = (0, [0; 4096]);
+        while self.has_buffered() {
+            let n = self.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+
+            (&*file).write_all(&buf[..n])?;
+            written += n as u64;
+        }
+
+        let pipe = Pipe::new()?;
+        loop {
+            let remaining = match *self.inner.get_ref().1 {
+                HttpReader::SizedReader(_, n) => n,
+                _ => 0
+            };
+
+            if remaining == 0 {
+                break;
+            }
+
+            for hook in self.hooks.iter_mut() {
+                hook.before_read()?;
+            }
+
+            let len = ::std::cmp::min(remaining, SPLICE_LEN as u64) as usize;
+            let n = match pipe.fill_from(socket, len) {
+                Ok(0) => self.check_client_gone(None)?,
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(ref e) if splice::is_unsupported(e) => {
+                    return Ok(written + self.copy_to(&mut &*file)?);
+                }
+                Err(e) => self.check_client_gone(Some(e))?,
+            };
+
+            pipe.drain_to(file, n)?;
+            if let HttpReader::SizedReader(_, ref mut remaining) = *self.inner.get_mut().1 {
+                *remaining -= n as u64;
+            }
+
+            written += n as u64;
+            self.consumed += n as u64;
+            for hook in self.hooks.iter_mut() {
+                hook.after_splice(n)?;
+            }
+        }
+
+        for hook in self.hooks.iter_mut() {
+            hook.after_splice(0)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Reads from the underlying stream, running the hooks around the read.
+    fn read_inner(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        for hook in self.hooks.iter_mut() {
+            hook.before_read()?;
+        }
+
+        // Reads are served from the peek buffer until it runs out.
+        let reaches_body = {
+            let peeked = self.inner.get_ref().0;
+            peeked.position() as usize >= peeked.get_ref().len()
+        };
+
+        if reaches_body {
+            self.chunks.check()?;
+        }
+
+        let mut result = self.inner.read(buf);
+        if reaches_body {
+            result = self.chunks.record(self.inner.get_ref().1, result);
+        }
+
+        let n = match result {
+            Ok(0) if !buf.is_empty() => self.check_client_gone(None)?,
+            Ok(n) => n,
+            Err(e) => self.check_client_gone(Some(e))?,
+        };
+
+        self.consumed += n as u64;
+        for hook in self.hooks.iter_mut() {
+            hook.after_read(&buf[..n])?;
+        }
+
+        Ok(n)
+    }
+
+    /// Converts the end of the underlying stream, or the error `e` ending it,
+    /// into a `ClientGone` error if a sized body still has bytes remaining.
+    /// Hyper reports a sized body cut short as an `Other` "early eof" error.
+    fn check_client_gone(&self, e: Option<io::Error>) -> io::Result<usize> {
+        let remaining = match BodyKind::of(self.inner.get_ref().1) {
+            BodyKind::Sized(n) if n > 0 => n,
+            _ => return e.map_or(Ok(0), Err)
+        };
+
+        match e {
+            Some(e) => match e.kind() {
+                io::ErrorKind::UnexpectedEof | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted | io::ErrorKind::BrokenPipe => {}
+                io::ErrorKind::Other if e.to_string() == "early eof" => {}
+                _ => return Err(e)
+            },
+            None => {}
+        }
+
+        let error = ClientGone { remaining: remaining };
+        Err(io::Error::new(io::ErrorKind::ConnectionAborted, error))
+    }
+
+    /// Adds `hook` to the hooks run on every read from this stream.
+    #[inline]
+    pub(crate) fn with_hook<H: ReadHook + 'static>(self, hook: H) -> DataStream {
+        self.with_boxed_hook(Box::new(hook))
+    }
+
+    /// Adds the already boxed `hook` to the hooks run on every read.
+    #[inline]
+    pub(crate) fn with_boxed_hook(mut self, hook: Box<ReadHook>) -> DataStream {
+        self.hooks.push(hook);
+        self
+    }
+}
+
+/// Iterator returned by `DataStream::frames_u32_be`.
+struct U32Frames {
+    stream: DataStream,
+    done: bool,
+}
+
+impl U32Frames {
+    fn next_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut prefix = [0; 4];
+        if !self.stream.read_exact_or_eof(&mut prefix)? {
+            return Ok(None);
+        }
+
+        let len = (prefix[0] as u64) << 24 | (prefix[1] as u64) << 16
+            | (prefix[2] as u64) << 8 | prefix[3] as u64;
+
+        read_payload(&mut self.stream, len).map(Some)
+    }
+}
+
+impl Iterator for U32Frames {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_frame() {
+            Ok(Some(frame)) => Some(Ok(frame)),
+            Ok(None) => { self.done = true; None }
+            Err(e) => { self.done = true; Some(Err(e)) }
+        }
+    }
+}
+
+/// The maximum length of a frame header read by `DataStream::frames`.
+const MAX_FRAME_HEADER: usize = 64;
+
+fn read_payload(stream: &mut DataStream, len: u64) -> io::Result<Vec<u8>> {
+    let mut payload = vec![];
+    let n = stream.take(len).read_to_end(&mut payload)?;
+    if (n as u64) < len {
+        let msg = "stream ended in the middle of a frame";
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, msg));
+    }
+
+    Ok(payload)
+}
+
+/// Iterator returned by `DataStream::frames`.
+struct Frames<F> {
+    stream: DataStream,
+    parse: F,
+    done: bool,
+}
+
+impl<F: FnMut(&[u8]) -> io::Result<Option<u64>>> Frames<F> {
+    fn next_frame(&mut self) -> io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let mut header = vec![];
+        let len = loop {
+            let mut byte = [0];
+            if !self.stream.read_exact_or_eof(&mut byte)? {
+                if header.is_empty() {
+                    return Ok(None);
+                }
+
+                let msg = "stream ended in the middle of a frame header";
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, msg));
+            }
+
+            header.push(byte[0]);
+            if let Some(len) = (self.parse)(&header)? {
+                break len;
+            }
+
+            if header.len() >= MAX_FRAME_HEADER {
+                let msg = "frame header exceeds the maximum length";
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        };
+
+        let payload = read_payload(&mut self.stream, len)?;
+        Ok(Some((header, payload)))
+    }
+}
+
+impl<F: FnMut(&[u8]) -> io::Result<Option<u64>>> Iterator for Frames<F> {
+    type Item = io::Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_frame() {
+            Ok(Some(frame)) => Some(Ok(frame)),
+            Ok(None) => { self.done = true; None }
+            Err(e) => { self.done = true; Some(Err(e)) }
+        }
+    }
+}
 
 // TODO: Have a `BufRead` impl for `DataStream`. At the moment, this isn't
 // possible since Hyper's `HttpReader` doesn't implement `BufRead`.
@@ -22,7 +962,22 @@ impl Read for DataStream {
     #[inline(always)]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         trace_!("DataStream::read()");
-        self.0.read(buf)
+        if self.has_pending() {
+            return self.pending.read(buf);
+        }
+
+        if !buf.is_empty() && buf.len() < self.read_buffer {
+            // Reuse the allocation of the exhausted pending buffer.
+            let mut chunk = ::std::mem::replace(self.pending.get_mut(), vec![]);
+            chunk.clear();
+            chunk.resize(self.read_buffer, 0);
+            let n = self.read_inner(&mut chunk)?;
+            chunk.truncate(n);
+            self.pending = Cursor::new(chunk);
+            return self.pending.read(buf);
+        }
+
+        self.read_inner(buf)
     }
 }
 
@@ -50,6 +1005,10 @@ pub fn kill_stream(stream: &mut BodyReader) {
 
 impl Drop for DataStream {
     fn drop(&mut self) {
-        kill_stream(&mut self.0.get_mut().1);
+        for hook in self.hooks.iter_mut() {
+            hook.finish();
+        }
+
+        kill_stream(&mut self.inner.get_mut().1);
     }
 }
@@ -0,0 +1,31 @@
+use std::io;
+use std::time::Instant;
+
+use super::data_stream::ReadHook;
+
+/// A read hook that fails every read once an instant has passed.
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// Creates a deadline that passes at `at`.
+    pub fn new(at: Instant) -> Deadline {
+        Deadline { at: at }
+    }
+}
+
+impl ReadHook for Deadline {
+    fn before_read(&mut self) -> io::Result<()> {
+        if Instant::now() >= self.at {
+            let msg = "the deadline for reading the request body has passed";
+            return Err(io::Error::new(io::ErrorKind::TimedOut, msg));
+        }
+
+        Ok(())
+    }
+
+    fn observes_bytes(&self) -> bool {
+        false
+    }
+}
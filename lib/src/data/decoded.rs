@@ -0,0 +1,130 @@
+use std::io::{self, Read};
+use std::ascii::AsciiExt;
+use std::ops::Deref;
+
+use outcome::Outcome::*;
+use http::Status;
+use request::Request;
+use super::{Data, FromData, Outcome};
+use super::from_data::read_error_status;
+
+/// The default limit, in bytes, on the size of a decoded body.
+const LIMIT: u64 = 1 << 20;
+
+/// A data guard that removes the `Content-Encoding` of the body before handing
+/// it to the data guard `T`.
+///
+/// `T` sees the request exactly as it was received, including its
+/// `Content-Type`, so guards that dispatch on the content type continue to do
+/// so on the declared type while parsing the decoded body. Bodies without a
+/// `Content-Encoding` or with the `identity` encoding are passed through
+/// untouched. `gzip` bodies are decoded into memory, up to the `decoded` limit
+/// (1MiB by default). A body with several codings, such as `gzip, gzip`, is
+/// decoded in the reverse of the order they were applied in. A coding that
+/// Rocket can't decode or that isn't listed in the `accepted_content_encodings`
+/// configuration parameter fails with `415 Unsupported Media Type`, and a
+/// decoded body that exceeds the limit fails with `413 Payload Too Large`.
+///
+/// This guard is only available when the `gzip` feature is enabled.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/events", data = "<events>")]
+/// fn events(events: Decoded<Json<Vec<Event>>>) { ... }
+/// ```
+#[derive(Debug)]
+pub struct Decoded<T>(pub T);
+
+impl<T> Decoded<T> {
+    /// Consumes `self` and returns the value produced by the inner guard.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Decoded<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// The error returned by the `Decoded` data guard.
+#[derive(Debug)]
+pub enum DecodeError<E> {
+    /// The body's `Content-Encoding` lists a coding that Rocket doesn't know
+    /// how to decode or that isn't accepted by the configuration.
+    Unsupported(String),
+    /// Decoding the body failed or the decoded body exceeded the limit.
+    Io(io::Error),
+    /// The inner data guard failed.
+    Inner(E),
+}
+
+/// Returns the canonical name of `coding`: trimmed, lowercased, and with the
+/// `x-gzip` alias replaced by `gzip`.
+fn normalize(coding: &str) -> String {
+    let coding = coding.trim().to_ascii_lowercase();
+    if coding == "x-gzip" { "gzip".to_string() } else { coding }
+}
+
+fn decode(data: Data, limit: u64) -> Result<Data, (Status, io::Error)> {
+    let mut decoded = vec![];
+    let result = data.open_gzip()
+        .and_then(|stream| stream.take(limit.saturating_add(1)).read_to_end(&mut decoded));
+
+    match result {
+        Ok(n) if n as u64 > limit => {
+            let msg = "decoded body exceeds the limit";
+            Err((Status::PayloadTooLarge, io::Error::new(io::ErrorKind::InvalidData, msg)))
+        }
+        Ok(_) => Ok(Data::local(decoded)),
+        Err(e) => Err((read_error_status(&e, Status::BadRequest), e)),
+    }
+}
+
+impl<T: FromData> FromData for Decoded<T> {
+    type Error = DecodeError<T::Error>;
+
+    fn from_data(request: &Request, data: Data) -> Outcome<Self, Self::Error> {
+        let codings: Vec<String> = request.headers().get("Content-Encoding")
+            .flat_map(|header| header.split(','))
+            .map(normalize)
+            .filter(|coding| !coding.is_empty() && coding != "identity")
+            .collect();
+
+        // Check every coding before decoding any so that a body is never
+        // partly decoded only to be rejected.
+        let accepted = &request.config().accepted_content_encodings;
+        for coding in codings.iter() {
+            if coding != "gzip" || !accepted.iter().any(|a| normalize(a) == *coding) {
+                warn_!("Unsupported content encoding: {}", coding);
+                let error = DecodeError::Unsupported(coding.clone());
+                return Failure((Status::UnsupportedMediaType, error));
+            }
+        }
+
+        // Codings are listed in the order they were applied.
+        let limit = request.limits().get("decoded").unwrap_or(LIMIT);
+        let mut data = data;
+        for coding in codings.iter().rev() {
+            data = match decode(data, limit) {
+                Ok(decoded) => decoded,
+                Err((status, e)) => {
+                    error_!("Failed to decode {} body: {:?}", coding, e);
+                    return Failure((status, DecodeError::Io(e)));
+                }
+            };
+        }
+
+        match T::from_data(request, data) {
+            Success(value) => Success(Decoded(value)),
+            Failure((status, e)) => Failure((status, DecodeError::Inner(e))),
+            Forward(data) => Forward(data),
+        }
+    }
+}
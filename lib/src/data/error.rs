@@ -0,0 +1,214 @@
+use std::{io, fmt};
+use std::net::SocketAddr;
+
+use config::TlsVersion;
+
+/// An error that occurs while retrieving the body of an incoming request.
+#[derive(Debug)]
+pub enum DataError {
+    /// The connection isn't of a type Rocket knows how to read a body from.
+    /// Additional types can be registered with
+    /// [`Rocket::recognize_stream`](/rocket/struct.Rocket.html#method.recognize_stream).
+    NotHttpStream,
+    /// The read timeout could not be set on the connection.
+    TimeoutSetFailed(io::Error),
+    /// The position of the body in the buffer received alongside the request's
+    /// headers, `pos`, lies beyond that buffer's length, `len`.
+    Underflow {
+        /// The position of the start of the body.
+        pos: usize,
+        /// The length of the buffer.
+        len: usize,
+    },
+    /// A previous request's body is still open on the connection from this
+    /// address, typically because a handler leaked its `Data` or
+    /// `DataStream`. The request is answered with a `503 Service Unavailable`
+    /// and the connection is closed.
+    TooManyOpenStreams(SocketAddr),
+    /// The framing of a chunked body is invalid: a chunk's size couldn't be
+    /// parsed, its data wasn't followed by a CRLF, or the body ended inside
+    /// it. Reads from such a body fail with an `io::Error` of kind
+    /// `InvalidData` wrapping this error.
+    MalformedChunk {
+        /// The zero-based index of the offending chunk.
+        index: u64,
+    },
+    /// The request declared both a `Content-Length` and a chunked
+    /// `Transfer-Encoding`, and the configured `length_conflict` policy is to
+    /// reject such requests. Rocket responds with `400 Bad Request`.
+    ConflictingLength {
+        /// The address of the client, if known.
+        peer: Option<SocketAddr>,
+    },
+    /// Reading the start of the body into the `peek` buffer failed, and the
+    /// configured `peek_error` policy is to fail the request.
+    PeekFailed(io::Error),
+    /// The body arrived over a TLS connection that negotiated a version older
+    /// than the configured `min_tls_version`. Rocket responds with
+    /// `426 Upgrade Required`.
+    TlsVersionTooOld {
+        /// The negotiated version, or `None` if it's older than TLS 1.0.
+        negotiated: Option<TlsVersion>,
+        /// The oldest accepted version.
+        min: TlsVersion,
+    },
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DataError::NotHttpStream => write!(f, "stream is not an HTTP(s) stream"),
+            DataError::TimeoutSetFailed(ref e) => {
+                write!(f, "failed to set the read timeout: {}", e)
+            }
+            DataError::Underflow { pos, len } => {
+                write!(f, "body position {} exceeds buffer length {}", pos, len)
+            }
+            DataError::TooManyOpenStreams(addr) => {
+                write!(f, "a previous request body from {} is still open", addr)
+            }
+            DataError::MalformedChunk { index } => write!(f, "chunk {} is malformed", index),
+            DataError::ConflictingLength { peer: Some(peer) } => {
+                write!(f, "request from {} has both a length and a chunked body", peer)
+            }
+            DataError::ConflictingLength { peer: None } => {
+                write!(f, "request has both a length and a chunked body")
+            }
+            DataError::PeekFailed(ref e) => write!(f, "failed to read the peek buffer: {}", e),
+            DataError::TlsVersionTooOld { negotiated: Some(version), min } => {
+                write!(f, "TLS {} is older than the minimum accepted, TLS {}", version, min)
+            }
+            DataError::TlsVersionTooOld { negotiated: None, min } => {
+                write!(f, "TLS version is older than the minimum accepted, TLS {}", min)
+            }
+        }
+    }
+}
+
+impl ::std::error::Error for DataError {
+    fn description(&self) -> &str {
+        match *self {
+            DataError::NotHttpStream => "stream is not an HTTP(s) stream",
+            DataError::TimeoutSetFailed(_) => "failed to set the read timeout",
+            DataError::Underflow { .. } => "body position exceeds buffer length",
+            DataError::TooManyOpenStreams(_) => "a previous request body is still open",
+            DataError::MalformedChunk { .. } => "a chunk of the body is malformed",
+            DataError::ConflictingLength { .. } => "request has both a length and a chunked body",
+            DataError::PeekFailed(_) => "failed to read the peek buffer",
+            DataError::TlsVersionTooOld { .. } => "TLS version is older than the minimum accepted",
+        }
+    }
+
+    fn cause(&self) -> Option<&::std::error::Error> {
+        match *self {
+            DataError::TimeoutSetFailed(ref e) | DataError::PeekFailed(ref e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+/// The error returned when the client closes the connection before sending
+/// all of a body whose length it declared, typically because it cancelled the
+/// upload.
+///
+/// This error is returned by reads from a
+/// [`DataStream`](/rocket/data/struct.DataStream.html) as the inner error of
+/// an `io::Error` of kind `ConnectionAborted` and can be recovered with
+/// `io::Error::get_ref` and `downcast_ref`. A body that ends normally is never
+/// reported this way, so a handler can safely roll back partial work when it
+/// sees this error and nowhere else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientGone {
+    /// The number of declared bytes that were never received.
+    pub remaining: u64,
+}
+
+impl fmt::Display for ClientGone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "client disconnected with {} body bytes unsent", self.remaining)
+    }
+}
+
+impl ::std::error::Error for ClientGone {
+    fn description(&self) -> &str {
+        "client disconnected mid-body"
+    }
+}
+
+/// The error returned by
+/// [`Data::bytes_limited`](/rocket/data/struct.Data.html#method.bytes_limited)
+/// when a body exceeds the limit it was read with.
+///
+/// Reads from a body limited with
+/// [`Data::limit`](/rocket/data/struct.Data.html#method.limit) fail with this
+/// error as the inner error of an `io::Error` of kind `InvalidData`.
+/// [`read_error_status`](/rocket/data/fn.read_error_status.html) maps it to
+/// `413 Payload Too Large`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyTooLarge {
+    /// The limit the body exceeded.
+    pub limit: usize,
+    /// The number of bytes read before giving up. This is `0` when the
+    /// declared length alone exceeded the limit and `limit + 1` otherwise.
+    pub read: u64,
+    /// The length the client declared for the body, if any.
+    pub declared: Option<u64>,
+}
+
+impl fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "body exceeds the limit of {} bytes", self.limit)
+    }
+}
+
+impl ::std::error::Error for BodyTooLarge {
+    fn description(&self) -> &str {
+        "body exceeds the limit"
+    }
+}
+
+/// The error returned by the streaming form parsers,
+/// [`FormPairs`](/rocket/data/struct.FormPairs.html) and
+/// [`Multipart`](/rocket/data/struct.Multipart.html), when a form exceeds one
+/// of its field limits.
+///
+/// This error is returned as the inner error of an `io::Error` of kind
+/// `InvalidData` and can be recovered with `io::Error::get_ref` and
+/// `downcast_ref`. [`read_error_status`](/rocket/data/fn.read_error_status.html)
+/// maps it to `413 Payload Too Large`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldLimitExceeded {
+    /// A field's name was longer than the limit, in bytes.
+    Name(u64),
+    /// A field's value was longer than the limit, in bytes.
+    Value(u64),
+    /// The form had more fields than the limit.
+    Fields(usize),
+}
+
+impl FieldLimitExceeded {
+    /// Returns this error as the inner error of an `io::Error`.
+    pub(crate) fn into_io(self) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, self)
+    }
+}
+
+impl fmt::Display for FieldLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FieldLimitExceeded::Name(n) => write!(f, "field name exceeds {} bytes", n),
+            FieldLimitExceeded::Value(n) => write!(f, "field value exceeds {} bytes", n),
+            FieldLimitExceeded::Fields(n) => write!(f, "form exceeds {} fields", n),
+        }
+    }
+}
+
+impl ::std::error::Error for FieldLimitExceeded {
+    fn description(&self) -> &str {
+        match *self {
+            FieldLimitExceeded::Name(_) => "field name exceeds the limit",
+            FieldLimitExceeded::Value(_) => "field value exceeds the limit",
+            FieldLimitExceeded::Fields(_) => "form exceeds the field limit",
+        }
+    }
+}
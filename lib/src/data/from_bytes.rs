@@ -0,0 +1,116 @@
+use std::io;
+
+/// Trait implemented by types with a fixed-size binary encoding, for use
+/// with [`DataStream::read_struct`].
+///
+/// `SIZE` is the number of bytes in the encoding, and `from_bytes` parses
+/// exactly that many bytes. Integers have no implementation of their own:
+/// their byte order must be chosen explicitly with the [`BigEndian`] and
+/// [`LittleEndian`] wrappers. `u8`, whose encoding has no byte order,
+/// implements `FromBytes` directly. Tuples of up to four `FromBytes` types
+/// are encoded as the concatenation of their elements.
+///
+/// # Example
+///
+/// A record header consisting of a 2-byte magic number, a 1-byte kind, and a
+/// 4-byte, big-endian payload length:
+///
+/// ```rust
+/// use std::io;
+/// use rocket::data::{FromBytes, BigEndian};
+///
+/// struct Header {
+///     kind: u8,
+///     len: u32,
+/// }
+///
+/// impl FromBytes for Header {
+///     const SIZE: usize = 7;
+///
+///     fn from_bytes(bytes: &[u8]) -> io::Result<Header> {
+///         if &bytes[..2] != b"RK" {
+///             return Err(io::Error::new(io::ErrorKind::InvalidData, "bad magic"));
+///         }
+///
+///         let (kind, BigEndian(len)) = <(u8, BigEndian<u32>)>::from_bytes(&bytes[2..])?;
+///         Ok(Header { kind: kind, len: len })
+///     }
+/// }
+/// ```
+///
+/// [`DataStream::read_struct`]: /rocket/data/struct.DataStream.html#method.read_struct
+/// [`BigEndian`]: /rocket/data/struct.BigEndian.html
+/// [`LittleEndian`]: /rocket/data/struct.LittleEndian.html
+pub trait FromBytes: Sized {
+    /// The number of bytes in the encoding of `Self`.
+    const SIZE: usize;
+
+    /// Parses `bytes`, which are exactly `SIZE` bytes long. Returns an error,
+    /// typically of kind `InvalidData`, if they don't encode a valid `Self`.
+    fn from_bytes(bytes: &[u8]) -> io::Result<Self>;
+}
+
+/// An integer encoded most significant byte first (network byte order).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BigEndian<T>(pub T);
+
+/// An integer encoded least significant byte first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LittleEndian<T>(pub T);
+
+impl FromBytes for u8 {
+    const SIZE: usize = 1;
+
+    #[inline(always)]
+    fn from_bytes(bytes: &[u8]) -> io::Result<u8> {
+        Ok(bytes[0])
+    }
+}
+
+macro_rules! impl_with_byte_order {
+    ($($T:ident),+) => ($(
+        impl FromBytes for BigEndian<$T> {
+            const SIZE: usize = ::std::mem::size_of::<$T>();
+
+            #[inline]
+            fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+                let n = bytes[..Self::SIZE].iter().fold(0, |n, &b| n << 8 | b as $T);
+                Ok(BigEndian(n))
+            }
+        }
+
+        impl FromBytes for LittleEndian<$T> {
+            const SIZE: usize = ::std::mem::size_of::<$T>();
+
+            #[inline]
+            fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+                let n = bytes[..Self::SIZE].iter().rev().fold(0, |n, &b| n << 8 | b as $T);
+                Ok(LittleEndian(n))
+            }
+        }
+    )+)
+}
+
+impl_with_byte_order!(u16, u32, u64);
+
+macro_rules! impl_for_tuple {
+    ($($T:ident),+) => (
+        impl<$($T: FromBytes),+> FromBytes for ($($T,)+) {
+            const SIZE: usize = 0 $(+ $T::SIZE)+;
+
+            #[allow(unused_assignments)]
+            fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+                let mut start = 0;
+                Ok(($({
+                    let value = $T::from_bytes(&bytes[start..(start + $T::SIZE)])?;
+                    start += $T::SIZE;
+                    value
+                },)+))
+            }
+        }
+    )
+}
+
+impl_for_tuple!(A, B);
+impl_for_tuple!(A, B, C);
+impl_for_tuple!(A, B, C, D);
@@ -4,7 +4,8 @@ use outcome::{self, IntoOutcome};
 use outcome::Outcome::*;
 use http::Status;
 use request::Request;
-use data::Data;
+use data::{Data, SpillLimitReached, ShuttingDown, FieldLimitExceeded, BodyTooLarge};
+use data::{ConnectionBudgetExceeded, OffsetMismatch};
 
 /// Type alias for the `Outcome` of a `FromData` conversion.
 pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), Data>;
@@ -30,6 +31,67 @@ impl<'a, S, E> IntoOutcome<S, (Status, E), Data> for Result<S, E> {
     }
 }
 
+/// Returns the status a data guard should fail with when reading the body
+/// fails with `error`: `408 Request Timeout` if the read timed out, `503
+/// Service Unavailable` if a [`SpillLimitReached`] error kept the body from
+/// being written to disk or a [`ShuttingDown`] error ended the read, `413
+/// Payload Too Large` if a form exceeded a [`FieldLimitExceeded`] limit, the
+/// body exceeded its limit with a [`BodyTooLarge`] error, or its connection
+/// exceeded its budget with a [`ConnectionBudgetExceeded`] error, `409
+/// Conflict` if a resumable upload's chunk had the wrong offset, with an
+/// [`OffsetMismatch`] error, and `default` otherwise.
+///
+/// Guards that read the body directly can use this so that clients that stall
+/// mid-body are told so instead of receiving an obscure error. Handlers that
+/// take the guard's `Result` still see the original `io::Error` and may respond
+/// however they choose.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io;
+/// use rocket::http::Status;
+/// use rocket::data::read_error_status;
+///
+/// let timeout = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+/// assert_eq!(read_error_status(&timeout, Status::BadRequest), Status::RequestTimeout);
+///
+/// let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "eof");
+/// assert_eq!(read_error_status(&eof, Status::BadRequest), Status::BadRequest);
+/// ```
+///
+/// [`SpillLimitReached`]: /rocket/data/struct.SpillLimitReached.html
+/// [`ShuttingDown`]: /rocket/data/struct.ShuttingDown.html
+/// [`BodyTooLarge`]: /rocket/data/struct.BodyTooLarge.html
+/// [`FieldLimitExceeded`]: /rocket/data/enum.FieldLimitExceeded.html
+/// [`ConnectionBudgetExceeded`]: /rocket/data/struct.ConnectionBudgetExceeded.html
+/// [`OffsetMismatch`]: /rocket/data/struct.OffsetMismatch.html
+pub fn read_error_status(error: &io::Error, default: Status) -> Status {
+    let inner = error.get_ref();
+    if inner.map_or(false, |e| e.is::<SpillLimitReached>() || e.is::<ShuttingDown>()) {
+        return Status::ServiceUnavailable;
+    }
+
+    if inner.map_or(false, |e| e.is::<FieldLimitExceeded>() || e.is::<BodyTooLarge>()) {
+        return Status::PayloadTooLarge;
+    }
+
+    if inner.map_or(false, |e| e.is::<ConnectionBudgetExceeded>()) {
+        return Status::PayloadTooLarge;
+    }
+
+    if inner.map_or(false, |e| e.is::<OffsetMismatch>()) {
+        return Status::Conflict;
+    }
+
+    // A socket read timeout is reported as `WouldBlock` on Unix platforms and
+    // `TimedOut` on Windows.
+    match error.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => Status::RequestTimeout,
+        _ => default
+    }
+}
+
 /// Trait implemented by data guards to derive a value from request body data.
 ///
 /// # Data Guards
@@ -262,7 +324,7 @@ impl FromData for String {
         let mut string = String::new();
         match data.open().read_to_string(&mut string) {
             Ok(_) => Success(string),
-            Err(e) => Failure((Status::BadRequest, e))
+            Err(e) => Failure((read_error_status(&e, Status::BadRequest), e))
         }
     }
 }
@@ -274,7 +336,7 @@ impl FromData for Vec<u8> {
         let mut bytes = Vec::new();
         match data.open().read_to_end(&mut bytes) {
             Ok(_) => Success(bytes),
-            Err(e) => Failure((Status::BadRequest, e))
+            Err(e) => Failure((read_error_status(&e, Status::BadRequest), e))
         }
     }
 }
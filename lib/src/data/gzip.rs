@@ -0,0 +1,143 @@
+use std::{io, fmt};
+use std::io::{Read, BufReader};
+
+use flate2::Crc;
+use flate2::bufread::DeflateDecoder;
+
+use super::DataStream;
+
+const FHCRC: u8 = 1 << 1;
+const FEXTRA: u8 = 1 << 2;
+const FNAME: u8 = 1 << 3;
+const FCOMMENT: u8 = 1 << 4;
+
+/// The error returned when the trailer of a gzip stream doesn't match the data
+/// that was decompressed, indicating that the stream was corrupted.
+///
+/// This error is returned as the inner error of an `io::Error` of kind
+/// `InvalidData` and can be recovered with `io::Error::get_ref` and
+/// `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GzipChecksumError {
+    /// The CRC32 and length (modulo 2^32) stated by the stream's trailer.
+    pub expected: (u32, u32),
+    /// The CRC32 and length (modulo 2^32) of the data actually decompressed.
+    pub actual: (u32, u32),
+}
+
+impl fmt::Display for GzipChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "gzip checksum mismatch: expected crc {:08x} and length {}, got {:08x} and {}",
+               self.expected.0, self.expected.1, self.actual.0, self.actual.1)
+    }
+}
+
+impl ::std::error::Error for GzipChecksumError {
+    fn description(&self) -> &str {
+        "gzip checksum mismatch"
+    }
+}
+
+/// A stream that decompresses a gzip-encoded request body.
+///
+/// This stream can only be obtained by calling
+/// [Data::open_gzip](/rocket/data/struct.Data.html#method.open_gzip). Only the
+/// first gzip member in the body is decompressed.
+pub struct GzipStream {
+    decoder: DeflateDecoder<BufReader<DataStream>>,
+    crc: Crc,
+    verified: bool,
+}
+
+impl GzipStream {
+    pub(crate) fn new(stream: DataStream) -> io::Result<GzipStream> {
+        let mut reader = BufReader::new(stream);
+        read_header(&mut reader)?;
+        Ok(GzipStream { decoder: DeflateDecoder::new(reader), crc: Crc::new(), verified: false })
+    }
+
+    /// Reads the trailer that follows the compressed data and checks it
+    /// against the decompressed data.
+    fn verify(&mut self) -> io::Result<()> {
+        let mut trailer = [0; 8];
+        self.decoder.get_mut().read_exact(&mut trailer)?;
+        let le_u32 = |b: &[u8]| {
+            (b[0] as u32) | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24
+        };
+
+        let expected = (le_u32(&trailer[..4]), le_u32(&trailer[4..]));
+        let actual = (self.crc.sum(), self.crc.amount());
+        if expected != actual {
+            let error = GzipChecksumError { expected: expected, actual: actual };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+
+        self.verified = true;
+        Ok(())
+    }
+}
+
+impl Read for GzipStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.verified || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let n = self.decoder.read(buf)?;
+        if n == 0 {
+            self.verify()?;
+        }
+
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+fn invalid_header() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid gzip header")
+}
+
+/// Skips bytes through the next zero byte.
+fn skip_zero_terminated<R: Read>(reader: &mut R) -> io::Result<()> {
+    let mut byte = [0];
+    loop {
+        reader.read_exact(&mut byte)?;
+        if byte[0] == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Reads and validates a gzip member header, leaving `reader` at the start of
+/// the compressed data.
+fn read_header<R: Read>(reader: &mut R) -> io::Result<()> {
+    let mut header = [0; 10];
+    reader.read_exact(&mut header).map_err(|_| invalid_header())?;
+    if header[..3] != [0x1f, 0x8b, 8] {
+        return Err(invalid_header());
+    }
+
+    let flags = header[3];
+    if flags & FEXTRA != 0 {
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        let len = (len[0] as u64) | (len[1] as u64) << 8;
+        if io::copy(&mut reader.by_ref().take(len), &mut io::sink())? < len {
+            return Err(invalid_header());
+        }
+    }
+
+    if flags & FNAME != 0 {
+        skip_zero_terminated(reader)?;
+    }
+
+    if flags & FCOMMENT != 0 {
+        skip_zero_terminated(reader)?;
+    }
+
+    if flags & FHCRC != 0 {
+        reader.read_exact(&mut [0; 2])?;
+    }
+
+    Ok(())
+}
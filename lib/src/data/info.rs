@@ -0,0 +1,72 @@
+use std::net::SocketAddr;
+
+#[cfg(feature = "serde")] use serde::ser::{Serialize, Serializer, SerializeStruct};
+
+/// How the length of a request body is delimited on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// The body's length was declared by a `Content-Length` header.
+    Sized,
+    /// The body is delimited by the end of the connection.
+    Eof,
+    /// The request has no body.
+    Empty,
+    /// The body uses chunked transfer encoding.
+    Chunked,
+}
+
+impl Framing {
+    /// Returns the name of this framing in lowercase.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Framing::Sized => "sized",
+            Framing::Eof => "eof",
+            Framing::Empty => "empty",
+            Framing::Chunked => "chunked",
+        }
+    }
+}
+
+/// A snapshot of everything Rocket knows about a request body before it is
+/// read, as returned by [`Data::info`].
+///
+/// With the `serde` feature enabled, this type implements `Serialize`.
+///
+/// [`Data::info`]: /rocket/data/struct.Data.html#method.info
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataInfo {
+    /// How the body is delimited.
+    pub framing: Framing,
+    /// The number of bytes remaining in the body, if known. See
+    /// [`Data::len`](/rocket/data/struct.Data.html#method.len).
+    pub declared_len: Option<u64>,
+    /// The number of bytes in the `peek` buffer.
+    pub peek_len: usize,
+    /// Whether the `peek` buffer contains the entire body.
+    pub is_complete: bool,
+    /// Whether the body arrived over a TLS connection.
+    pub is_secure: bool,
+    /// The address of the peer the body is being read from, if known.
+    pub peer: Option<SocketAddr>,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Framing {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DataInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DataInfo", 6)?;
+        state.serialize_field("framing", &self.framing)?;
+        state.serialize_field("declared_len", &self.declared_len)?;
+        state.serialize_field("peek_len", &self.peek_len)?;
+        state.serialize_field("is_complete", &self.is_complete)?;
+        state.serialize_field("is_secure", &self.is_secure)?;
+        state.serialize_field("peer", &self.peer)?;
+        state.end()
+    }
+}
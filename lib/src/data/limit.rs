@@ -0,0 +1,79 @@
+use std::io;
+
+use super::data_stream::ReadHook;
+use super::BodyTooLarge;
+
+/// Parses a human-readable size, such as `512`, `64KiB`, `100 MiB`, or
+/// `2GiB`, into a number of bytes. The units `B`, `KiB`, `MiB`, and `GiB` are
+/// recognized; a number without a unit is a number of bytes. Returns `None` if
+/// `size` is malformed or overflows a `u64`.
+///
+/// This is the parser used for the `limit` route attribute parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::parse_size;
+///
+/// assert_eq!(parse_size("100"), Some(100));
+/// assert_eq!(parse_size("64KiB"), Some(64 * 1024));
+/// assert_eq!(parse_size("100 MiB"), Some(100 << 20));
+/// assert_eq!(parse_size("1.5GiB"), None);
+/// assert_eq!(parse_size("10MB"), None);
+/// ```
+pub fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let digits = size.find(|c: char| !c.is_digit(10)).unwrap_or(size.len());
+    if digits == 0 {
+        return None;
+    }
+
+    let n: u64 = size[..digits].parse().ok()?;
+    let shift = match size[digits..].trim_left() {
+        "" | "B" => 0,
+        "KiB" => 10,
+        "MiB" => 20,
+        "GiB" => 30,
+        _ => return None
+    };
+
+    n.checked_mul(1 << shift)
+}
+
+/// A read hook that fails the read that takes a body past `limit` bytes.
+pub(crate) struct Limit {
+    limit: usize,
+    read: u64,
+    declared: Option<u64>,
+}
+
+impl Limit {
+    pub(crate) fn new(limit: usize, declared: Option<u64>) -> Limit {
+        Limit { limit: limit, read: 0, declared: declared }
+    }
+
+    fn count(&mut self, n: usize) -> io::Result<()> {
+        self.read += n as u64;
+        if self.read > self.limit as u64 {
+            let read = self.limit as u64 + 1;
+            let error = BodyTooLarge { limit: self.limit, read: read, declared: self.declared };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+
+        Ok(())
+    }
+}
+
+impl ReadHook for Limit {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.count(bytes.len())
+    }
+
+    fn observes_bytes(&self) -> bool {
+        false
+    }
+
+    fn after_splice(&mut self, n: usize) -> io::Result<()> {
+        self.count(n)
+    }
+}
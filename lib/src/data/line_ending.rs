@@ -0,0 +1,120 @@
+use std::io::{self, Read};
+
+use super::DataStream;
+
+/// A line ending that [`Data::open_normalized`] rewrites every line ending
+/// to.
+///
+/// [`Data::open_normalized`]: /rocket/data/struct.Data.html#method.open_normalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// A line feed, `\n`, as used on Unix-like systems.
+    Lf,
+    /// A carriage return followed by a line feed, `\r\n`, as used on Windows
+    /// and in most Internet protocols.
+    CrLf,
+}
+
+impl LineEnding {
+    #[inline]
+    fn as_bytes(&self) -> &'static [u8] {
+        match *self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// A stream that rewrites the line endings of a request body.
+///
+/// This stream can only be obtained by calling
+/// [Data::open_normalized](/rocket/data/struct.Data.html#method.open_normalized).
+/// Every `\r\n`, lone `\r`, and lone `\n` in the body is replaced by the
+/// target line ending. All other bytes are passed through untouched.
+pub struct NormalizedStream {
+    stream: DataStream,
+    target: LineEnding,
+    // Whether the last byte read from `stream` was a `\r`.
+    after_cr: bool,
+    // The normalized bytes not yet returned and the position of the next.
+    out: Vec<u8>,
+    pos: usize,
+}
+
+impl NormalizedStream {
+    pub(crate) fn new(stream: DataStream, target: LineEnding) -> NormalizedStream {
+        NormalizedStream { stream: stream, target: target, after_cr: false, out: vec![], pos: 0 }
+    }
+}
+
+/// Appends `input`, with its line endings rewritten to `target`, to `out`.
+/// `after_cr` records whether the byte preceding `input` was a `\r` so that a
+/// `\r\n` split across two inputs is rewritten as one line ending.
+pub(crate) fn normalize(input: &[u8], target: LineEnding, after_cr: &mut bool, out: &mut Vec<u8>) {
+    for &byte in input {
+        match byte {
+            b'\r' => out.extend_from_slice(target.as_bytes()),
+            b'\n' if *after_cr => {}
+            b'\n' => out.extend_from_slice(target.as_bytes()),
+            _ => out.push(byte),
+        }
+
+        *after_cr = byte == b'\r';
+    }
+}
+
+impl Read for NormalizedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // A read that consists solely of the `\n` of a `\r\n` produces nothing,
+        // so keep reading until there's output or the stream ends.
+        while self.pos >= self.out.len() {
+            let mut chunk = [0; 4096];
+            let n = self.stream.read(&mut chunk)?;
+            if n == 0 {
+                return Ok(0);
+            }
+
+            self.out.clear();
+            self.pos = 0;
+            normalize(&chunk[..n], self.target, &mut self.after_cr, &mut self.out);
+        }
+
+        let n = ::std::cmp::min(buf.len(), self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..(self.pos + n)]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, LineEnding};
+
+    fn normalize_split(pieces: &[&[u8]], target: LineEnding) -> Vec<u8> {
+        let (mut after_cr, mut out) = (false, vec![]);
+        for piece in pieces {
+            normalize(piece, target, &mut after_cr, &mut out);
+        }
+
+        out
+    }
+
+    #[test]
+    fn test_normalize() {
+        let input: &[&[u8]] = &[b"a\r\nb\rc\nd\r\r\ne\n\n"];
+        assert_eq!(normalize_split(input, LineEnding::Lf), b"a\nb\nc\nd\n\ne\n\n");
+        assert_eq!(normalize_split(input, LineEnding::CrLf),
+                   &b"a\r\nb\r\nc\r\nd\r\n\r\ne\r\n\r\n"[..]);
+    }
+
+    #[test]
+    fn test_normalize_split_crlf() {
+        // A `\r\n` split across reads is a single line ending.
+        let input: &[&[u8]] = &[b"a\r", b"\nb\r", b"", b"\n", b"\r", b"c"];
+        assert_eq!(normalize_split(input, LineEnding::Lf), b"a\nb\n\nc");
+        assert_eq!(normalize_split(input, LineEnding::CrLf), b"a\r\nb\r\n\r\nc");
+
+        let input: &[&[u8]] = &[b"a\r", b"b\n", b"\n"];
+        assert_eq!(normalize_split(input, LineEnding::Lf), b"a\nb\n\n");
+    }
+}
@@ -1,10 +1,127 @@
 //! Types and traits for handling incoming body data.
 
 mod data;
+#[cfg(feature = "aead")] mod aead;
+mod arena;
+mod audit;
+mod background;
+mod bom;
+#[cfg(feature = "cas")] mod cas;
+mod chunked;
+mod chunks;
+mod clean_text;
+mod connection;
+#[cfg(feature = "digest")] mod content_digest;
+mod counted;
+#[cfg(feature = "crc")] mod crc;
 mod data_stream;
+mod deadline;
+#[cfg(feature = "gzip")] mod decoded;
+mod error;
+mod info;
+mod limit;
+mod line_ending;
 mod net_stream;
+mod no_body;
+mod from_bytes;
 mod from_data;
+#[cfg(feature = "gzip")] mod gzip;
+mod multipart;
+mod pipe;
+mod probe;
+mod redact;
+mod resumable;
+mod scan;
+mod shutdown;
+#[cfg(feature = "hmac")] mod signed;
+mod slow;
+mod snapshot;
+mod sniff;
+#[cfg(target_os = "linux")] mod splice;
+mod spill;
+mod stats;
+mod throttle;
+#[cfg(feature = "tls")] mod tls;
+mod type_map;
+mod urlencoded;
 
-pub use self::data::Data;
+#[cfg(test)]
+mod tests;
+
+pub use self::data::{Data, Overflow, SinkFailure, ChunkedPeek};
 pub use self::data_stream::DataStream;
-pub use self::from_data::{FromData, Outcome};
+pub use self::arena::Arena;
+pub use self::background::BackgroundReader;
+pub use self::bom::{Bom, BomStripped};
+pub use self::chunks::OffsetChunks;
+pub use self::clean_text::{CleanText, CleanTextError, TextPolicy, Multiline, SingleLine};
+pub use self::connection::ConnectionBudgetExceeded;
+pub use self::counted::Counted;
+#[cfg(feature = "aead")] pub use self::aead::AesGcmStream;
+#[cfg(feature = "cas")] pub use self::cas::HashAlgorithm;
+#[cfg(feature = "digest")] pub use self::content_digest::ContentDigestMismatch;
+pub use self::error::{DataError, ClientGone, BodyTooLarge, FieldLimitExceeded};
+pub use self::info::{DataInfo, Framing};
+pub use self::limit::parse_size;
+pub use self::line_ending::{LineEnding, NormalizedStream};
+pub use self::no_body::NoBody;
+pub use self::from_bytes::{FromBytes, BigEndian, LittleEndian};
+pub use self::from_data::{FromData, Outcome, read_error_status};
+#[cfg(feature = "gzip")] pub use self::gzip::{GzipStream, GzipChecksumError};
+#[cfg(feature = "gzip")] pub use self::decoded::{Decoded, DecodeError};
+pub use self::pipe::ChildOutput;
+pub use self::probe::{Probe, Format};
+pub use self::resumable::{ResumableUpload, OffsetMismatch};
+pub use self::scan::ScanVerdict;
+pub use self::shutdown::{ShutdownHandle, ShuttingDown};
+#[cfg(feature = "hmac")] pub use self::signed::{SignedBody, SigningSecret, SignatureError};
+pub use self::slow::BodyTooSlow;
+pub use self::snapshot::SnapshotMeta;
+pub use self::spill::SpillLimitReached;
+pub use self::stats::{DataStats, AllocStats};
+#[cfg(feature = "tls")] pub use self::tls::TlsInfo;
+pub use self::type_map::TypeMap;
+pub use self::urlencoded::{FormPairs, FormLimits};
+pub use self::multipart::{Multipart, PartLimits, Fields, Field, TempFile};
+
+pub(crate) use self::connection::{OpenStreams, BodyBudgets};
+pub(crate) use self::data_stream::ReadHook;
+pub(crate) use self::spill::SpillLimiter;
+pub(crate) use self::net_stream::{StreamRecognizer, default_recognizers, recognize_dynamic};
+#[cfg(feature = "tls")] pub(crate) use self::tls::TlsServer;
+
+/// Returns a `Data` containing `bytes` as a request body, for fuzzing
+/// [`FromData`](/rocket/data/trait.FromData.html) implementations.
+///
+/// Unlike the `Data` dispatched by a local [`Client`](/rocket/local/), which
+/// is constructed directly from its buffer, the returned `Data` is built by
+/// the same code that builds bodies received from the network: its `peek`
+/// buffer is filled by reading `bytes` as a sized body, and `open` streams
+/// the remainder the same way. A guard that is fuzzed through `fuzz_data`
+/// thus observes exactly what it would observe in production.
+///
+/// This function is only available when the `fuzz` feature is enabled.
+///
+/// # Example
+///
+/// A `cargo-fuzz` target for a custom guard, `MyGuard`, might look as follows:
+///
+/// ```rust,ignore
+/// #![no_main]
+/// #[macro_use] extern crate libfuzzer_sys;
+/// extern crate rocket;
+///
+/// use rocket::data::{self, FromData};
+/// use rocket::local::Client;
+///
+/// fuzz_target!(|bytes: &[u8]| {
+///     let client = Client::new(rocket::ignite()).unwrap();
+///     let request = client.post("/");
+///     let _ = MyGuard::from_data(request.inner(), data::fuzz_data(bytes));
+/// });
+/// ```
+#[cfg(feature = "fuzz")]
+#[inline]
+pub fn fuzz_data(bytes: &[u8]) -> Data {
+    Data::fuzz(bytes)
+}
@@ -0,0 +1,562 @@
+use std::io::{self, Read, Write};
+use std::ascii::AsciiExt;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::collections::HashMap;
+use std::time::Duration;
+use std::env;
+
+use super::{Data, DataStream, FieldLimitExceeded};
+use super::spill::SpillLimiter;
+use http::ContentType;
+use request::Request;
+
+static SPILL_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// The maximum size of the headers of a single part.
+const HEADER_LIMIT: u64 = 8 * 1024;
+
+/// Size limits applied by [`Multipart::into_fields`].
+///
+/// Text fields are parts without a `filename`; they're collected into memory
+/// and are limited by `text`. File fields are parts with a `filename`; they're
+/// written to temporary files and are limited by `file`. The length of every
+/// field's name is limited by `name`, and the number of fields by `fields`.
+///
+/// [`Multipart::into_fields`]: /rocket/data/struct.Multipart.html#method.into_fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartLimits {
+    text: u64,
+    file: u64,
+    name: u64,
+    fields: usize,
+}
+
+impl Default for PartLimits {
+    /// A limit of 64KiB for text fields, 8MiB for file fields, 1KiB for
+    /// names, and 1000 fields.
+    fn default() -> PartLimits {
+        PartLimits { text: 64 * 1024, file: 8 * 1024 * 1024, name: 1024, fields: 1000 }
+    }
+}
+
+impl PartLimits {
+    /// Sets the maximum size, in bytes, of a text field.
+    #[inline]
+    pub fn text(mut self, limit: u64) -> Self {
+        self.text = limit;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of a file field.
+    #[inline]
+    pub fn file(mut self, limit: u64) -> Self {
+        self.file = limit;
+        self
+    }
+
+    /// Sets the maximum length, in bytes, of a field's name.
+    #[inline]
+    pub fn name(mut self, limit: u64) -> Self {
+        self.name = limit;
+        self
+    }
+
+    /// Sets the maximum number of fields.
+    #[inline]
+    pub fn fields(mut self, limit: usize) -> Self {
+        self.fields = limit;
+        self
+    }
+}
+
+/// A file field that has been written to a temporary file.
+///
+/// The temporary file is removed when the `TempFile` is dropped unless it is
+/// first moved with [`persist_to`](#method.persist_to).
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+    file_name: Option<String>,
+    content_type: Option<ContentType>,
+    len: u64,
+    persisted: bool,
+}
+
+impl TempFile {
+    /// Returns the path to the temporary file.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the `filename` the client sent for this part, if any. The
+    /// name is client-controlled and must not be trusted as a path.
+    #[inline(always)]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_ref().map(|s| s.as_str())
+    }
+
+    /// Returns the `Content-Type` the client sent for this part, if any.
+    #[inline(always)]
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+
+    /// Returns the size of the file in bytes.
+    #[inline(always)]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Moves the temporary file to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be moved. This is typically the
+    /// case when `path` is on a different filesystem than the temporary file.
+    pub fn persist_to<P: AsRef<Path>>(mut self, path: P) -> io::Result<()> {
+        fs::rename(&self.path, path)?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// The value of a single multipart field.
+#[derive(Debug)]
+pub enum Field {
+    /// A text field, collected into memory.
+    Text(Vec<u8>),
+    /// A file field, written to a temporary file.
+    File(TempFile),
+}
+
+/// The fields of a multipart body as collected by
+/// [`Multipart::into_fields`](/rocket/data/struct.Multipart.html#method.into_fields).
+#[derive(Debug, Default)]
+pub struct Fields {
+    fields: HashMap<String, Vec<Field>>,
+}
+
+impl Fields {
+    /// Returns the first field named `name`, if any.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Field> {
+        self.fields.get(name).and_then(|fields| fields.first())
+    }
+
+    /// Returns all of the fields named `name` in the order they were sent.
+    #[inline]
+    pub fn get_all(&self, name: &str) -> &[Field] {
+        self.fields.get(name).map(|fields| &fields[..]).unwrap_or(&[])
+    }
+
+    /// Removes and returns all of the fields named `name`.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Vec<Field> {
+        self.fields.remove(name).unwrap_or_else(Vec::new)
+    }
+
+    /// Returns an iterator over the names of the fields.
+    #[inline]
+    pub fn names<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
+        self.fields.keys().map(|name| name.as_str())
+    }
+}
+
+/// The headers of a single part.
+struct PartHeaders {
+    name: String,
+    file_name: Option<String>,
+    content_type: Option<ContentType>,
+}
+
+/// A streaming parser for a `multipart/form-data` body.
+pub struct Multipart {
+    stream: DataStream,
+    // The delimiter between parts: `\r\n--` followed by the boundary.
+    delimiter: Vec<u8>,
+    temp_dir: PathBuf,
+    // The limiter, maximum, and wait applied to spills, if any.
+    spills: Option<(SpillLimiter, usize, Duration)>,
+    started: bool,
+    done: bool,
+}
+
+impl Multipart {
+    /// Returns a parser for the multipart body of `request`, `data`, delimited
+    /// by the `boundary` parameter of the request's `Content-Type`. Returns
+    /// `None` if the request's `Content-Type` isn't `multipart/form-data` or
+    /// has no `boundary`. The boundary may be quoted, parameter names are
+    /// matched case-insensitively, and whitespace around parameters is
+    /// ignored. File fields are written to the configured
+    /// `temp_dir`, no more than the configured `max_spills` at once across the
+    /// application.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Request, Data};
+    /// use rocket::data::Multipart;
+    ///
+    /// fn handler(request: &Request, data: Data) {
+    ///     match Multipart::new(request, data) {
+    ///         Some(multipart) => { /* read the fields */ },
+    ///         None => println!("not a multipart body")
+    ///     }
+    /// }
+    /// ```
+    pub fn new(request: &Request, data: Data) -> Option<Multipart> {
+        // The raw header is parsed leniently: a `Content-Type` that strays
+        // from the grammar, say with whitespace around `=`, is common enough.
+        let boundary = match request.headers().get_one("Content-Type") {
+            Some(header) => match form_data_boundary(header) {
+                Some(boundary) => boundary,
+                None => return None
+            },
+            None => return None
+        };
+
+        let config = request.config();
+        let limit = (config.max_spills as usize, Duration::from_millis(config.spill_wait));
+        let mut multipart = Multipart::with_boundary(boundary, data);
+        multipart.spills = Some((request.spill_limiter().clone(), limit.0, limit.1));
+        Some(multipart.temp_dir(&config.temp_dir))
+    }
+
+    /// Returns a parser for the multipart body in `data` delimited by
+    /// `boundary`. File fields are written to the system's temporary
+    /// directory unless another is set with [`temp_dir`](#method.temp_dir).
+    pub fn with_boundary(boundary: &str, data: Data) -> Multipart {
+        let mut delimiter = b"\r\n--".to_vec();
+        delimiter.extend_from_slice(boundary.as_bytes());
+        Multipart {
+            stream: data.open(),
+            delimiter: delimiter,
+            temp_dir: env::temp_dir(),
+            spills: None,
+            started: false,
+            done: false
+        }
+    }
+
+    /// Sets the directory in which file fields are written to `dir`.
+    #[inline]
+    pub fn temp_dir<P: AsRef<Path>>(mut self, dir: P) -> Multipart {
+        self.temp_dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Reads every part of the body, collecting text fields into memory and
+    /// writing file fields to temporary files, subject to `limits`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the body is malformed, an
+    /// error of kind `InvalidData` wrapping a [`FieldLimitExceeded`] if the
+    /// form exceeds any of its limits, and an error of kind `Other` wrapping a
+    /// [`SpillLimitReached`] if a file field couldn't be written because too
+    /// many others were being written at once. Temporary files written before
+    /// the error are removed. Other I/O errors are returned as-is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::{Request, Data};
+    /// use rocket::data::{Multipart, PartLimits, Field};
+    ///
+    /// fn handler(request: &Request, data: Data) -> io::Result<()> {
+    ///     let multipart = Multipart::new(request, data)
+    ///         .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "!multipart"))?;
+    ///
+    ///     let mut fields = multipart.into_fields(PartLimits::default().text(1024))?;
+    ///     if let Some(Field::File(upload)) = fields.remove("upload").pop() {
+    ///         upload.persist_to("/var/uploads/latest")?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`FieldLimitExceeded`]: /rocket/data/enum.FieldLimitExceeded.html
+    /// [`SpillLimitReached`]: /rocket/data/struct.SpillLimitReached.html
+    pub fn into_fields(mut self, limits: PartLimits) -> io::Result<Fields> {
+        let (mut fields, mut count) = (Fields::default(), 0);
+        while let Some(headers) = self.next_part()? {
+            if count == limits.fields {
+                return Err(FieldLimitExceeded::Fields(limits.fields).into_io());
+            } else if headers.name.len() as u64 > limits.name {
+                return Err(FieldLimitExceeded::Name(limits.name).into_io());
+            }
+
+            count += 1;
+            let field = match headers.file_name {
+                Some(_) => Field::File(self.spill(&headers, limits.file)?),
+                None => {
+                    let mut text = vec![];
+                    self.read_part(&mut text, limits.text)?;
+                    Field::Text(text)
+                }
+            };
+
+            fields.fields.entry(headers.name).or_insert_with(Vec::new).push(field);
+        }
+
+        Ok(fields)
+    }
+
+    /// Advances to the next part and returns its headers, or `None` if the
+    /// closing delimiter has been read.
+    fn next_part(&mut self) -> io::Result<Option<PartHeaders>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        if !self.started {
+            // The first delimiter needn't be preceded by a line break.
+            self.stream.unread(b"\r\n".to_vec());
+            if !self.skip_part()? {
+                return Err(malformed("missing multipart boundary"));
+            }
+
+            self.started = true;
+        }
+
+        // A delimiter is followed by `--` for the last part or a line break.
+        let mut suffix = [0; 2];
+        if !self.stream.read_exact_or_eof(&mut suffix)? || &suffix == b"--" {
+            self.done = true;
+            return Ok(None);
+        }
+
+        if &suffix != b"\r\n" {
+            return Err(malformed("invalid multipart delimiter"));
+        }
+
+        let mut raw = vec![];
+        let found = copy_until(&mut self.stream, b"\r\n\r\n", &mut raw, Some(HEADER_LIMIT))
+            .map_err(|e| match e.get_ref().map_or(false, |e| e.is::<FieldLimitExceeded>()) {
+                true => malformed("multipart headers exceed their size limit"),
+                false => e
+            })?;
+
+        if !found {
+            return Err(malformed("multipart headers are incomplete"));
+        }
+
+        parse_headers(&raw).map(Some)
+    }
+
+    /// Reads and discards the remainder of the current part. Returns `true` if
+    /// a delimiter was found.
+    fn skip_part(&mut self) -> io::Result<bool> {
+        copy_until(&mut self.stream, &self.delimiter, &mut io::sink(), None)
+    }
+
+    /// Copies the current part into `out`, failing if it exceeds `limit`.
+    fn read_part<W: Write>(&mut self, out: &mut W, limit: u64) -> io::Result<u64> {
+        let mut counter = Counter { inner: out, count: 0 };
+        if !copy_until(&mut self.stream, &self.delimiter, &mut counter, Some(limit))? {
+            return Err(malformed("multipart body ended before its closing boundary"));
+        }
+
+        Ok(counter.count)
+    }
+
+    /// Writes the current part to a new temporary file.
+    fn spill(&mut self, headers: &PartHeaders, limit: u64) -> io::Result<TempFile> {
+        let _permit = match self.spills {
+            Some((ref limiter, max, wait)) => Some(limiter.acquire(max, wait)?),
+            None => None
+        };
+
+        let mut temp = TempFile {
+            path: temporary_path(&self.temp_dir),
+            file_name: headers.file_name.clone(),
+            content_type: headers.content_type.clone(),
+            len: 0,
+            persisted: false,
+        };
+
+        let mut file = File::create(&temp.path)?;
+        temp.len = self.read_part(&mut file, limit)?;
+        Ok(temp)
+    }
+}
+
+/// Copies bytes from `stream` into `out` until `delim` is found, returning
+/// `true`, or the stream ends, returning `false`. The delimiter is consumed
+/// but not written; bytes following it remain in `stream`. Fails with
+/// `FieldLimitExceeded::Value` if more than `limit` bytes precede the
+/// delimiter.
+fn copy_until<W: Write>(
+    stream: &mut DataStream,
+    delim: &[u8],
+    out: &mut W,
+    limit: Option<u64>
+) -> io::Result<bool> {
+    let mut written = 0u64;
+    let mut window: Vec<u8> = vec![];
+    let mut buf = [0; 4096];
+    loop {
+        let n = match stream.read(&mut buf) {
+            Ok(0) => {
+                write_limited(out, &window, &mut written, limit)?;
+                return Ok(false);
+            }
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        window.extend_from_slice(&buf[..n]);
+        if let Some(i) = window.windows(delim.len()).position(|w| w == delim) {
+            write_limited(out, &window[..i], &mut written, limit)?;
+            stream.unread(window.split_off(i + delim.len()));
+            return Ok(true);
+        }
+
+        // Keep just enough to find a delimiter that spans reads.
+        let keep = ::std::cmp::min(window.len(), delim.len() - 1);
+        let rest = window.split_off(window.len() - keep);
+        write_limited(out, &window, &mut written, limit)?;
+        window = rest;
+    }
+}
+
+fn write_limited<W: Write>(
+    out: &mut W,
+    bytes: &[u8],
+    written: &mut u64,
+    limit: Option<u64>
+) -> io::Result<()> {
+    *written += bytes.len() as u64;
+    if let Some(limit) = limit {
+        if *written > limit {
+            return Err(FieldLimitExceeded::Value(limit).into_io());
+        }
+    }
+
+    out.write_all(bytes)
+}
+
+struct Counter<'w, W: Write + 'w> {
+    inner: &'w mut W,
+    count: u64,
+}
+
+impl<'w, W: Write> Write for Counter<'w, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[inline]
+fn malformed(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn temporary_path(dir: &Path) -> PathBuf {
+    let id = SPILL_ID.fetch_add(1, Ordering::Relaxed);
+    let name = format!("rocket-multipart.{}.{}.tmp", ::std::process::id(), id);
+    dir.join(name)
+}
+
+/// Returns the `boundary` parameter of the `Content-Type` header value
+/// `header` if it is `multipart/form-data` and has a non-empty boundary.
+fn form_data_boundary(header: &str) -> Option<&str> {
+    let media_type = header.split(';').next().unwrap_or("").trim();
+    if !media_type.eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+
+    header_param(header, "boundary").and_then(|b| if b.is_empty() { None } else { Some(b) })
+}
+
+/// Returns the value of `param` in a header value with parameters, such as
+/// that of `Content-Disposition`. The parameter's name is matched
+/// case-insensitively, whitespace around the name and value is ignored, and
+/// quotes around the value are removed.
+fn header_param<'a>(value: &'a str, param: &str) -> Option<&'a str> {
+    value.split(';').skip(1).filter_map(|pair| {
+        let mut kv = pair.splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) if k.trim().eq_ignore_ascii_case(param) => {
+                let v = v.trim();
+                Some(if v.len() >= 2 && v.starts_with('"') && v.ends_with('"') {
+                    &v[1..(v.len() - 1)]
+                } else {
+                    v
+                })
+            }
+            _ => None
+        }
+    }).next()
+}
+
+fn parse_headers(raw: &[u8]) -> io::Result<PartHeaders> {
+    let raw = ::std::str::from_utf8(raw)
+        .map_err(|_| malformed("multipart headers are not valid UTF-8"))?;
+
+    let (mut name, mut file_name, mut content_type) = (None, None, None);
+    for line in raw.split("\r\n").filter(|line| !line.is_empty()) {
+        let mut header = line.splitn(2, ':');
+        let (key, value) = match (header.next(), header.next()) {
+            (Some(key), Some(value)) => (key.trim(), value.trim()),
+            _ => return Err(malformed("invalid multipart header")),
+        };
+
+        if key.eq_ignore_ascii_case("Content-Disposition") {
+            name = header_param(value, "name").map(|s| s.to_string());
+            file_name = header_param(value, "filename").map(|s| s.to_string());
+        } else if key.eq_ignore_ascii_case("Content-Type") {
+            content_type = value.parse().ok();
+        }
+    }
+
+    match name {
+        Some(name) => Ok(PartHeaders { name: name, file_name: file_name, content_type: content_type }),
+        None => Err(malformed("multipart part is missing a field name")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::form_data_boundary as boundary;
+
+    #[test]
+    fn test_boundary() {
+        assert_eq!(boundary("multipart/form-data; boundary=xyz"), Some("xyz"));
+        assert_eq!(boundary("multipart/form-data;boundary=a=b"), Some("a=b"));
+        assert_eq!(boundary(r#"multipart/form-data; boundary="----WebKitFormBoundaryxyz""#),
+                   Some("----WebKitFormBoundaryxyz"));
+        assert_eq!(boundary("Multipart/Form-Data ;  BOUNDARY = xyz  "), Some("xyz"));
+        assert_eq!(boundary(" multipart/form-data; charset=utf-8; Boundary=\"x y\" "),
+                   Some("x y"));
+    }
+
+    #[test]
+    fn test_boundary_missing() {
+        assert_eq!(boundary("multipart/form-data"), None);
+        assert_eq!(boundary("multipart/form-data; boundary="), None);
+        assert_eq!(boundary(r#"multipart/form-data; boundary="""#), None);
+        assert_eq!(boundary("multipart/mixed; boundary=xyz"), None);
+        assert_eq!(boundary("text/plain; boundary=xyz"), None);
+    }
+}
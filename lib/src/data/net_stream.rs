@@ -1,24 +1,167 @@
 use std::io;
-use std::net::{SocketAddr, Shutdown};
+use std::net::{SocketAddr, Shutdown, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
+#[cfg(target_os = "linux")] use std::os::unix::io::{AsRawFd, RawFd};
 
-#[cfg(feature = "tls")] use hyper_sync_rustls::{WrappedStream, ServerSession};
 use http::hyper::net::{HttpStream, NetworkStream};
+use super::stats;
+#[cfg(feature = "tls")] use super::tls::{TlsStream, TlsInfo};
 
 use self::NetStream::*;
 
-#[cfg(feature = "tls")] pub type HttpsStream = WrappedStream<ServerSession>;
+#[cfg(feature = "tls")] pub type HttpsStream = TlsStream;
 
 // This is a representation of all of the possible network streams we might get.
-// This really shouldn't be necessary, but, you know, Hyper.
-#[derive(Clone)]
+// This really shouldn't be necessary, but, you know, Hyper. Every variant must
+// be `Send` so that `DataStream` is: Hyper's `NetworkStream` requires it.
 pub enum NetStream {
     Http(HttpStream),
     #[cfg(feature = "tls")]
     Https(HttpsStream),
+    // A stream whose type was registered at runtime via `recognize_stream`.
+    Dynamic(Box<NetworkStream>),
+    // A stream whose reads are counted, when `count_read_syscalls` is set.
+    Counted(Box<NetStream>, Arc<AtomicUsize>),
     Empty,
 }
 
+/// A function that attempts to recover a concrete `NetStream` from Hyper's
+/// type-erased network stream, returning `None` if the stream isn't of the
+/// type the function recognizes.
+pub type StreamRecognizer = fn(&NetworkStream) -> Option<NetStream>;
+
+fn recognize_http(stream: &NetworkStream) -> Option<NetStream> {
+    stream.downcast_ref::<HttpStream>().map(|s| Http(s.clone()))
+}
+
+#[cfg(feature = "tls")]
+fn recognize_https(stream: &NetworkStream) -> Option<NetStream> {
+    stream.downcast_ref::<HttpsStream>().map(|s| Https(s.clone()))
+}
+
+pub fn recognize_dynamic<S: NetworkStream + Clone>(stream: &NetworkStream) -> Option<NetStream> {
+    stream.downcast_ref::<S>().map(|s| Dynamic(Box::new(s.clone())))
+}
+
+/// The recognizers for the streams Rocket itself knows how to serve.
+pub fn default_recognizers() -> Vec<StreamRecognizer> {
+    let mut recognizers: Vec<StreamRecognizer> = vec![recognize_http];
+    #[cfg(feature = "tls")] recognizers.push(recognize_https);
+    recognizers
+}
+
+impl NetStream {
+    /// Wraps `self` so that its reads are counted. An empty stream never
+    /// reads from a connection, so it's returned as-is.
+    pub fn counted(self) -> NetStream {
+        match self {
+            Empty => Empty,
+            stream => Counted(Box::new(stream), Arc::new(AtomicUsize::new(0)))
+        }
+    }
+
+    /// Returns the number of reads made from the connection so far if they're
+    /// being counted.
+    pub fn read_syscalls(&self) -> Option<usize> {
+        match *self {
+            Counted(_, ref count) => Some(count.load(Ordering::Relaxed)),
+            _ => None,
+        }
+    }
+
+    /// Returns `false` if the peer is known to have closed the connection and
+    /// `true` otherwise. Streams that can't be inspected, including local and
+    /// TLS streams, are always considered connected.
+    pub fn is_peer_connected(&self) -> bool {
+        match *self {
+            Http(ref stream) => tcp_is_connected(&stream.0),
+            Counted(ref stream, _) => stream.is_peer_connected(),
+            _ => true,
+        }
+    }
+
+    /// Returns the file descriptor of the underlying socket if bytes can be
+    /// read from it directly: that is, if this is a plaintext TCP stream.
+    #[cfg(target_os = "linux")]
+    pub fn raw_fd(&self) -> Option<RawFd> {
+        match *self {
+            Http(ref stream) => Some(stream.0.as_raw_fd()),
+            Counted(ref stream, _) => stream.raw_fd(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is a TLS stream.
+    pub fn is_secure(&self) -> bool {
+        match *self {
+            #[cfg(feature = "tls")] Https(_) => true,
+            Counted(ref stream, _) => stream.is_secure(),
+            _ => false,
+        }
+    }
+
+    /// Returns the parameters negotiated for this stream if it's a TLS stream.
+    #[cfg(feature = "tls")]
+    pub fn tls_info(&self) -> Option<TlsInfo> {
+        match *self {
+            Https(ref stream) => stream.info(),
+            Counted(ref stream, _) => stream.tls_info(),
+            _ => None,
+        }
+    }
+
+    /// Enables or disables Nagle's algorithm (`TCP_NODELAY`) on the underlying
+    /// socket. Local streams ignore the setting. Returns an error for streams
+    /// whose socket isn't accessible, including TLS streams.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        match *self {
+            Http(ref stream) => stream.0.set_nodelay(nodelay),
+            Counted(ref stream, _) => stream.set_nodelay(nodelay),
+            Empty => Ok(()),
+            _ => Err(io::Error::new(io::ErrorKind::Other, "stream's socket is inaccessible")),
+        }
+    }
+
+    /// Moves the underlying socket into or out of non-blocking mode. Local
+    /// streams, which never block, ignore the setting. Returns an error for
+    /// streams whose socket isn't accessible, including TLS streams.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match *self {
+            Http(ref stream) => stream.0.set_nonblocking(nonblocking),
+            Counted(ref stream, _) => stream.set_nonblocking(nonblocking),
+            Empty => Ok(()),
+            _ => Err(io::Error::new(io::ErrorKind::Other, "stream's socket is inaccessible")),
+        }
+    }
+}
+
+/// Peeks at `stream` without blocking. A zero-byte peek means the peer has
+/// closed its half of the connection; any other outcome, including a peek that
+/// would block, means it may still be there.
+fn tcp_is_connected(stream: &TcpStream) -> bool {
+    if let Err(e) = stream.set_nonblocking(true) {
+        warn_!("Failed to set stream to non-blocking: {:?}", e);
+        return true;
+    }
+
+    let mut buf = [0; 1];
+    let connected = match stream.peek(&mut buf) {
+        Ok(n) => n > 0,
+        Err(ref e) => match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => true,
+            _ => false
+        }
+    };
+
+    if let Err(e) = stream.set_nonblocking(false) {
+        error_!("Failed to restore stream to blocking: {:?}", e);
+    }
+
+    connected
+}
+
 impl io::Read for NetStream {
     #[inline(always)]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -26,6 +169,12 @@ impl io::Read for NetStream {
         let res = match *self {
             Http(ref mut stream) => stream.read(buf),
             #[cfg(feature = "tls")] Https(ref mut stream) => stream.read(buf),
+            Dynamic(ref mut stream) => stream.read(buf),
+            Counted(ref mut stream, ref count) => {
+                count.fetch_add(1, Ordering::Relaxed);
+                stats::record_read_syscall();
+                stream.read(buf)
+            }
             Empty => Ok(0),
         };
 
@@ -41,6 +190,8 @@ impl io::Write for NetStream {
         match *self {
             Http(ref mut stream) => stream.write(buf),
             #[cfg(feature = "tls")] Https(ref mut stream) => stream.write(buf),
+            Dynamic(ref mut stream) => stream.write(buf),
+            Counted(ref mut stream, _) => stream.write(buf),
             Empty => Ok(0),
         }
     }
@@ -50,6 +201,8 @@ impl io::Write for NetStream {
         match *self {
             Http(ref mut stream) => stream.flush(),
             #[cfg(feature = "tls")] Https(ref mut stream) => stream.flush(),
+            Dynamic(ref mut stream) => stream.flush(),
+            Counted(ref mut stream, _) => stream.flush(),
             Empty => Ok(()),
         }
     }
@@ -61,6 +214,8 @@ impl NetworkStream for NetStream {
         match *self {
             Http(ref mut stream) => stream.peer_addr(),
             #[cfg(feature = "tls")] Https(ref mut stream) => stream.peer_addr(),
+            Dynamic(ref mut stream) => stream.peer_addr(),
+            Counted(ref mut stream, _) => stream.peer_addr(),
             Empty => Err(io::Error::from(io::ErrorKind::AddrNotAvailable)),
         }
     }
@@ -70,6 +225,8 @@ impl NetworkStream for NetStream {
         match *self {
             Http(ref stream) => stream.set_read_timeout(dur),
             #[cfg(feature = "tls")] Https(ref stream) => stream.set_read_timeout(dur),
+            Dynamic(ref stream) => stream.set_read_timeout(dur),
+            Counted(ref stream, _) => stream.set_read_timeout(dur),
             Empty => Ok(()),
         }
     }
@@ -79,6 +236,8 @@ impl NetworkStream for NetStream {
         match *self {
             Http(ref stream) => stream.set_write_timeout(dur),
             #[cfg(feature = "tls")] Https(ref stream) => stream.set_write_timeout(dur),
+            Dynamic(ref stream) => stream.set_write_timeout(dur),
+            Counted(ref stream, _) => stream.set_write_timeout(dur),
             Empty => Ok(()),
         }
     }
@@ -88,6 +247,8 @@ impl NetworkStream for NetStream {
         match *self {
             Http(ref mut stream) => stream.close(how),
             #[cfg(feature = "tls")] Https(ref mut stream) => stream.close(how),
+            Dynamic(ref mut stream) => stream.close(how),
+            Counted(ref mut stream, _) => stream.close(how),
             Empty => Ok(()),
         }
     }
@@ -0,0 +1,62 @@
+use std::io::{self, Read};
+
+use outcome::Outcome::*;
+use http::Status;
+use request::Request;
+use super::{Data, DataStream, FromData, Outcome};
+use super::from_data::read_error_status;
+
+/// The number of bytes of an unexpected body that are read and discarded. If
+/// more remain, the connection is closed when the stream is dropped.
+const DRAIN_LIMIT: u64 = 64 * 1024;
+
+/// A data guard that succeeds only if the request has no body.
+///
+/// A body is absent if its declared length is `0` or if it ends before a
+/// single byte is read. A request with a body fails with `400 Bad Request` and
+/// an error of kind `InvalidData`. Either way, the body is drained so the
+/// connection stays clean for the next request: a small body is read and
+/// discarded; a large one closes the connection.
+///
+/// This is useful for endpoints that should never receive a body, where one
+/// signals a misbehaving client or an attempt at request smuggling.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/ping", data = "<_empty>")]
+/// fn ping(_empty: NoBody) -> &'static str {
+///     "pong"
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoBody;
+
+fn drain(stream: DataStream) {
+    if let Err(e) = io::copy(&mut stream.take(DRAIN_LIMIT), &mut io::sink()) {
+        debug_!("Failed to drain unexpected body: {}", e);
+    }
+}
+
+impl FromData for NoBody {
+    type Error = io::Error;
+
+    fn from_data(_: &Request, data: Data) -> Outcome<Self, Self::Error> {
+        if data.len() == Some(0) {
+            return Success(NoBody);
+        }
+
+        // The peek buffer, if any, is read first, so a single byte decides.
+        let mut stream = data.open();
+        match stream.read(&mut [0]) {
+            Ok(0) => Success(NoBody),
+            Ok(_) => {
+                warn_!("Rejecting a request with an unexpected body.");
+                drain(stream);
+                let e = io::Error::new(io::ErrorKind::InvalidData, "request has a body");
+                Failure((Status::BadRequest, e))
+            }
+            Err(e) => Failure((read_error_status(&e, Status::BadRequest), e))
+        }
+    }
+}
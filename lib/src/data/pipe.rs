@@ -0,0 +1,110 @@
+use std::io::{self, Read, Write};
+use std::process::{Child, ChildStdout, Command, ExitStatus, Stdio};
+use std::thread::{self, JoinHandle};
+
+use super::DataStream;
+
+/// A reader over the standard output of a process that a body is being piped
+/// through.
+///
+/// This reader can only be obtained by calling
+/// [Data::pipe_through](/rocket/data/struct.Data.html#method.pipe_through).
+/// The body is written to the process's standard input by a separate thread
+/// while this reader is read, so a process that writes output before it has
+/// read all of its input never deadlocks.
+///
+/// Once the output has been read, [`finish`](#method.finish) waits for the
+/// process to exit and reports whether writing the body to it succeeded. If
+/// a `ChildOutput` is dropped instead, the process is killed.
+pub struct ChildOutput {
+    child: Child,
+    stdout: ChildStdout,
+    writer: Option<JoinHandle<io::Result<u64>>>,
+}
+
+impl ChildOutput {
+    pub(crate) fn spawn(stream: DataStream, mut command: Command) -> io::Result<ChildOutput> {
+        let mut child = command.stdin(Stdio::piped()).stdout(Stdio::piped()).spawn()?;
+        let (mut stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => (stdin, stdout),
+            _ => unreachable!("stdin and stdout are piped")
+        };
+
+        let mut stream = stream;
+        let writer = thread::Builder::new().name("rocket-pipe".into()).spawn(move || {
+            let (mut buf, mut written) = ([0; 8192], 0);
+            loop {
+                let n = match stream.read(&mut buf) {
+                    Ok(0) => return Ok(written),
+                    Ok(n) => n,
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e)
+                };
+
+                // The process exiting without reading all of its input isn't
+                // an error of the body's.
+                match stdin.write_all(&buf[..n]) {
+                    Ok(()) => written += n as u64,
+                    Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(written),
+                    Err(e) => return Err(e)
+                }
+            }
+        });
+
+        match writer {
+            Ok(writer) => Ok(ChildOutput { child: child, stdout: stdout, writer: Some(writer) }),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the OS-assigned process identifier of the process.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Waits for the body to be written to the process and for the process to
+    /// exit, returning the number of bytes of the body written to it and its
+    /// exit status. Any output that hasn't been read is discarded.
+    ///
+    /// A process that exits without reading all of its input isn't considered
+    /// a failure; the number of bytes returned is then the number written
+    /// before it exited. Failing to read the body is.
+    pub fn finish(mut self) -> io::Result<(u64, ExitStatus)> {
+        io::copy(&mut self.stdout, &mut io::sink())?;
+        let written = self.join_writer();
+        let status = self.child.wait()?;
+        Ok((written?, status))
+    }
+
+    fn join_writer(&mut self) -> io::Result<u64> {
+        match self.writer.take() {
+            Some(writer) => writer.join().unwrap_or_else(|_| {
+                Err(io::Error::new(io::ErrorKind::Other, "body writer thread panicked"))
+            }),
+            None => Ok(0)
+        }
+    }
+}
+
+impl Read for ChildOutput {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stdout.read(buf)
+    }
+}
+
+impl Drop for ChildOutput {
+    fn drop(&mut self) {
+        // The writer isn't joined: it may be blocked reading the body. Once
+        // the process is gone, its next write fails and it exits.
+        if self.writer.is_some() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
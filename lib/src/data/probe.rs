@@ -0,0 +1,54 @@
+/// The name of a format identified by a [`Probe`](/rocket/data/struct.Probe.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Format(pub &'static str);
+
+/// A magic-byte signature identifying a file format, for use with
+/// [`Data::probe`](/rocket/data/struct.Data.html#method.probe).
+///
+/// A probe matches when the bytes `magic` appear at `offset` in the body.
+/// [`Probe::DEFAULTS`](#associatedconstant.DEFAULTS) contains probes for common
+/// formats; custom probes are constructed directly and can be combined with
+/// the defaults.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::{Probe, Format};
+///
+/// const PARQUET: Probe = Probe { offset: 0, magic: b"PAR1", format: Format("parquet") };
+///
+/// let mut probes = Probe::DEFAULTS.to_vec();
+/// probes.push(PARQUET);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    /// The offset of `magic` from the start of the body.
+    pub offset: usize,
+    /// The bytes identifying the format.
+    pub magic: &'static [u8],
+    /// The format identified by this probe.
+    pub format: Format,
+}
+
+impl Probe {
+    /// Probes for common binary formats: `png`, `gif`, `jpeg`, `webp`, `pdf`,
+    /// `zip`, `gzip`, `woff`, and `woff2`.
+    pub const DEFAULTS: &'static [Probe] = &[
+        Probe { offset: 0, magic: b"\x89PNG\r\n\x1a\n", format: Format("png") },
+        Probe { offset: 0, magic: b"GIF87a", format: Format("gif") },
+        Probe { offset: 0, magic: b"GIF89a", format: Format("gif") },
+        Probe { offset: 0, magic: b"\xFF\xD8\xFF", format: Format("jpeg") },
+        Probe { offset: 8, magic: b"WEBP", format: Format("webp") },
+        Probe { offset: 0, magic: b"%PDF-", format: Format("pdf") },
+        Probe { offset: 0, magic: b"PK\x03\x04", format: Format("zip") },
+        Probe { offset: 0, magic: b"\x1f\x8b", format: Format("gzip") },
+        Probe { offset: 0, magic: b"wOFF", format: Format("woff") },
+        Probe { offset: 0, magic: b"wOF2", format: Format("woff2") },
+    ];
+
+    /// Returns `true` if `bytes`, the first bytes of a body, match this probe.
+    #[inline]
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= self.offset && bytes[self.offset..].starts_with(self.magic)
+    }
+}
@@ -0,0 +1,65 @@
+use std::io::{self, Write};
+
+use super::data_stream::ReadHook;
+
+/// The longest line buffered for redaction. A longer line is redacted in
+/// pieces of this many bytes.
+const MAX_LINE: usize = 8 * 1024;
+
+/// A read hook that writes a redacted copy of the bytes read from a body to a
+/// sink, one line at a time.
+pub(crate) struct RedactingTee<W, F> {
+    sink: Option<W>,
+    redact: F,
+    line: Vec<u8>,
+}
+
+impl<W: Write, F: FnMut(&[u8]) -> Vec<u8>> RedactingTee<W, F> {
+    pub(crate) fn new(sink: W, redact: F) -> RedactingTee<W, F> {
+        RedactingTee { sink: Some(sink), redact: redact, line: vec![] }
+    }
+
+    // Redacts the buffered line and writes it out. A sink that fails is
+    // dropped so that logging never fails the body's reads.
+    fn emit(&mut self) {
+        if self.line.is_empty() {
+            return;
+        }
+
+        let redacted = (self.redact)(&self.line);
+        self.line.clear();
+        let result = match self.sink {
+            Some(ref mut sink) => sink.write_all(&redacted),
+            None => return
+        };
+
+        if let Err(e) = result {
+            warn_!("Failed to write to the redacted body log: {}", e);
+            self.sink = None;
+        }
+    }
+}
+
+impl<W, F> ReadHook for RedactingTee<W, F>
+    where W: Write + Send, F: FnMut(&[u8]) -> Vec<u8> + Send
+{
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        for &byte in bytes {
+            self.line.push(byte);
+            if byte == b'\n' || self.line.len() >= MAX_LINE {
+                self.emit();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        self.emit();
+        if let Some(ref mut sink) = self.sink {
+            if let Err(e) = sink.flush() {
+                warn_!("Failed to flush the redacted body log: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::{io, fmt};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Data;
+
+/// The error returned by
+/// [`ResumableUpload::append`](/rocket/data/struct.ResumableUpload.html#method.append)
+/// when a chunk's declared offset isn't the number of bytes uploaded so far.
+///
+/// This error is returned as the inner error of an `io::Error` of kind
+/// `InvalidInput` and can be recovered with `io::Error::get_ref` and
+/// `downcast_ref`. [`read_error_status`](/rocket/data/fn.read_error_status.html)
+/// maps it to `409 Conflict`, as the tus protocol requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetMismatch {
+    /// The number of bytes uploaded so far: the offset the chunk should have.
+    pub expected: u64,
+    /// The offset the chunk declared.
+    pub received: u64,
+}
+
+impl fmt::Display for OffsetMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "upload offset {} doesn't match the {} bytes uploaded",
+               self.received, self.expected)
+    }
+}
+
+impl ::std::error::Error for OffsetMismatch {
+    fn description(&self) -> &str {
+        "upload offset doesn't match the bytes uploaded"
+    }
+}
+
+/// The state of a resumable upload, as in the [tus] protocol, stored in a
+/// file: the number of bytes uploaded so far is the size of the file.
+///
+/// Each chunk of the upload is sent with the offset it begins at, as in tus's
+/// `Upload-Offset` header, and [`append`](#method.append) writes it to the
+/// file only if that offset is the file's current size. A chunk that's cut
+/// short leaves the bytes it did send in the file, so the client can resume
+/// from the new [`offset`](#method.offset).
+///
+/// Appends to one upload must not run concurrently: the file's size is
+/// checked before the chunk is written, so two chunks at the same offset
+/// could otherwise both be accepted.
+///
+/// [tus]: https://tus.io/protocols/resumable-upload.html
+///
+/// # Example
+///
+/// ```rust
+/// use std::io;
+/// use rocket::Data;
+/// use rocket::data::ResumableUpload;
+///
+/// fn patch(data: Data, id: &str, upload_offset: u64) -> io::Result<u64> {
+///     let upload = ResumableUpload::new(format!("/uploads/{}", id)).length(1 << 30);
+///     upload.append(data, upload_offset)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumableUpload {
+    path: PathBuf,
+    length: Option<u64>,
+}
+
+impl ResumableUpload {
+    /// Creates the state of an upload stored in the file at `path`, which
+    /// needn't exist yet.
+    pub fn new<P: Into<PathBuf>>(path: P) -> ResumableUpload {
+        ResumableUpload { path: path.into(), length: None }
+    }
+
+    /// Sets the total length of the upload, as declared by tus's
+    /// `Upload-Length` header. A chunk may not extend the upload past it.
+    pub fn length(mut self, length: u64) -> ResumableUpload {
+        self.length = Some(length);
+        self
+    }
+
+    /// Returns the path of the file the upload is stored in.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of bytes uploaded so far: the size of the file, or
+    /// `0` if it doesn't exist yet.
+    pub fn offset(&self) -> io::Result<u64> {
+        match fs::metadata(&self.path) {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Returns `true` if the upload has a length and all of it was uploaded.
+    pub fn is_complete(&self) -> io::Result<bool> {
+        match self.length {
+            Some(length) => Ok(self.offset()? >= length),
+            None => Ok(false)
+        }
+    }
+
+    /// Appends `data`, a chunk that declared it begins at `offset`, to the
+    /// upload and returns the upload's new offset.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an [`OffsetMismatch`] error, without reading `data`, if
+    /// `offset` isn't the upload's current offset. If the upload has a
+    /// length, reading a chunk that extends past it fails with a
+    /// [`BodyTooLarge`] error once the length is reached. Reading or writing
+    /// the chunk may fail with other I/O errors; the bytes written before the
+    /// failure remain part of the upload.
+    ///
+    /// [`OffsetMismatch`]: /rocket/data/struct.OffsetMismatch.html
+    /// [`BodyTooLarge`]: /rocket/data/struct.BodyTooLarge.html
+    pub fn append(&self, data: Data, offset: u64) -> io::Result<u64> {
+        let expected = self.offset()?;
+        if offset != expected {
+            let error = OffsetMismatch { expected: expected, received: offset };
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+        }
+
+        let data = match self.length {
+            Some(length) => {
+                let remaining = length.saturating_sub(offset);
+                let max = ::std::cmp::min(remaining, usize::max_value() as u64) as usize;
+                data.limit(max).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            }
+            None => data
+        };
+
+        let written = data.stream_to_file_at(&self.path, offset)?;
+        Ok(offset + written)
+    }
+}
@@ -0,0 +1,55 @@
+use std::io;
+
+use super::data_stream::ReadHook;
+
+/// The verdict of a body scanner on a chunk of a body.
+///
+/// See [`Data::open_scanned`](/rocket/data/struct.Data.html#method.open_scanned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanVerdict {
+    /// The chunk is acceptable; continue reading.
+    Continue,
+    /// The body is unacceptable; fail this and every subsequent read.
+    Reject,
+}
+
+/// A `ReadHook` that runs a scanner over every chunk read and fails reads once
+/// the scanner rejects the body.
+pub struct Scanner<F> {
+    scan: F,
+    rejected: bool,
+}
+
+impl<F: FnMut(&[u8]) -> ScanVerdict> Scanner<F> {
+    pub fn new(scan: F) -> Scanner<F> {
+        Scanner { scan: scan, rejected: false }
+    }
+}
+
+#[inline]
+fn rejected() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "body rejected by scanner")
+}
+
+impl<F: FnMut(&[u8]) -> ScanVerdict + Send> ReadHook for Scanner<F> {
+    fn before_read(&mut self) -> io::Result<()> {
+        if self.rejected {
+            return Err(rejected());
+        }
+
+        Ok(())
+    }
+
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        if (self.scan)(bytes) == ScanVerdict::Reject {
+            self.rejected = true;
+            return Err(rejected());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use std::{io, fmt};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::data_stream::ReadHook;
+
+/// The error returned by reads of a request body once shutdown was requested
+/// and the configured `shutdown_grace` period has passed.
+///
+/// This error is returned as the inner error of an `io::Error` of kind `Other`
+/// and can be recovered with `io::Error::get_ref` and `downcast_ref`.
+/// [`read_error_status`](/rocket/data/fn.read_error_status.html) maps it to
+/// `503 Service Unavailable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShuttingDown;
+
+impl fmt::Display for ShuttingDown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the server is shutting down")
+    }
+}
+
+impl ::std::error::Error for ShuttingDown {
+    fn description(&self) -> &str {
+        "the server is shutting down"
+    }
+}
+
+#[derive(Default)]
+struct State {
+    requested: AtomicBool,
+    at: Mutex<Option<Instant>>,
+}
+
+/// A handle used to tell Rocket's data layer that the application is shutting
+/// down.
+///
+/// Once [`shutdown`](#method.shutdown) is called, request bodies that are
+/// being read, or that are read later, may continue to be read for the
+/// configured `shutdown_grace` period. After it passes, every read of a
+/// body fails with a [`ShuttingDown`] error so that handlers can roll back
+/// their work instead of holding up the shutdown. The deadline is checked
+/// before every read, so a read that is already blocked waiting for the
+/// client ends only once the read timeout passes.
+///
+/// A handle is obtained from [`Rocket::shutdown_handle`] before the
+/// application is launched. Clones refer to the same application.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::thread;
+///
+/// let rocket = rocket::ignite();
+/// let handle = rocket.shutdown_handle();
+/// thread::spawn(move || {
+///     /* wait for a shutdown signal */
+///     handle.shutdown();
+/// });
+///
+/// rocket.launch();
+/// ```
+///
+/// [`ShuttingDown`]: /rocket/data/struct.ShuttingDown.html
+/// [`Rocket::shutdown_handle`]: /rocket/struct.Rocket.html#method.shutdown_handle
+#[derive(Clone, Default)]
+pub struct ShutdownHandle {
+    state: Arc<State>,
+}
+
+impl ShutdownHandle {
+    /// Requests that the application shut down, starting the grace period for
+    /// body reads. Calls after the first have no effect.
+    pub fn shutdown(&self) {
+        if let Ok(mut at) = self.state.at.lock() {
+            if at.is_none() {
+                *at = Some(Instant::now());
+            }
+        }
+
+        self.state.requested.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if shutdown has been requested.
+    #[inline]
+    pub fn is_shutting_down(&self) -> bool {
+        self.state.requested.load(Ordering::Acquire)
+    }
+
+    /// Returns the instant shutdown was requested at, if it has been.
+    fn requested_at(&self) -> Option<Instant> {
+        if !self.is_shutting_down() {
+            return None;
+        }
+
+        self.state.at.lock().ok().and_then(|at| *at)
+    }
+}
+
+/// A read hook that fails reads once `grace` has passed since shutdown was
+/// requested through `handle`.
+pub struct ShutdownDeadline {
+    handle: ShutdownHandle,
+    grace: Duration,
+}
+
+impl ShutdownDeadline {
+    pub fn new(handle: ShutdownHandle, grace: Duration) -> ShutdownDeadline {
+        ShutdownDeadline { handle: handle, grace: grace }
+    }
+}
+
+impl ReadHook for ShutdownDeadline {
+    fn before_read(&mut self) -> io::Result<()> {
+        match self.handle.requested_at() {
+            Some(at) if Instant::now() >= at + self.grace => {
+                Err(io::Error::new(io::ErrorKind::Other, ShuttingDown))
+            }
+            _ => Ok(())
+        }
+    }
+
+    fn observes_bytes(&self) -> bool {
+        false
+    }
+}
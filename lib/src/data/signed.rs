@@ -0,0 +1,202 @@
+use std::io::{self, Read};
+use std::ops::Deref;
+
+use ring::{digest, hmac};
+
+use outcome::Outcome::*;
+use http::Status;
+use request::{Request, State};
+use super::{Data, FromData, Outcome};
+use super::from_data::read_error_status;
+
+/// The default limit, in bytes, on the size of a signed body.
+const LIMIT: u64 = 1 << 20;
+
+/// The secret and header that a [`SignedBody`] verifies a body's signature
+/// with, registered with `Rocket::manage`.
+///
+/// The header's value is the hexadecimal HMAC-SHA256 of the body, optionally
+/// preceded by a fixed prefix, as in GitHub's `X-Hub-Signature-256: sha256=..`.
+///
+/// [`SignedBody`]: /rocket/data/struct.SignedBody.html
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::SigningSecret;
+///
+/// let secret = SigningSecret::new("X-Hub-Signature-256", "It's a secret to everybody.")
+///     .prefix("sha256=");
+///
+/// rocket::ignite().manage(secret);
+/// ```
+pub struct SigningSecret {
+    header: String,
+    prefix: String,
+    key: hmac::VerificationKey,
+}
+
+impl SigningSecret {
+    /// Creates a `SigningSecret` that verifies the signature in the header
+    /// named `header` with the key `secret`.
+    pub fn new<H: Into<String>, S: AsRef<[u8]>>(header: H, secret: S) -> SigningSecret {
+        SigningSecret {
+            header: header.into(),
+            prefix: String::new(),
+            key: hmac::VerificationKey::new(&digest::SHA256, secret.as_ref()),
+        }
+    }
+
+    /// Sets the prefix that precedes the hexadecimal signature in the header,
+    /// such as `sha256=`. There is no prefix by default.
+    pub fn prefix<P: Into<String>>(mut self, prefix: P) -> SigningSecret {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Returns the signature in `value`, a value of the signature header, if
+    /// it's well-formed.
+    fn signature(&self, value: &str) -> Option<Vec<u8>> {
+        let value = value.trim();
+        if !value.starts_with(&*self.prefix) {
+            return None;
+        }
+
+        decode_hex(&value[self.prefix.len()..])
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.chars().all(|c| c.is_digit(16)) {
+        return None;
+    }
+
+    (0..(hex.len() / 2)).map(|i| u8::from_str_radix(&hex[(2 * i)..(2 * i + 2)], 16).ok()).collect()
+}
+
+/// A data guard that verifies the HMAC-SHA256 signature of the body before
+/// handing it to the data guard `T`, as for webhooks.
+///
+/// The signature is checked against the [`SigningSecret`] managed by the
+/// application and the header it names. It's computed over the body exactly
+/// as it was received, before any `Content-Encoding` is removed, which is read
+/// into memory up to the `signed` limit (1MiB by default). `T` only ever sees
+/// a body whose signature matched.
+///
+/// A request without a well-formed signature, or whose signature doesn't
+/// match, fails with `401 Unauthorized`; a body that exceeds the limit fails
+/// with `413 Payload Too Large`. If no `SigningSecret` is managed, every
+/// request fails with `500 Internal Server Error`.
+///
+/// This guard is only available when the `hmac` feature is enabled.
+///
+/// [`SigningSecret`]: /rocket/data/struct.SigningSecret.html
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/hooks/github", data = "<event>")]
+/// fn github(event: SignedBody<Json<PushEvent>>) { ... }
+/// ```
+#[derive(Debug)]
+pub struct SignedBody<T>(pub T);
+
+impl<T> SignedBody<T> {
+    /// Consumes `self` and returns the value produced by the inner guard.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for SignedBody<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// The error returned by the `SignedBody` data guard.
+#[derive(Debug)]
+pub enum SignatureError<E> {
+    /// No `SigningSecret` is managed by the application.
+    NoSecret,
+    /// The signature header is missing or malformed.
+    Unsigned,
+    /// The signature doesn't match the body.
+    Mismatch,
+    /// Reading the body failed or the body exceeded the limit.
+    Io(io::Error),
+    /// The inner data guard failed.
+    Inner(E),
+}
+
+impl<T: FromData> FromData for SignedBody<T> {
+    type Error = SignatureError<T::Error>;
+
+    fn from_data(request: &Request, data: Data) -> Outcome<Self, Self::Error> {
+        let secret = match request.guard::<State<SigningSecret>>() {
+            Success(secret) => secret,
+            _ => {
+                error_!("A `SignedBody` guard was used without a managed `SigningSecret`.");
+                return Failure((Status::InternalServerError, SignatureError::NoSecret));
+            }
+        };
+
+        let signature = request.headers().get_one(&secret.header)
+            .and_then(|value| secret.signature(value));
+
+        let signature = match signature {
+            Some(signature) => signature,
+            None => {
+                warn_!("Request has no well-formed '{}' signature.", secret.header);
+                return Failure((Status::Unauthorized, SignatureError::Unsigned));
+            }
+        };
+
+        let limit = request.limits().get("signed").unwrap_or(LIMIT);
+        let mut body = vec![];
+        match data.open().take(limit.saturating_add(1)).read_to_end(&mut body) {
+            Ok(n) if n as u64 > limit => {
+                let msg = "signed body exceeds the limit";
+                let e = io::Error::new(io::ErrorKind::InvalidData, msg);
+                return Failure((Status::PayloadTooLarge, SignatureError::Io(e)));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let status = read_error_status(&e, Status::BadRequest);
+                return Failure((status, SignatureError::Io(e)));
+            }
+        }
+
+        if hmac::verify(&secret.key, &body, &signature).is_err() {
+            warn_!("Body signature in '{}' doesn't match.", secret.header);
+            return Failure((Status::Unauthorized, SignatureError::Mismatch));
+        }
+
+        match T::from_data(request, Data::local(body)) {
+            Success(value) => Success(SignedBody(value)),
+            Failure((status, e)) => Failure((status, SignatureError::Inner(e))),
+            Forward(data) => Forward(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_hex, SigningSecret};
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(decode_hex("00ff1A"), Some(vec![0x00, 0xff, 0x1a]));
+        assert_eq!(decode_hex("0"), None);
+        assert_eq!(decode_hex("zz"), None);
+        assert_eq!(decode_hex("é0"), None);
+
+        let secret = SigningSecret::new("X-Signature", "key").prefix("sha256=");
+        assert_eq!(secret.signature(" sha256=beef "), Some(vec![0xbe, 0xef]));
+        assert_eq!(secret.signature("sha1=beef"), None);
+    }
+}
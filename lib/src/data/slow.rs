@@ -0,0 +1,153 @@
+use std::{io, fmt};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use super::data_stream::ReadHook;
+
+/// A read hook that logs a warning if the time from the first read of a body
+/// to its end exceeds a threshold.
+pub struct SlowBodyLog {
+    threshold: Duration,
+    peer: Option<SocketAddr>,
+    started: Option<Instant>,
+    bytes: u64,
+    done: bool,
+}
+
+impl SlowBodyLog {
+    /// Creates a hook that warns about bodies from `peer` that take longer
+    /// than `threshold` to read.
+    pub fn new(threshold: Duration, peer: Option<SocketAddr>) -> SlowBodyLog {
+        SlowBodyLog { threshold: threshold, peer: peer, started: None, bytes: 0, done: false }
+    }
+}
+
+impl ReadHook for SlowBodyLog {
+    fn before_read(&mut self) -> io::Result<()> {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+
+        Ok(())
+    }
+
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.after_splice(bytes.len())
+    }
+
+    fn observes_bytes(&self) -> bool {
+        false
+    }
+
+    fn after_splice(&mut self, n: usize) -> io::Result<()> {
+        self.bytes += n as u64;
+        if n > 0 || self.done {
+            return Ok(());
+        }
+
+        // The first empty read marks the end of the body.
+        self.done = true;
+        let elapsed = self.started.map_or(Duration::from_secs(0), |started| started.elapsed());
+        if elapsed > self.threshold {
+            let ms = elapsed.as_secs() * 1000 + (elapsed.subsec_nanos() / 1_000_000) as u64;
+            match self.peer {
+                Some(peer) => warn_!("Reading {} body bytes from {} took {}ms.",
+                                     self.bytes, peer, ms),
+                None => warn_!("Reading {} body bytes took {}ms.", self.bytes, ms),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned by reads of a request body that has been read for longer
+/// than the configured `slowloris_grace` period at an average rate below
+/// `slowloris_min_rate`, as in a slowloris attack.
+///
+/// This error is returned as the inner error of an `io::Error` of kind
+/// `TimedOut` and can be recovered with `io::Error::get_ref` and
+/// `downcast_ref`. [`read_error_status`](/rocket/data/fn.read_error_status.html)
+/// maps it to `408 Request Timeout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyTooSlow {
+    /// The number of body bytes read when the body was rejected.
+    pub read: u64,
+    /// The time from the request's arrival to the rejection.
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for BodyTooSlow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "body sent only {} bytes in {}s", self.read, self.elapsed.as_secs())
+    }
+}
+
+impl ::std::error::Error for BodyTooSlow {
+    fn description(&self) -> &str {
+        "body is being sent too slowly"
+    }
+}
+
+/// A read hook that fails reads of a body that is both old and slow: one read
+/// for longer than `grace` since `arrived` at an average rate below
+/// `min_rate` bytes per second.
+pub struct SlowlorisGuard {
+    arrived: Instant,
+    grace: Duration,
+    min_rate: u64,
+    peer: Option<SocketAddr>,
+    read: u64,
+}
+
+impl SlowlorisGuard {
+    pub fn new(
+        arrived: Instant,
+        grace: Duration,
+        min_rate: u64,
+        peer: Option<SocketAddr>
+    ) -> SlowlorisGuard {
+        SlowlorisGuard { arrived: arrived, grace: grace, min_rate: min_rate, peer: peer, read: 0 }
+    }
+
+    fn check(&self) -> io::Result<()> {
+        let elapsed = self.arrived.elapsed();
+        if elapsed < self.grace {
+            return Ok(());
+        }
+
+        // Compare `read / elapsed < min_rate` without dividing.
+        let ms = elapsed.as_secs() * 1000 + (elapsed.subsec_nanos() / 1_000_000) as u64;
+        if self.read.saturating_mul(1000) >= self.min_rate.saturating_mul(ms) {
+            return Ok(());
+        }
+
+        match self.peer {
+            Some(peer) => warn_!("Rejecting a body from {} sent at {} bytes in {}ms.",
+                                 peer, self.read, ms),
+            None => warn_!("Rejecting a body sent at {} bytes in {}ms.", self.read, ms),
+        }
+
+        let error = BodyTooSlow { read: self.read, elapsed: elapsed };
+        Err(io::Error::new(io::ErrorKind::TimedOut, error))
+    }
+}
+
+impl ReadHook for SlowlorisGuard {
+    fn before_read(&mut self) -> io::Result<()> {
+        self.check()
+    }
+
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.after_splice(bytes.len())
+    }
+
+    fn observes_bytes(&self) -> bool {
+        false
+    }
+
+    fn after_splice(&mut self, n: usize) -> io::Result<()> {
+        self.read += n as u64;
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Write, BufWriter};
+use std::ascii::AsciiExt;
+use std::fs::File;
+use std::path::Path;
+
+use super::data_stream::ReadHook;
+
+/// The metadata of a request body recorded with
+/// [`Data::record_snapshot`](/rocket/data/struct.Data.html#method.record_snapshot).
+///
+/// A `Data` created by
+/// [`Data::from_snapshot`](/rocket/data/struct.Data.html#method.from_snapshot)
+/// carries the metadata of its snapshot in its
+/// [`extensions`](/rocket/data/struct.Data.html#method.extensions).
+///
+/// A snapshot file holds one `Name: value` line for each header recorded,
+/// followed by an empty line and the raw bytes of the body. Snapshots can
+/// thus be inspected with a text editor and written by hand.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotMeta {
+    /// The value of the request's `Content-Type` header, if any.
+    pub content_type: Option<String>,
+    /// The value of the request's `Content-Encoding` header, if any.
+    pub content_encoding: Option<String>,
+}
+
+impl SnapshotMeta {
+    fn header(&self) -> Vec<u8> {
+        let mut header = String::new();
+        if let Some(ref value) = self.content_type {
+            header.push_str(&format!("Content-Type: {}\n", value));
+        }
+
+        if let Some(ref value) = self.content_encoding {
+            header.push_str(&format!("Content-Encoding: {}\n", value));
+        }
+
+        header.push('\n');
+        header.into_bytes()
+    }
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed body snapshot")
+}
+
+/// Reads the snapshot at `path`, returning its metadata and body. Headers
+/// other than those in `SnapshotMeta` are ignored.
+pub(crate) fn read<P: AsRef<Path>>(path: P) -> io::Result<(SnapshotMeta, Vec<u8>)> {
+    let mut bytes = ::std::fs::read(path)?;
+    let (mut meta, mut start) = (SnapshotMeta::default(), 0);
+    loop {
+        let end = bytes[start..].iter().position(|&b| b == b'\n').ok_or_else(malformed)?;
+        let line = ::std::str::from_utf8(&bytes[start..(start + end)])
+            .map_err(|_| malformed())?
+            .trim_right_matches('\r');
+
+        start += end + 1;
+        if line.is_empty() {
+            break;
+        }
+
+        let colon = line.find(':').ok_or_else(malformed)?;
+        let (name, value) = (line[..colon].trim(), line[(colon + 1)..].trim().to_string());
+        if name.eq_ignore_ascii_case("Content-Type") {
+            meta.content_type = Some(value);
+        } else if name.eq_ignore_ascii_case("Content-Encoding") {
+            meta.content_encoding = Some(value);
+        }
+    }
+
+    Ok((meta, bytes.split_off(start)))
+}
+
+/// A read hook that writes a snapshot of the bytes read from a body to a
+/// file. A failure to write the snapshot is logged and ends the recording;
+/// it never fails the read.
+pub struct SnapshotWriter {
+    file: Option<BufWriter<File>>,
+}
+
+impl SnapshotWriter {
+    /// Creates the snapshot file at `path`, writing the header for `meta`.
+    pub fn create<P: AsRef<Path>>(path: P, meta: &SnapshotMeta) -> io::Result<SnapshotWriter> {
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(&meta.header())?;
+        Ok(SnapshotWriter { file: Some(file) })
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let result = match self.file {
+            Some(ref mut file) if !bytes.is_empty() => file.write_all(bytes),
+            Some(ref mut file) => file.flush(),
+            None => return
+        };
+
+        if let Err(e) = result {
+            warn_!("Failed to write body snapshot: {:?}. Recording stopped.", e);
+            self.file = None;
+        }
+    }
+}
+
+impl ReadHook for SnapshotWriter {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.write(bytes);
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        self.write(&[]);
+    }
+}
@@ -0,0 +1,113 @@
+use std::ascii::AsciiExt;
+
+use http::ContentType;
+
+/// Returns the content type identified by a well-known, unambiguous magic
+/// byte prefix of `bytes`, if any.
+fn magic(bytes: &[u8]) -> Option<ContentType> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(ContentType::PNG)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(ContentType::GIF)
+    } else if bytes.starts_with(b"\xFF\xD8\xFF") {
+        Some(ContentType::JPEG)
+    } else if bytes.starts_with(b"%PDF-") {
+        Some(ContentType::PDF)
+    } else if bytes.starts_with(b"wOFF") {
+        Some(ContentType::WOFF)
+    } else {
+        None
+    }
+}
+
+#[inline(always)]
+fn is_whitespace(byte: &u8) -> bool {
+    match *byte {
+        b' ' | b'\t' | b'\r' | b'\n' => true,
+        _ => false
+    }
+}
+
+#[inline]
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| !is_whitespace(b));
+    start.map_or(&bytes[..0], |i| &bytes[i..])
+}
+
+#[inline]
+fn trim_end(bytes: &[u8]) -> &[u8] {
+    let end = bytes.iter().rposition(|b| !is_whitespace(b));
+    end.map_or(&bytes[..0], |i| &bytes[..(i + 1)])
+}
+
+#[inline]
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() >= prefix.len()
+        && bytes[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Guesses the content type of `bytes`, the first bytes of a body, by looking
+/// for well-known signatures. `complete` indicates whether `bytes` is the
+/// entire body. Returns `None` whenever the guess would be ambiguous.
+pub(crate) fn sniff(bytes: &[u8], complete: bool) -> Option<ContentType> {
+    if let Some(content_type) = magic(bytes) {
+        return Some(content_type);
+    }
+
+    // Textual formats can be preceded by whitespace; binary formats cannot.
+    let text = trim_start(bytes);
+    if starts_with_ignore_case(text, b"<?xml") {
+        return Some(ContentType::XML);
+    }
+
+    if starts_with_ignore_case(text, b"<!doctype html")
+        || starts_with_ignore_case(text, b"<html") {
+        return Some(ContentType::HTML);
+    }
+
+    // A leading `{` or `[` isn't enough to call something JSON. We only guess
+    // JSON when we've seen the whole body and it's properly bracketed.
+    if complete {
+        let text = trim_end(text);
+        match (text.first(), text.last()) {
+            (Some(&b'{'), Some(&b'}')) | (Some(&b'['), Some(&b']')) => {
+                return Some(ContentType::JSON);
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sniff;
+    use http::ContentType;
+
+    #[test]
+    fn test_sniff_magic() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\nabc", false), Some(ContentType::PNG));
+        assert_eq!(sniff(b"GIF89a...", false), Some(ContentType::GIF));
+        assert_eq!(sniff(b"%PDF-1.4", false), Some(ContentType::PDF));
+        assert_eq!(sniff(b"\xFF\xD8\xFF\xE0", false), Some(ContentType::JPEG));
+    }
+
+    #[test]
+    fn test_sniff_text() {
+        assert_eq!(sniff(b"  <?xml version=\"1.0\"?>", false), Some(ContentType::XML));
+        assert_eq!(sniff(b"<!DOCTYPE html><html>", false), Some(ContentType::HTML));
+        assert_eq!(sniff(b"<HTML>", false), Some(ContentType::HTML));
+        assert_eq!(sniff(b" {\"a\": 1}\n", true), Some(ContentType::JSON));
+        assert_eq!(sniff(b"[1, 2, 3]", true), Some(ContentType::JSON));
+    }
+
+    #[test]
+    fn test_sniff_uncertain() {
+        assert_eq!(sniff(b"", true), None);
+        assert_eq!(sniff(b"hello, world", true), None);
+        assert_eq!(sniff(b"{\"a\": 1", false), None);
+        assert_eq!(sniff(b"{\"a\": 1", true), None);
+        assert_eq!(sniff(b" \x89PNG\r\n\x1a\n", false), None);
+    }
+}
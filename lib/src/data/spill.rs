@@ -0,0 +1,91 @@
+use std::{io, fmt};
+use std::sync::{Arc, Mutex, Condvar};
+use std::time::{Duration, Instant};
+
+/// The error returned when a body couldn't be spilled to disk because the
+/// configured maximum number of concurrent spills (`max_spills`) was reached.
+///
+/// This error is returned as the inner error of an `io::Error` of kind `Other`
+/// and can be recovered with `io::Error::get_ref` and `downcast_ref`.
+/// [`read_error_status`](/rocket/data/fn.read_error_status.html) maps it to
+/// `503 Service Unavailable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpillLimitReached;
+
+impl fmt::Display for SpillLimitReached {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "too many uploads are being written to disk")
+    }
+}
+
+impl ::std::error::Error for SpillLimitReached {
+    fn description(&self) -> &str {
+        "too many uploads are being written to disk"
+    }
+}
+
+type Count = Arc<(Mutex<usize>, Condvar)>;
+
+/// Limits the number of temporary file spills in progress at once across the
+/// application. Clones share the same count.
+#[derive(Clone, Default)]
+pub(crate) struct SpillLimiter {
+    count: Count,
+}
+
+impl SpillLimiter {
+    /// Waits up to `wait` for fewer than `max` spills to be in progress, then
+    /// records the start of a spill, returning a permit that records its end
+    /// when dropped. A `max` of `0` means there is no limit. Fails with a
+    /// `SpillLimitReached` error if no spill finished in time.
+    pub(crate) fn acquire(&self, max: usize, wait: Duration) -> io::Result<SpillPermit> {
+        if max == 0 {
+            return Ok(SpillPermit { count: None });
+        }
+
+        let &(ref lock, ref cvar) = &*self.count;
+        let mut count = match lock.lock() {
+            Ok(count) => count,
+            Err(poisoned) => poisoned.into_inner()
+        };
+
+        let deadline = Instant::now() + wait;
+        while *count >= max {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(io::Error::new(io::ErrorKind::Other, SpillLimitReached));
+            }
+
+            count = match cvar.wait_timeout(count, deadline - now) {
+                Ok((count, _)) => count,
+                Err(poisoned) => poisoned.into_inner().0
+            };
+        }
+
+        *count += 1;
+        Ok(SpillPermit { count: Some(self.count.clone()) })
+    }
+}
+
+/// Marks a spill as in progress for as long as it is alive. Spills that aren't
+/// limited aren't counted.
+pub(crate) struct SpillPermit {
+    count: Option<Count>,
+}
+
+impl Drop for SpillPermit {
+    fn drop(&mut self) {
+        let &(ref lock, ref cvar) = match self.count {
+            Some(ref count) => &**count,
+            None => return
+        };
+
+        let mut count = match lock.lock() {
+            Ok(count) => count,
+            Err(poisoned) => poisoned.into_inner()
+        };
+
+        *count = count.saturating_sub(1);
+        cvar.notify_one();
+    }
+}
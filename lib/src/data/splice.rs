@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+use std::fs::File;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::ptr;
+
+use libc;
+
+/// The most bytes moved by a single `splice`: the default capacity of a pipe.
+pub const SPLICE_LEN: usize = 64 * 1024;
+
+/// A pipe through which bytes are spliced from a socket to a file. Both ends
+/// are closed when the pipe is dropped.
+pub struct Pipe {
+    read: RawFd,
+    write: RawFd,
+}
+
+impl Pipe {
+    pub fn new() -> io::Result<Pipe> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Pipe { read: fds[0], write: fds[1] })
+    }
+
+    /// Moves up to `len` bytes from `fd` into the pipe, returning the number
+    /// of bytes moved. The pipe must be empty.
+    pub fn fill_from(&self, fd: RawFd, len: usize) -> io::Result<usize> {
+        splice(fd, self.write, len)
+    }
+
+    /// Moves the `len` bytes in the pipe to `file`. If the file system doesn't
+    /// support `splice`, the bytes are copied through userspace instead.
+    pub fn drain_to(&self, mut file: &File, mut len: usize) -> io::Result<()> {
+        while len > 0 {
+            match splice(self.read, file.as_raw_fd(), len) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "spliced nothing")),
+                Ok(n) => len -= n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(ref e) if is_unsupported(e) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut buf = [0u8; 4096];
+        while len > 0 {
+            let max = ::std::cmp::min(len, buf.len());
+            let n = unsafe { libc::read(self.read, buf.as_mut_ptr() as *mut libc::c_void, max) };
+            if n < 0 {
+                let e = io::Error::last_os_error();
+                if e.kind() == io::ErrorKind::Interrupted { continue }
+                return Err(e);
+            }
+
+            file.write_all(&buf[..(n as usize)])?;
+            len -= n as usize;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.read);
+            libc::close(self.write);
+        }
+    }
+}
+
+/// Returns `true` if `e` means that one of the descriptors given to `splice`
+/// doesn't support it.
+pub fn is_unsupported(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(libc::EINVAL) || e.raw_os_error() == Some(libc::ENOSYS)
+}
+
+fn splice(from: RawFd, to: RawFd, len: usize) -> io::Result<usize> {
+    let flags = libc::SPLICE_F_MOVE | libc::SPLICE_F_MORE;
+    let n = unsafe { libc::splice(from, ptr::null_mut(), to, ptr::null_mut(), len, flags) };
+    if n < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(n as usize)
+}
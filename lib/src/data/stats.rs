@@ -0,0 +1,91 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+static PEEK_INSUFFICIENT: AtomicUsize = ATOMIC_USIZE_INIT;
+static READ_SYSCALLS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// A snapshot of the process-wide counters kept by Rocket's data layer.
+///
+/// The counters only ever increase; to measure an interval, subtract an
+/// earlier snapshot from a later one.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::DataStats;
+///
+/// let stats = DataStats::snapshot();
+/// println!("{} bodies outgrew the peek buffer", stats.peek_insufficient);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DataStats {
+    /// The number of bodies whose stream was opened even though the `peek`
+    /// buffer didn't contain the entire body. A high count relative to the
+    /// number of requests suggests that guards regularly need more than the
+    /// `peek` buffer holds.
+    pub peek_insufficient: usize,
+    /// The number of reads request bodies made from their network connections.
+    /// Reads are only counted while the `count_read_syscalls` configuration
+    /// parameter is enabled.
+    pub read_syscalls: usize,
+}
+
+impl DataStats {
+    /// Returns the current value of every counter.
+    pub fn snapshot() -> DataStats {
+        DataStats {
+            peek_insufficient: PEEK_INSUFFICIENT.load(Ordering::Relaxed),
+            read_syscalls: READ_SYSCALLS.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn record_peek_insufficient() {
+    PEEK_INSUFFICIENT.fetch_add(1, Ordering::Relaxed);
+}
+
+#[inline]
+pub(crate) fn record_read_syscall() {
+    READ_SYSCALLS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The allocation behavior of a single in-memory read of a body, reported by
+/// [`Data::bytes_limited_with_stats`].
+///
+/// The length of the bytes read and the memory reserved to hold them differ
+/// as the buffer grows. Frequent reallocations suggest that a larger initial
+/// capacity would serve better.
+///
+/// [`Data::bytes_limited_with_stats`]: /rocket/data/struct.Data.html#method.bytes_limited_with_stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllocStats {
+    /// The capacity, in bytes, of the buffer when the read ended. A buffer
+    /// never shrinks while it's read into, so this is also its peak.
+    pub capacity: usize,
+    /// The number of times the buffer grew after its initial allocation.
+    pub reallocations: usize,
+}
+
+/// A writer that appends to a vector, recording its growth in `stats`.
+pub(crate) struct TrackedVec<'a> {
+    pub(crate) vec: &'a mut Vec<u8>,
+    pub(crate) stats: &'a mut AllocStats,
+}
+
+impl<'a> Write for TrackedVec<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let before = self.vec.capacity();
+        self.vec.extend_from_slice(buf);
+        if self.vec.capacity() != before {
+            self.stats.reallocations += 1;
+        }
+
+        self.stats.capacity = self.vec.capacity();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
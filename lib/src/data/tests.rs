@@ -0,0 +1,1453 @@
+use std::io::{Read, Cursor};
+
+use super::{Data, Multipart, PartLimits, Field, Framing};
+use super::data::BodyKind;
+use super::net_stream::NetStream;
+use http::hyper::h1::HttpReader;
+
+fn body(bytes: &[u8]) -> Cursor<Vec<u8>> {
+    Cursor::new(bytes.to_vec())
+}
+
+#[test]
+fn test_empty_reader_is_empty_and_complete() {
+    let stream = HttpReader::EmptyReader(body(b"").chain(NetStream::Empty));
+    let data = Data::new(stream);
+    assert_eq!(data.peek(), &[] as &[u8]);
+    assert!(data.peek_complete());
+    assert_eq!(data.len(), Some(0));
+
+    let mut contents = vec![];
+    data.open().read_to_end(&mut contents).unwrap();
+    assert!(contents.is_empty());
+}
+
+#[test]
+fn test_empty_reader_ignores_trailing_bytes() {
+    // Bytes after a bodiless request belong to the next request.
+    let stream = HttpReader::EmptyReader(body(b"GET / HTTP/1.1").chain(NetStream::Empty));
+    let data = Data::new(stream);
+    assert_eq!(data.peek(), &[] as &[u8]);
+    assert!(data.peek_complete());
+    assert_eq!(data.len(), Some(0));
+}
+
+#[test]
+fn test_zero_length_sized_reader_is_empty_and_complete() {
+    let stream = HttpReader::SizedReader(body(b"").chain(NetStream::Empty), 0);
+    let data = Data::new(stream);
+    assert_eq!(data.peek(), &[] as &[u8]);
+    assert!(data.peek_complete());
+    assert_eq!(data.len(), Some(0));
+}
+
+#[test]
+fn test_local_empty_data() {
+    let data = Data::local(vec![]);
+    assert_eq!(data.peek(), &[] as &[u8]);
+    assert!(data.peek_complete());
+    assert_eq!(data.len(), Some(0));
+}
+
+fn from_parts(buf: &[u8], pos: usize, kind: BodyKind) -> Data {
+    Data::from_parts(buf.to_vec(), pos, NetStream::Empty, kind)
+}
+
+fn read_all(data: Data) -> Vec<u8> {
+    let mut contents = vec![];
+    data.open().read_to_end(&mut contents).unwrap();
+    contents
+}
+
+#[test]
+fn test_from_parts_skips_consumed_prefix() {
+    let data = from_parts(b"HEADERShello", 7, BodyKind::Sized(5));
+    assert_eq!(data.peek(), b"hello");
+    assert!(data.peek_complete());
+    assert_eq!(data.len(), Some(5));
+    assert_eq!(read_all(data), b"hello");
+}
+
+#[test]
+fn test_from_parts_sized_stops_at_length() {
+    // Anything after the declared length belongs to the next request.
+    let data = from_parts(b"helloGET /", 0, BodyKind::Sized(5));
+    assert_eq!(read_all(data), b"hello");
+}
+
+#[test]
+fn test_from_parts_sized_large_body() {
+    let body = vec![b'a'; 2048];
+    let data = from_parts(&body, 0, BodyKind::Sized(2048));
+    assert_eq!(data.peek().len(), 512);
+    assert!(!data.peek_complete());
+    assert_eq!(data.len(), Some(2048));
+    assert_eq!(read_all(data), body);
+}
+
+#[test]
+fn test_from_parts_chunked() {
+    let data = from_parts(b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n", 0, BodyKind::Chunked(None));
+    assert_eq!(data.peek(), b"hello world");
+    assert!(data.peek_complete());
+    assert_eq!(data.len(), Some(11));
+    assert_eq!(read_all(data), b"hello world");
+}
+
+#[test]
+fn test_from_parts_chunked_with_extensions() {
+    let body = b"5;name=value\r\nhello\r\n6 ; a=1;b=\"2\"\r\n world\r\n0;last\r\n\r\n";
+    let data = from_parts(body, 0, BodyKind::Chunked(None));
+    assert_eq!(data.peek(), b"hello world");
+    assert!(data.peek_complete());
+    assert_eq!(read_all(data), b"hello world");
+}
+
+#[test]
+fn test_from_parts_eof() {
+    let data = from_parts(b"XXhello", 2, BodyKind::Eof);
+    assert!(data.peek_complete());
+    assert_eq!(read_all(data), b"hello");
+}
+
+#[test]
+fn test_from_parts_empty() {
+    let data = from_parts(b"GET / HTTP/1.1", 0, BodyKind::Empty);
+    assert_eq!(data.peek(), &[] as &[u8]);
+    assert!(data.peek_complete());
+    assert_eq!(read_all(data), b"");
+}
+
+#[test]
+fn test_body_kind_round_trip() {
+    for &kind in &[BodyKind::Sized(10), BodyKind::Eof, BodyKind::Empty,
+                   BodyKind::Chunked(None), BodyKind::Chunked(Some(3))] {
+        assert_eq!(BodyKind::of(&kind.reader(())), kind);
+    }
+}
+
+#[test]
+fn test_chunked_peek() {
+    let data = from_parts(b"5\r\nhello\r\n0\r\n\r\n", 0, BodyKind::Chunked(None));
+    let chunked = data.chunked_peek().unwrap();
+    assert_eq!(chunked.decoded_len(), 5);
+    assert_eq!(chunked.has_more(), Some(false));
+    assert_eq!(chunked.current_chunk_remaining(), None);
+
+    let mut body = b"400\r\n".to_vec();
+    body.extend_from_slice(&[b'a'; 1024]);
+    let data = from_parts(&body, 0, BodyKind::Chunked(None));
+    let chunked = data.chunked_peek().unwrap();
+    assert_eq!(chunked.decoded_len(), 512);
+    assert_eq!(chunked.has_more(), Some(true));
+    assert_eq!(chunked.current_chunk_remaining(), Some(512));
+
+    assert!(from_parts(b"hello", 0, BodyKind::Sized(5)).chunked_peek().is_none());
+}
+
+const MULTIPART: &[u8] = b"preamble\r\n--XyZ\r\n\
+    Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+    hello\r\n--XyZ\r\n\
+    Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\
+    Content-Type: text/plain\r\n\r\n\
+    file contents\r\n--XyZ--\r\n";
+
+#[test]
+fn test_multipart_into_fields() {
+    let multipart = Multipart::with_boundary("XyZ", Data::local(MULTIPART.to_vec()));
+    let mut fields = multipart.into_fields(PartLimits::default()).unwrap();
+    match fields.get("title") {
+        Some(&Field::Text(ref text)) => assert_eq!(text, b"hello"),
+        _ => panic!("expected a text field")
+    }
+
+    let path = match fields.remove("upload").pop() {
+        Some(Field::File(file)) => {
+            assert_eq!(file.file_name(), Some("a.txt"));
+            assert_eq!(file.len(), 13);
+            assert_eq!(::std::fs::read(file.path()).unwrap(), b"file contents");
+            file.path().to_path_buf()
+        }
+        _ => panic!("expected a file field")
+    };
+
+    // The temporary file is removed when the field is dropped.
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_multipart_limits() {
+    let multipart = Multipart::with_boundary("XyZ", Data::local(MULTIPART.to_vec()));
+    assert!(multipart.into_fields(PartLimits::default().text(4)).is_err());
+
+    let multipart = Multipart::with_boundary("XyZ", Data::local(MULTIPART.to_vec()));
+    assert!(multipart.into_fields(PartLimits::default().file(12)).is_err());
+
+    let multipart = Multipart::with_boundary("XyZ", Data::local(b"--XyZ\r\n".to_vec()));
+    assert!(multipart.into_fields(PartLimits::default()).is_err());
+}
+
+#[test]
+fn test_multipart_field_limits() {
+    use super::FieldLimitExceeded;
+
+    let exceeded = |limits: PartLimits| {
+        let multipart = Multipart::with_boundary("XyZ", Data::local(MULTIPART.to_vec()));
+        let error = multipart.into_fields(limits).err().unwrap();
+        error.get_ref().and_then(|e| e.downcast_ref::<FieldLimitExceeded>()).cloned()
+    };
+
+    assert_eq!(exceeded(PartLimits::default().text(4)), Some(FieldLimitExceeded::Value(4)));
+    assert_eq!(exceeded(PartLimits::default().name(5)), Some(FieldLimitExceeded::Name(5)));
+    assert_eq!(exceeded(PartLimits::default().fields(1)), Some(FieldLimitExceeded::Fields(1)));
+}
+
+#[test]
+fn test_bench_roundtrip() {
+    assert_eq!(Data::bench_roundtrip(vec![]), b"");
+    assert_eq!(Data::bench_roundtrip(b"hello".to_vec()), b"hello");
+    assert_eq!(Data::bench_roundtrip(vec![7; 4096]), vec![7; 4096]);
+}
+
+#[cfg(feature = "gzip")]
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(bytes).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_open_gzip() {
+    let mut body = String::new();
+    let data = Data::local(gzip(b"hello, gzip"));
+    data.open_gzip().unwrap().read_to_string(&mut body).unwrap();
+    assert_eq!(body, "hello, gzip");
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_open_gzip_bad_checksum() {
+    use super::GzipChecksumError;
+
+    let mut compressed = gzip(b"hello, gzip");
+    let crc_start = compressed.len() - 8;
+    compressed[crc_start] ^= 0xFF;
+
+    let mut body = vec![];
+    let data = Data::local(compressed);
+    let error = data.open_gzip().unwrap().read_to_end(&mut body).unwrap_err();
+    assert!(error.get_ref().and_then(|e| e.downcast_ref::<GzipChecksumError>()).is_some());
+
+    assert!(Data::local(b"not gzip".to_vec()).open_gzip().is_err());
+}
+
+#[test]
+fn test_split() {
+    let mut body = vec![b'a'; 512];
+    body.extend_from_slice(b"remainder");
+    let (head, mut rest) = from_parts(&body, 0, BodyKind::Sized(body.len() as u64)).split();
+    assert_eq!(head, vec![b'a'; 512]);
+
+    let mut remainder = vec![];
+    rest.read_to_end(&mut remainder).unwrap();
+    assert_eq!(remainder, b"remainder");
+
+    let (head, mut rest) = Data::local(b"hello".to_vec()).split();
+    assert_eq!(head, b"hello");
+    assert_eq!(rest.read(&mut [0; 8]).unwrap(), 0);
+}
+
+#[test]
+fn test_open_scanned() {
+    use super::ScanVerdict;
+
+    let data = Data::local(b"hello, world".to_vec());
+    let mut stream = data.open_scanned(|_| ScanVerdict::Continue);
+    let mut contents = vec![];
+    stream.read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"hello, world");
+
+    let data = Data::local(b"hello, world".to_vec());
+    let mut stream = data.open_scanned(|chunk| {
+        if chunk.contains(&b',') { ScanVerdict::Reject } else { ScanVerdict::Continue }
+    });
+
+    assert!(stream.read(&mut [0; 64]).is_err());
+    assert!(stream.read(&mut [0; 64]).is_err());
+}
+
+#[test]
+fn test_unpeeked() {
+    let stream = HttpReader::SizedReader(body(b"hello").chain(NetStream::Empty), 5);
+    let data = Data::unpeeked(stream);
+    assert_eq!(data.peek(), &[] as &[u8]);
+    assert!(!data.peek_complete());
+    assert_eq!(data.len(), Some(5));
+    assert_eq!(read_all(data), b"hello");
+
+    let stream = HttpReader::EmptyReader(body(b"").chain(NetStream::Empty));
+    assert!(Data::unpeeked(stream).peek_complete());
+}
+
+#[test]
+fn test_truncated_sized_body() {
+    let data = from_parts(b"", 0, BodyKind::Sized(5));
+    assert!(data.is_truncated());
+    assert!(!data.peek_complete());
+
+    let data = from_parts(b"hel", 0, BodyKind::Sized(5));
+    assert!(data.is_truncated());
+    assert!(!data.peek_complete());
+
+    let data = from_parts(b"hello", 0, BodyKind::Sized(5));
+    assert!(!data.is_truncated());
+    assert!(data.peek_complete());
+}
+
+#[test]
+fn test_read_string_limited() {
+    let mut stream = Data::local(b"hello, world".to_vec()).open();
+    stream.read_exact(&mut [0; 7]).unwrap();
+    assert_eq!(stream.read_string_limited(5).unwrap(), "world");
+
+    let mut stream = Data::local(b"hello, world".to_vec()).open();
+    assert!(stream.read_string_limited(11).is_err());
+
+    let mut stream = Data::local(b"\xFF\xFE".to_vec()).open();
+    assert!(stream.read_string_limited(16).is_err());
+}
+
+#[test]
+fn test_with_buffer() {
+    let data = Data::with_buffer(b"hello".to_vec(), false);
+    assert_eq!(data.peek(), b"hello");
+    assert!(!data.peek_complete());
+    assert_eq!(read_all(data), b"hello");
+
+    assert!(Data::with_buffer(b"hello".to_vec(), true).peek_complete());
+}
+
+#[test]
+#[cfg(feature = "bytes")]
+fn test_bytes_buf() {
+    let data = Data::local(b"hello".to_vec());
+    assert_eq!(&data.bytes_buf().unwrap()[..], b"hello");
+
+    let big = vec![b'a'; 2048];
+    let data = from_parts(&big, 0, BodyKind::Sized(2048));
+    assert_eq!(&data.bytes_buf().unwrap()[..], &big[..]);
+}
+
+#[test]
+fn test_info() {
+    let info = from_parts(b"hello", 0, BodyKind::Sized(5)).info();
+    assert_eq!(info.framing, Framing::Sized);
+    assert_eq!(info.declared_len, Some(5));
+    assert_eq!(info.peek_len, 5);
+    assert!(info.is_complete);
+    assert!(!info.is_secure);
+    assert_eq!(info.peer, None);
+}
+
+#[test]
+#[cfg(feature = "tls")]
+fn test_tls_info_of_plaintext_body() {
+    assert_eq!(from_parts(b"hello", 0, BodyKind::Sized(5)).tls_info(), None);
+    assert_eq!(Data::local(b"hello".to_vec()).tls_info(), None);
+}
+
+#[test]
+fn test_stream_to_channel() {
+    use std::sync::mpsc::sync_channel;
+    use super::{BodyBudgets, ConnectionBudgetExceeded};
+
+    let (tx, rx) = sync_channel(8);
+    let data = Data::local(b"hello, world".to_vec());
+    assert_eq!(data.stream_to_channel(tx, 5).unwrap(), 12);
+
+    let chunks: Vec<_> = rx.iter().map(|chunk| chunk.unwrap()).collect();
+    assert_eq!(chunks, vec![b"hello".to_vec(), b", wor".to_vec(), b"ld".to_vec()]);
+
+    let (tx, rx) = sync_channel(8);
+    drop(rx);
+    assert!(Data::local(b"hello".to_vec()).stream_to_channel(tx, 5).is_err());
+
+    // A read error is returned with its cause; the receiver gets a copy.
+    let budgets = BodyBudgets::default();
+    let peer = "127.0.0.1:8000".parse().unwrap();
+    let (tx, rx) = sync_channel(8);
+    let data = Data::local(b"hello".to_vec()).with_hook(budgets.charge(peer, 3));
+    let err = data.stream_to_channel(tx, 2).unwrap_err();
+    assert!(err.get_ref().map_or(false, |e| e.is::<ConnectionBudgetExceeded>()));
+
+    let copy = rx.iter().last().unwrap().unwrap_err();
+    assert_eq!(copy.kind(), err.kind());
+    assert_eq!(copy.to_string(), err.to_string());
+}
+
+#[test]
+#[cfg(feature = "crc")]
+fn test_stream_with_crc32c() {
+    let mut sink = vec![];
+    let data = Data::local(b"123456789".to_vec());
+    assert_eq!(data.stream_with_crc32c(&mut sink).unwrap(), (9, 0xE3069283));
+    assert_eq!(sink, b"123456789");
+
+    let (n, crc) = Data::local(vec![]).stream_with_crc32c(vec![]).unwrap();
+    assert_eq!((n, crc), (0, 0));
+}
+
+#[test]
+#[cfg(feature = "cas")]
+fn test_stream_to_cas() {
+    use std::fs;
+    use super::HashAlgorithm;
+
+    let dir = ::std::env::temp_dir().join(format!("rocket-cas-test.{}", ::std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    let data = Data::local(b"hello".to_vec());
+    let (path, n) = data.stream_to_cas(&dir, HashAlgorithm::Sha256).unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(path, dir.join(digest));
+    let mut contents = vec![];
+    fs::File::open(&path).unwrap().read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"hello");
+
+    let data = Data::local(b"hello".to_vec());
+    let (dup, _) = data.stream_to_cas(&dir, HashAlgorithm::Sha256).unwrap();
+    assert_eq!(dup, path);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_probe() {
+    use super::{Probe, Format};
+
+    let data = Data::local(b"RIFF\x00\x00\x00\x00WEBPVP8 ".to_vec());
+    assert_eq!(data.probe(Probe::DEFAULTS), Some(Format("webp")));
+
+    let custom = [Probe { offset: 0, magic: b"PAR1", format: Format("parquet") }];
+    let data = Data::local(b"PAR1....".to_vec());
+    assert_eq!(data.probe(Probe::DEFAULTS), None);
+    assert_eq!(data.probe(&custom), Some(Format("parquet")));
+    assert_eq!(read_all(data), b"PAR1....");
+}
+
+#[test]
+fn test_small_reads_are_coalesced() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::data_stream::ReadHook;
+
+    struct Reads(Arc<AtomicUsize>);
+
+    impl ReadHook for Reads {
+        fn after_read(&mut self, _: &[u8]) -> ::std::io::Result<()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    let body = vec![b'a'; 2048];
+    let reads = Arc::new(AtomicUsize::new(0));
+    let data = from_parts(&body, 0, BodyKind::Sized(2048));
+    let mut stream = data.with_hook(Reads(reads.clone())).open();
+
+    let (mut contents, mut byte) = (vec![], [0]);
+    while stream.read(&mut byte).unwrap() == 1 {
+        contents.push(byte[0]);
+    }
+
+    assert_eq!(contents, body);
+    assert!(reads.load(Ordering::Relaxed) < 8);
+}
+
+#[test]
+fn test_streams_are_send() {
+    use super::DataStream;
+
+    fn assert_send<T: Send>() {  }
+    assert_send::<Data>();
+    assert_send::<DataStream>();
+}
+
+#[test]
+fn test_peek_insufficient_stat() {
+    use super::DataStats;
+
+    let before = DataStats::snapshot().peek_insufficient;
+    let body = vec![b'a'; 2048];
+    read_all(from_parts(&body, 0, BodyKind::Sized(2048)));
+    assert!(DataStats::snapshot().peek_insufficient > before);
+}
+
+#[test]
+fn test_stream_to_all() {
+    use std::io::{self, Write};
+    use super::SinkFailure;
+
+    struct Broken;
+
+    impl Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "broken"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let (mut a, mut b) = (vec![], vec![]);
+    {
+        let writers: &mut [&mut Write] = &mut [&mut a, &mut b];
+        let n = Data::local(b"hello".to_vec()).stream_to_all(writers, SinkFailure::Abort);
+        assert_eq!(n.unwrap(), 5);
+    }
+    assert_eq!((&a[..], &b[..]), (&b"hello"[..], &b"hello"[..]));
+
+    let mut c = vec![];
+    {
+        let writers: &mut [&mut Write] = &mut [&mut Broken, &mut c];
+        let data = Data::local(b"hello".to_vec());
+        assert!(data.stream_to_all(writers, SinkFailure::Continue).is_ok());
+    }
+    assert_eq!(c, b"hello");
+
+    let writers: &mut [&mut Write] = &mut [&mut Broken, &mut vec![]];
+    let data = Data::local(b"hello".to_vec());
+    assert!(data.stream_to_all(writers, SinkFailure::Abort).is_err());
+}
+
+#[test]
+fn test_deadline() {
+    use std::time::{Duration, Instant};
+    use super::deadline::Deadline;
+
+    let later = Instant::now() + Duration::from_secs(60);
+    let data = from_parts(b"hello", 0, BodyKind::Sized(5)).with_hook(Deadline::new(later));
+    assert_eq!(read_all(data), b"hello");
+
+    let data = from_parts(b"hello", 0, BodyKind::Sized(5)).with_hook(Deadline::new(Instant::now()));
+    let err = data.open().read(&mut [0; 8]).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::TimedOut);
+}
+
+// Creates a `Data` for the sized body `bytes` that runs `hook` over the reads
+// that fill its peek buffer. Returns the error those reads failed with, if any.
+fn peeked(bytes: &[u8], hook: Box<super::data_stream::ReadHook>) -> Result<Data, ::std::io::Error> {
+    use config::PeekErrorPolicy;
+    use super::DataError;
+
+    let len = bytes.len() as u64;
+    let stream = HttpReader::SizedReader(body(bytes).chain(NetStream::Empty), len);
+    match Data::peeked(stream, PeekErrorPolicy::FailRequest, vec![hook]) {
+        Ok(data) => Ok(data),
+        Err(DataError::PeekFailed(e)) => Err(e),
+        Err(e) => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_hooks_run_over_peek() {
+    use std::time::Instant;
+    use super::{BodyBudgets, ConnectionBudgetExceeded};
+    use super::deadline::Deadline;
+
+    let err = peeked(b"hello", Box::new(Deadline::new(Instant::now()))).err().unwrap();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::TimedOut);
+
+    let budgets = BodyBudgets::default();
+    let peer = "127.0.0.1:8000".parse().unwrap();
+    let err = peeked(b"hello", Box::new(budgets.charge(peer, 3))).err().unwrap();
+    assert!(err.get_ref().map_or(false, |e| e.is::<ConnectionBudgetExceeded>()));
+
+    // Peeked bytes are charged once, not again when they're read.
+    let budgets = BodyBudgets::default();
+    let data = peeked(b"hello", Box::new(budgets.charge(peer, 5))).unwrap();
+    assert_eq!(data.peek(), b"hello");
+    assert_eq!(read_all(data), b"hello");
+    assert!(!budgets.take_exhausted(peer, 5));
+}
+
+#[test]
+#[cfg(feature = "digest")]
+fn test_verify_content_digest() {
+    use super::ContentDigestMismatch;
+
+    let header = concat!("sha-256=:LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=:, ",
+                         "md5=:XUFAKrxLKna5cZ2REBfFkg==:");
+    let body = Data::local(b"hello".to_vec()).verify_content_digest(header, 64).unwrap();
+    assert_eq!(body, b"hello");
+
+    let err = Data::local(b"jello".to_vec()).verify_content_digest(header, 64).unwrap_err();
+    let mismatch = err.get_ref().and_then(|e| e.downcast_ref::<ContentDigestMismatch>());
+    assert_eq!(mismatch.map(|m| m.algorithm), Some("sha-256"));
+
+    assert!(Data::local(b"hello".to_vec()).verify_content_digest("md5=:AAAA:", 64).is_err());
+    assert!(Data::local(b"hello".to_vec()).verify_content_digest(header, 2).is_err());
+}
+
+#[test]
+fn test_spill_limiter() {
+    use std::time::Duration;
+    use super::{SpillLimiter, SpillLimitReached, read_error_status};
+    use http::Status;
+
+    let limiter = SpillLimiter::default();
+    let first = limiter.acquire(1, Duration::from_millis(0)).unwrap();
+    let err = limiter.clone().acquire(1, Duration::from_millis(10)).err().unwrap();
+    assert!(err.get_ref().map_or(false, |e| e.is::<SpillLimitReached>()));
+    assert_eq!(read_error_status(&err, Status::BadRequest), Status::ServiceUnavailable);
+
+    let _unlimited = limiter.acquire(0, Duration::from_millis(0)).unwrap();
+    drop(first);
+    assert!(limiter.acquire(1, Duration::from_millis(0)).is_ok());
+}
+
+#[test]
+fn test_offset_chunks_span_peek_buffer() {
+    let bytes: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+    let stream = HttpReader::SizedReader(body(&bytes).chain(NetStream::Empty), 1000);
+    let data = Data::new(stream);
+    assert_eq!(data.peek().len(), 512);
+
+    let chunks: Vec<_> = data.offset_chunks(300).map(|c| c.unwrap()).collect();
+    let offsets: Vec<_> = chunks.iter().map(|&(offset, _)| offset).collect();
+    assert_eq!(offsets, vec![0, 300, 600, 900]);
+    assert_eq!(chunks[3].1.len(), 100);
+    assert_eq!(chunks.iter().flat_map(|c| c.1.clone()).collect::<Vec<_>>(), bytes);
+
+    assert_eq!(Data::local(vec![]).offset_chunks(16).count(), 0);
+}
+
+#[test]
+fn test_client_gone() {
+    use std::io::ErrorKind;
+    use super::ClientGone;
+
+    let mut stream = from_parts(b"hel", 0, BodyKind::Sized(5)).open();
+    let err = stream.read_to_end(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ConnectionAborted);
+    let gone = err.get_ref().and_then(|e| e.downcast_ref::<ClientGone>());
+    assert_eq!(gone, Some(&ClientGone { remaining: 2 }));
+
+    assert_eq!(read_all(from_parts(b"hello", 0, BodyKind::Sized(5))), b"hello");
+    assert_eq!(read_all(from_parts(b"hel", 0, BodyKind::Eof)), b"hel");
+}
+
+#[test]
+fn test_extensions() {
+    #[derive(Debug, PartialEq)]
+    struct Format(&'static str);
+
+    let data = Data::local(b"{}".to_vec());
+    assert_eq!(data.extensions().get::<Format>(), None);
+    assert!(data.extensions().insert(Format("json")));
+    assert!(!data.extensions().insert(Format("xml")));
+    assert!(data.extensions().insert(7usize));
+    assert_eq!(data.extensions().get::<Format>(), Some(&Format("json")));
+    assert_eq!(data.extensions().get::<usize>(), Some(&7));
+}
+
+#[test]
+fn test_form_pairs() {
+    use super::FormPairs;
+
+    let pairs = |body: &[u8], limit| -> Vec<_> {
+        FormPairs::new(Data::local(body.to_vec()), limit).map(|p| p.map_err(|e| e.kind())).collect()
+    };
+
+    let ok = |k: &str, v: &str| Ok((k.to_string(), v.to_string()));
+    assert_eq!(pairs(b"a=1&b=hello+world&&c=%26%3D&d=", 64),
+               vec![ok("a", "1"), ok("b", "hello world"), ok("c", "&="), ok("d", "")]);
+
+    // A value spanning the end of the peek buffer.
+    let mut body = b"skip=".to_vec();
+    body.extend_from_slice(&[b'x'; 505]);
+    body.extend_from_slice(b"&long=abcdefgh&last=1");
+    let stream = HttpReader::SizedReader(Cursor::new(body.clone()).chain(NetStream::Empty),
+                                         body.len() as u64);
+    let results: Vec<_> = FormPairs::new(Data::new(stream), 1024).map(|p| p.unwrap()).collect();
+    assert_eq!(results[1], ("long".to_string(), "abcdefgh".to_string()));
+    assert_eq!(results[2], ("last".to_string(), "1".to_string()));
+
+    use std::io::ErrorKind::InvalidData;
+    assert_eq!(pairs(b"a=1&b", 64), vec![ok("a", "1"), Err(InvalidData)]);
+    assert_eq!(pairs(b"=1&b=2", 64), vec![Err(InvalidData)]);
+    assert_eq!(pairs(b"a=12345", 4), vec![Err(InvalidData)]);
+}
+
+#[test]
+fn test_form_pairs_limits() {
+    use super::{FormPairs, FormLimits, FieldLimitExceeded};
+
+    let pairs = |body: &[u8], limits| -> Vec<_> {
+        FormPairs::with_limits(Data::local(body.to_vec()), limits).map(|p| p.map_err(|e| {
+            e.get_ref().and_then(|e| e.downcast_ref::<FieldLimitExceeded>()).cloned()
+        })).collect()
+    };
+
+    let ok = |k: &str, v: &str| Ok((k.to_string(), v.to_string()));
+    let limits = FormLimits::default().name(3).value(4).fields(2);
+    assert_eq!(pairs(b"abc=1234&d=", limits), vec![ok("abc", "1234"), ok("d", "")]);
+    assert_eq!(pairs(b"abcd=1", limits), vec![Err(Some(FieldLimitExceeded::Name(3)))]);
+    assert_eq!(pairs(b"abcd", limits), vec![Err(Some(FieldLimitExceeded::Name(3)))]);
+    assert_eq!(pairs(b"a=12345", limits), vec![Err(Some(FieldLimitExceeded::Value(4)))]);
+    assert_eq!(pairs(b"a=1&b=2&&c=3", limits),
+               vec![ok("a", "1"), ok("b", "2"), Err(Some(FieldLimitExceeded::Fields(2)))]);
+}
+
+#[test]
+fn test_bytes_limited() {
+    use super::BodyTooLarge;
+
+    assert_eq!(Data::local(b"hello".to_vec()).bytes_limited(5).unwrap(), Ok(b"hello".to_vec()));
+
+    let err = Data::local(b"hello".to_vec()).bytes_limited(4).unwrap().unwrap_err();
+    assert_eq!(err, BodyTooLarge { limit: 4, read: 0, declared: Some(5) });
+
+    // The length of an unterminated body isn't known until it's read.
+    let data = from_parts(&[b'a'; 600], 0, BodyKind::Eof);
+    let err = data.bytes_limited(3).unwrap().unwrap_err();
+    assert_eq!(err, BodyTooLarge { limit: 3, read: 4, declared: None });
+}
+
+#[test]
+fn test_is_buffered() {
+    use super::Overflow;
+
+    assert!(Data::local(b"hello".to_vec()).is_buffered());
+    assert!(from_parts(b"hello", 0, BodyKind::Sized(5)).is_buffered());
+    assert!(!from_parts(b"hel", 0, BodyKind::Sized(5)).is_buffered());
+
+    let data = from_parts(&[b'a'; 600], 0, BodyKind::Sized(600));
+    assert!(!data.is_buffered());
+    let data = data.buffer_fully(1024, Overflow::Error).unwrap();
+    assert!(data.is_buffered());
+    assert_eq!(read_all(data).len(), 600);
+}
+
+#[test]
+fn test_buffer_fully_runs_hooks() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use super::Overflow;
+    use super::data_stream::ReadHook;
+    use super::deadline::Deadline;
+
+    struct Record(Arc<Mutex<Vec<u8>>>);
+
+    impl ReadHook for Record {
+        fn after_read(&mut self, bytes: &[u8]) -> ::std::io::Result<()> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    // The hook sees the bytes read past the `peek` buffer as they're buffered
+    // and the rest as the stream is read, but never the same byte twice.
+    let body: Vec<u8> = (0..600).map(|i| i as u8).collect();
+    let seen = Arc::new(Mutex::new(vec![]));
+    let data = from_parts(&body, 0, BodyKind::Sized(600)).with_hook(Record(seen.clone()));
+    let data = data.buffer_fully(1024, Overflow::Error).unwrap();
+    assert_eq!(&seen.lock().unwrap()[..], &body[512..]);
+    assert_eq!(read_all(data), body);
+    assert_eq!(&seen.lock().unwrap()[..88], &body[512..]);
+    assert_eq!(&seen.lock().unwrap()[88..], &body[..512]);
+
+    let data = from_parts(&body, 0, BodyKind::Sized(600)).with_hook(Deadline::new(Instant::now()));
+    let err = data.buffer_fully(1024, Overflow::Stream).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn test_bytes_limited_with_stats() {
+    use super::AllocStats;
+
+    // A declared length is reserved up front, so the buffer never grows.
+    let mut stats = AllocStats::default();
+    let data = from_parts(&[b'a'; 600], 0, BodyKind::Sized(600));
+    let body = data.bytes_limited_with_stats(1024, &mut stats).unwrap().unwrap();
+    assert_eq!(body.len(), 600);
+    assert_eq!(stats, AllocStats { capacity: 600, reallocations: 0 });
+
+    // Without one, the buffer starts at the size of the `peek` buffer.
+    let data = from_parts(&[b'a'; 20000], 0, BodyKind::Eof);
+    let body = data.bytes_limited_with_stats(1 << 20, &mut stats).unwrap().unwrap();
+    assert_eq!(body.len(), 20000);
+    assert!(stats.reallocations > 0);
+    assert_eq!(stats.capacity, body.capacity());
+}
+
+#[test]
+fn test_read_tail() {
+    let bytes: Vec<u8> = (0..20000).map(|i| (i % 251) as u8).collect();
+    let data = from_parts(&bytes, 0, BodyKind::Sized(20000));
+    assert_eq!(data.read_tail(1000).unwrap(), &bytes[19000..]);
+
+    let data = from_parts(&bytes, 0, BodyKind::Sized(20000));
+    assert_eq!(data.read_tail(8193).unwrap(), &bytes[(20000 - 8193)..]);
+
+    assert_eq!(Data::local(b"hello".to_vec()).read_tail(3).unwrap(), b"llo");
+    assert_eq!(Data::local(b"hello".to_vec()).read_tail(10).unwrap(), b"hello");
+    assert_eq!(Data::local(b"hello".to_vec()).read_tail(0).unwrap(), b"");
+}
+
+#[test]
+fn test_shutdown_deadline() {
+    use std::time::Duration;
+    use super::{ShutdownHandle, ShuttingDown};
+    use super::shutdown::ShutdownDeadline;
+
+    let handle = ShutdownHandle::default();
+    let hook = ShutdownDeadline::new(handle.clone(), Duration::from_secs(60));
+    let data = Data::local(b"hello".to_vec()).with_hook(hook);
+    handle.shutdown();
+    assert!(handle.is_shutting_down());
+    assert_eq!(read_all(data), b"hello");
+
+    let hook = ShutdownDeadline::new(handle.clone(), Duration::from_secs(0));
+    let data = Data::local(b"hello".to_vec()).with_hook(hook);
+    let err = data.open().read(&mut [0; 8]).unwrap_err();
+    assert!(err.get_ref().map_or(false, |e| e.is::<ShuttingDown>()));
+
+    // A body that arrives after the grace period fails as its peek is filled.
+    let hook = ShutdownDeadline::new(handle.clone(), Duration::from_secs(0));
+    let err = peeked(b"hello", Box::new(hook)).err().unwrap();
+    assert!(err.get_ref().map_or(false, |e| e.is::<ShuttingDown>()));
+}
+
+#[test]
+fn test_read_struct() {
+    use super::{BigEndian, LittleEndian};
+
+    let mut stream = Data::local(b"\x01\x00\x02\x03\x00\x00\x00rest".to_vec()).open();
+    let header: (u8, BigEndian<u16>, LittleEndian<u32>) = stream.read_struct().unwrap();
+    assert_eq!(header, (1, BigEndian(2), LittleEndian(3)));
+
+    let mut rest = vec![];
+    stream.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"rest");
+
+    let mut stream = Data::local(b"\x01\x02\x03".to_vec()).open();
+    let error = stream.read_struct::<BigEndian<u32>>().err().unwrap();
+    assert_eq!(error.kind(), ::std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_try_read() {
+    let mut stream = Data::local(b"hello".to_vec()).open();
+    let mut buf = [0; 3];
+    assert_eq!(stream.try_read(&mut buf).unwrap(), Some(3));
+    assert_eq!(&buf, b"hel");
+    assert_eq!(stream.try_read(&mut buf).unwrap(), Some(2));
+    assert_eq!(stream.try_read(&mut buf).unwrap(), Some(0));
+}
+
+#[test]
+fn test_malformed_chunks() {
+    use super::DataError;
+
+    fn malformed_chunk(body: &[u8]) -> Option<u64> {
+        let mut contents = vec![];
+        let error = Data::local_chunked(body.to_vec()).open().read_to_end(&mut contents).err()?;
+        match error.get_ref().and_then(|e| e.downcast_ref::<DataError>()) {
+            Some(&DataError::MalformedChunk { index }) => Some(index),
+            _ => panic!("unexpected error: {:?}", error)
+        }
+    }
+
+    let body = b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n";
+    assert_eq!(read_all(Data::local_chunked(body.to_vec())), b"hello world");
+    assert_eq!(malformed_chunk(body), None);
+
+    // A chunk shorter than its declared size, a chunk longer than it, a body
+    // ending inside a chunk, and an invalid chunk size.
+    assert_eq!(malformed_chunk(b"5\r\nhel\r\n0\r\n\r\n"), Some(0));
+    assert_eq!(malformed_chunk(b"5\r\nhello\r\n3\r\nworld\r\n0\r\n\r\n"), Some(1));
+    assert_eq!(malformed_chunk(b"a\r\nhello"), Some(0));
+    assert_eq!(malformed_chunk(b"5\r\nhello\r\nzz\r\nhello\r\n0\r\n\r\n"), Some(1));
+
+    // A malformed chunk found after the `peek` buffer is filled.
+    let mut body = b"258\r\n".to_vec();
+    body.extend_from_slice(&[b'a'; 600]);
+    body.extend_from_slice(b"\r\n3\r\nabcdef\r\n0\r\n\r\n");
+    assert_eq!(malformed_chunk(&body), Some(1));
+}
+
+#[test]
+fn test_read_into_arena() {
+    use super::Arena;
+
+    let arena = Arena::with_chunk_size(16);
+    let first = Data::local(b"hello".to_vec()).read_into_arena(&arena).unwrap();
+    let second = Data::local(b"world".to_vec()).read_into_arena(&arena).unwrap();
+    assert_eq!(arena.allocated(), 16);
+
+    // A body of unknown length that outgrows its chunk is moved to a new one.
+    let body: Vec<u8> = (0..600).map(|i| i as u8).collect();
+    let third = from_parts(&body, 0, BodyKind::Eof).read_into_arena(&arena).unwrap();
+    let empty = Data::local(vec![]).read_into_arena(&arena).unwrap();
+
+    assert_eq!((first, second, empty), (&b"hello"[..], &b"world"[..], &b""[..]));
+    assert_eq!(third, &body[..]);
+    assert!(arena.allocated() > 600);
+}
+
+#[test]
+fn test_buffered_stream_bytes() {
+    let data = from_parts(&[b'a'; 600], 0, BodyKind::Sized(600));
+    assert_eq!(data.peek().len(), 512);
+    assert_eq!(data.buffered_stream_bytes(), 88);
+
+    let data = from_parts(b"HEADERShello", 7, BodyKind::Sized(5));
+    assert_eq!(data.buffered_stream_bytes(), 0);
+}
+
+#[test]
+fn test_from_tcp_stream() {
+    use std::io::Write;
+    use std::net::{TcpListener, TcpStream};
+    use super::Framing;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    client.write_all(b" world!").unwrap();
+
+    let data = Data::from_tcp_stream(server, b"hello".to_vec(), Framing::Sized, Some(11)).unwrap();
+    assert_eq!(data.len(), Some(11));
+    assert_eq!(read_all(data), b"hello world");
+
+    let server = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    assert!(Data::from_tcp_stream(server, vec![], Framing::Sized, None).is_err());
+}
+
+#[test]
+fn test_data_stream_finish() {
+    let mut stream = from_parts(&[b'a'; 600], 0, BodyKind::Sized(600)).open();
+    stream.read_exact(&mut [0; 10]).unwrap();
+    assert_eq!(stream.finish().unwrap(), 600);
+
+    let body = b"5\r\nhello\r\n0\r\n\r\n";
+    assert_eq!(Data::local_chunked(body.to_vec()).open().finish().unwrap(), 5);
+
+    // A sized body that ends before its declared length.
+    let error = from_parts(b"hello", 0, BodyKind::Sized(10)).open().finish().err().unwrap();
+    assert_eq!(error.kind(), ::std::io::ErrorKind::ConnectionAborted);
+}
+
+#[test]
+fn test_limit() {
+    use super::{BodyTooLarge, read_error_status};
+    use http::Status;
+
+    assert!(from_parts(b"hello", 0, BodyKind::Sized(5)).limit(4).is_err());
+    let data = from_parts(b"hello", 0, BodyKind::Sized(5)).limit(5).unwrap();
+    assert_eq!(read_all(data), b"hello");
+
+    // A body without a declared length fails once it grows past the limit.
+    let mut body = b"258\r\n".to_vec();
+    body.extend_from_slice(&[b'a'; 600]);
+    body.extend_from_slice(b"\r\n0\r\n\r\n");
+    let data = Data::local_chunked(body).limit(550).unwrap();
+    let error = data.open().read_to_end(&mut vec![]).unwrap_err();
+    assert!(error.get_ref().map_or(false, |e| e.is::<BodyTooLarge>()));
+    assert_eq!(read_error_status(&error, Status::BadRequest), Status::PayloadTooLarge);
+}
+
+#[test]
+fn test_string_without_bom() {
+    let string = |body: &[u8]| Data::from_bytes(body.to_vec()).string_without_bom(64);
+    assert_eq!(string(b"\xEF\xBB\xBFhello").unwrap(), "hello");
+    assert_eq!(string(b"\xFF\xFEh\x00i\x00").unwrap(), "hi");
+    assert_eq!(string(b"\xFE\xFF\x00h\x00i").unwrap(), "hi");
+    assert_eq!(string(b"hello").unwrap(), "hello");
+
+    // A body that's only a byte order mark is empty.
+    assert_eq!(string(b"\xEF\xBB\xBF").unwrap(), "");
+    assert_eq!(string(b"\xFF\xFE").unwrap(), "");
+
+    assert!(string(b"\xFF\xFEh").is_err());
+    assert!(string(&[b'a'; 65]).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pipe_through() {
+    use std::process::Command;
+
+    // A body larger than a pipe's buffer would deadlock if it were written
+    // before the output was read.
+    let body = vec![b'a'; 1 << 20];
+    let mut output = Data::from_bytes(body.clone()).pipe_through(Command::new("cat")).unwrap();
+    let mut echoed = vec![];
+    output.read_to_end(&mut echoed).unwrap();
+    assert_eq!(echoed, body);
+
+    let (written, status) = output.finish().unwrap();
+    assert_eq!(written, 1 << 20);
+    assert!(status.success());
+
+    // A process that exits without reading its input isn't a failure.
+    let output = Data::from_bytes(body).pipe_through(Command::new("true")).unwrap();
+    assert!(output.finish().unwrap().1.success());
+}
+
+#[test]
+fn test_edit_peek() {
+    let mut body = b"SIG abc\n".to_vec();
+    body.extend_from_slice(&[b'a'; 600]);
+    let mut data = from_parts(&body, 0, BodyKind::Sized(body.len() as u64));
+    assert!(!data.peek_complete());
+
+    data.edit_peek(|prefix| {
+        prefix.drain(..8);
+        prefix.splice(..0, b"ok:".iter().cloned());
+    });
+
+    assert_eq!(data.len(), Some(603));
+    assert!(data.peek().starts_with(b"ok:aaa"));
+
+    let mut stream = data.open();
+    let mut contents = vec![];
+    stream.read_to_end(&mut contents).unwrap();
+    assert_eq!(&contents[..3], b"ok:");
+    assert_eq!(&contents[3..], &[b'a'; 600][..]);
+    assert_eq!(stream.finish().unwrap(), 603);
+}
+
+#[test]
+fn test_has_conflicting_length() {
+    use rocket::Rocket;
+    use request::Request;
+    use http::{Method, Header};
+    use super::data::has_conflicting_length;
+
+    let rocket = Rocket::custom(::config::Config::development().unwrap(), true);
+    let request = |headers: &[(&'static str, &'static str)]| {
+        let mut request = Request::new(&rocket, Method::Post, "/");
+        for &(name, value) in headers {
+            request.add_header(Header::new(name, value));
+        }
+
+        has_conflicting_length(&request)
+    };
+
+    assert!(request(&[("Content-Length", "5"), ("Transfer-Encoding", "chunked")]));
+    assert!(request(&[("Content-Length", "5"), ("Transfer-Encoding", "gzip, Chunked")]));
+    assert!(!request(&[("Content-Length", "5")]));
+    assert!(!request(&[("Transfer-Encoding", "chunked")]));
+    assert!(!request(&[("Content-Length", "5"), ("Transfer-Encoding", "gzip")]));
+}
+
+#[test]
+fn test_check_negotiated_version() {
+    use config::TlsVersion::*;
+    use super::DataError;
+    use super::data::check_negotiated_version;
+
+    assert!(check_negotiated_version(Some(V1_2), V1_2).is_ok());
+    assert!(check_negotiated_version(Some(V1_3), V1_2).is_ok());
+    assert!(check_negotiated_version(Some(V1_0), V1_0).is_ok());
+
+    match check_negotiated_version(Some(V1_1), V1_2) {
+        Err(DataError::TlsVersionTooOld { negotiated, min }) => {
+            assert_eq!(negotiated, Some(V1_1));
+            assert_eq!(min, V1_2);
+        }
+        _ => panic!("TLS 1.1 accepted with a minimum of TLS 1.2"),
+    }
+
+    match check_negotiated_version(None, V1_0) {
+        Err(DataError::TlsVersionTooOld { negotiated: None, min: V1_0 }) => {}
+        _ => panic!("a version older than TLS 1.0 was accepted"),
+    }
+}
+
+#[test]
+#[cfg(feature = "tls")]
+fn test_tls_info_version() {
+    use rustls::{ProtocolVersion, CipherSuite};
+    use config::TlsVersion;
+    use super::TlsInfo;
+
+    let info = |version| TlsInfo {
+        version: version,
+        cipher_suite: CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+    };
+
+    assert_eq!(info(ProtocolVersion::TLSv1_0).tls_version(), Some(TlsVersion::V1_0));
+    assert_eq!(info(ProtocolVersion::TLSv1_1).tls_version(), Some(TlsVersion::V1_1));
+    assert_eq!(info(ProtocolVersion::TLSv1_2).tls_version(), Some(TlsVersion::V1_2));
+    assert_eq!(info(ProtocolVersion::TLSv1_3).tls_version(), Some(TlsVersion::V1_3));
+    assert_eq!(info(ProtocolVersion::SSLv3).tls_version(), None);
+}
+
+#[test]
+fn test_reread() {
+    let data = from_parts(b"hello", 0, BodyKind::Sized(5));
+    for _ in 0..2 {
+        let mut contents = String::new();
+        data.reread().unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "hello");
+    }
+
+    assert_eq!(read_all(data), b"hello");
+
+    // A body that isn't entirely in memory can't be reread.
+    let data = from_parts(&[b'a'; 600], 0, BodyKind::Sized(600));
+    assert!(data.reread().is_none());
+    assert_eq!(read_all(data).len(), 600);
+}
+
+#[test]
+fn test_fill_peek_policies() {
+    use std::io;
+    use config::PeekErrorPolicy;
+    use super::data::fill_peek;
+
+    // Yields "abc", times out `failures` times, then yields "def".
+    struct Flaky { reads: usize, failures: usize }
+
+    impl Read for Flaky {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            match self.reads {
+                1 => { buf[..3].copy_from_slice(b"abc"); Ok(3) }
+                n if n < 2 + self.failures => Err(io::ErrorKind::TimedOut.into()),
+                n if n == 2 + self.failures => { buf[..3].copy_from_slice(b"def"); Ok(3) }
+                _ => Ok(0)
+            }
+        }
+    }
+
+    let mut buf = [0; 16];
+    let flaky = |failures| Flaky { reads: 0, failures: failures };
+    let n = fill_peek(&mut flaky(1), &mut buf, PeekErrorPolicy::RetryOnce).unwrap();
+    assert_eq!(&buf[..n], b"abcdef");
+
+    assert!(fill_peek(&mut flaky(2), &mut buf, PeekErrorPolicy::RetryOnce).is_err());
+    assert!(fill_peek(&mut flaky(1), &mut buf, PeekErrorPolicy::LogAndEmpty).is_err());
+    assert!(fill_peek(&mut flaky(1), &mut buf, PeekErrorPolicy::FailRequest).is_err());
+}
+
+#[test]
+fn test_open_streams() {
+    use super::connection::{OpenStreams, MAX_OPEN_STREAMS};
+
+    let streams = OpenStreams::default();
+    let peer = "127.0.0.1:8000".parse().unwrap();
+    let other = "127.0.0.1:8001".parse().unwrap();
+
+    // A leaked body blocks the next on its connection, and only on it.
+    let leaked = streams.acquire(peer, MAX_OPEN_STREAMS).unwrap();
+    assert!(streams.acquire(peer, MAX_OPEN_STREAMS).is_none());
+    assert!(streams.acquire(other, MAX_OPEN_STREAMS).is_some());
+
+    // The body is closed once its stream is dropped.
+    let stream = Data::local(b"hello".to_vec()).open().with_hook(leaked);
+    assert!(streams.acquire(peer, MAX_OPEN_STREAMS).is_none());
+    drop(stream);
+    assert!(streams.acquire(peer, MAX_OPEN_STREAMS).is_some());
+}
+
+#[test]
+fn test_connection_body_budget() {
+    use super::{BodyBudgets, ConnectionBudgetExceeded, read_error_status};
+    use http::Status;
+
+    let budgets = BodyBudgets::default();
+    let peer = "127.0.0.1:8000".parse().unwrap();
+    let other = "127.0.0.1:8001".parse().unwrap();
+
+    // The budget is spent across bodies on the same connection.
+    let data = Data::local(b"hello".to_vec()).with_hook(budgets.charge(peer, 8));
+    assert_eq!(read_all(data), b"hello");
+    assert!(!budgets.take_exhausted(peer, 8));
+
+    let data = Data::local(b"world".to_vec()).with_hook(budgets.charge(other, 8));
+    assert_eq!(read_all(data), b"world");
+
+    let data = Data::local(b"world".to_vec()).with_hook(budgets.charge(peer, 8));
+    let err = data.open().read_to_end(&mut vec![]).unwrap_err();
+    assert!(err.get_ref().map_or(false, |e| e.is::<ConnectionBudgetExceeded>()));
+    assert_eq!(read_error_status(&err, Status::BadRequest), Status::PayloadTooLarge);
+
+    // An exhausted connection is forgotten once it's reported.
+    assert!(budgets.take_exhausted(peer, 8));
+    assert!(!budgets.take_exhausted(peer, 8));
+    assert!(!budgets.take_exhausted(other, 8));
+}
+
+#[test]
+fn test_frames() {
+    use std::io;
+
+    // A 1-byte type followed by a 1-byte length.
+    fn header(header: &[u8]) -> io::Result<Option<u64>> {
+        match header.len() {
+            1 => Ok(None),
+            _ => Ok(Some(header[1] as u64)),
+        }
+    }
+
+    let frames: Vec<_> = Data::local(b"\x01\x02hi\x02\x00".to_vec()).open()
+        .frames(header)
+        .map(|frame| frame.unwrap())
+        .collect();
+
+    assert_eq!(frames, vec![
+        (b"\x01\x02".to_vec(), b"hi".to_vec()),
+        (b"\x02\x00".to_vec(), vec![]),
+    ]);
+
+    let mut frames = Data::local(b"\x01".to_vec()).open().frames(header);
+    assert_eq!(frames.next().unwrap().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    assert!(frames.next().is_none());
+
+    let mut frames = Data::local(vec![0; 100]).open().frames(|_| Ok(None));
+    assert_eq!(frames.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_has_declared_length() {
+    let data = from_parts(b"", 0, BodyKind::Sized(0));
+    assert_eq!(data.len(), Some(0));
+    assert!(data.has_declared_length());
+
+    let data = from_parts(b"", 0, BodyKind::Empty);
+    assert_eq!(data.len(), Some(0));
+    assert!(!data.has_declared_length());
+
+    assert!(!from_parts(b"0\r\n\r\n", 0, BodyKind::Chunked(None)).has_declared_length());
+    let stream = HttpReader::SizedReader(body(b"hi").chain(NetStream::Empty), 2);
+    assert!(Data::unpeeked(stream).has_declared_length());
+}
+
+#[test]
+fn test_stream_in_parts() {
+    fn parts(bytes: &[u8], size: usize) -> Vec<(Vec<u8>, bool)> {
+        let mut parts = vec![];
+        let streamed = Data::local(bytes.to_vec()).stream_in_parts(size, |part, is_last| {
+            parts.push((part, is_last));
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(streamed, bytes.len() as u64);
+        parts
+    }
+
+    assert_eq!(parts(b"abcde", 2), vec![
+        (b"ab".to_vec(), false),
+        (b"cd".to_vec(), false),
+        (b"e".to_vec(), true),
+    ]);
+
+    assert_eq!(parts(b"abcd", 2), vec![(b"ab".to_vec(), false), (b"cd".to_vec(), true)]);
+    assert_eq!(parts(b"", 2), vec![(vec![], true)]);
+
+    let error = Data::local(b"abcd".to_vec()).stream_in_parts(2, |_, _| {
+        Err(::std::io::Error::new(::std::io::ErrorKind::Other, "upload failed"))
+    });
+
+    assert_eq!(error.unwrap_err().kind(), ::std::io::ErrorKind::Other);
+}
+
+#[test]
+fn test_resumable_upload() {
+    use std::fs;
+    use super::{ResumableUpload, OffsetMismatch, BodyTooLarge, read_error_status};
+    use http::Status;
+
+    let path = ::std::env::temp_dir().join(format!("rocket-tus-test.{}", ::std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let upload = ResumableUpload::new(&path).length(8);
+    assert_eq!(upload.offset().unwrap(), 0);
+    assert_eq!(upload.append(Data::local(b"hello".to_vec()), 0).unwrap(), 5);
+
+    // A chunk at the wrong offset is rejected and the upload is unchanged.
+    let err = upload.append(Data::local(b"world".to_vec()), 0).unwrap_err();
+    let mismatch = err.get_ref().and_then(|e| e.downcast_ref::<OffsetMismatch>()).cloned();
+    assert_eq!(mismatch, Some(OffsetMismatch { expected: 5, received: 0 }));
+    assert_eq!(read_error_status(&err, Status::BadRequest), Status::Conflict);
+
+    // A chunk can't extend the upload past its length.
+    let err = upload.append(Data::local(b"world".to_vec()), 5).unwrap_err();
+    assert!(err.get_ref().map_or(false, |e| e.is::<BodyTooLarge>()));
+    assert!(!upload.is_complete().unwrap());
+
+    assert_eq!(upload.append(Data::local(b"!!!".to_vec()), 5).unwrap(), 8);
+    assert!(upload.is_complete().unwrap());
+    assert_eq!(fs::read(&path).unwrap(), b"hello!!!");
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_tee_redacted() {
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Log(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Log {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let log = Log::default();
+    let body = b"user=bob\npassword=hunter2\ndone".to_vec();
+    let data = Data::local(body.clone()).tee_redacted(log.clone(), |line: &[u8]| {
+        match line.starts_with(b"password=") {
+            true => b"password=***\n".to_vec(),
+            false => line.to_vec(),
+        }
+    });
+
+    assert_eq!(read_all(data), body);
+    assert_eq!(&*log.0.lock().unwrap(), b"user=bob\npassword=***\ndone");
+}
+
+#[test]
+fn test_read_in_background() {
+    use std::time::Instant;
+    use super::deadline::Deadline;
+
+    let mut body = Data::local(b"hello, world".to_vec()).read_in_background(5, 1).unwrap();
+    let mut contents = vec![];
+    body.read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, b"hello, world");
+
+    // An error on the reader thread surfaces on this side.
+    let data = Data::local(b"hello".to_vec()).with_hook(Deadline::new(Instant::now()));
+    let mut body = data.read_in_background(5, 1).unwrap();
+    let err = body.read_to_end(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::TimedOut);
+}
+
+#[test]
+fn test_read_syscalls() {
+    use super::DataStats;
+    use http::hyper::net::NetworkStream;
+
+    // A stream that reads from memory, standing in for a connection.
+    #[derive(Clone)]
+    struct Memory(Cursor<Vec<u8>>);
+
+    impl Read for Memory {
+        fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+            // Read at most 4 bytes at a time, like a slow connection.
+            let n = ::std::cmp::min(buf.len(), 4);
+            self.0.read(&mut buf[..n])
+        }
+    }
+
+    impl ::std::io::Write for Memory {
+        fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> { Ok(buf.len()) }
+        fn flush(&mut self) -> ::std::io::Result<()> { Ok(()) }
+    }
+
+    impl NetworkStream for Memory {
+        fn peer_addr(&mut self) -> ::std::io::Result<::std::net::SocketAddr> {
+            Err(::std::io::ErrorKind::AddrNotAvailable.into())
+        }
+
+        fn set_read_timeout(&self, _: Option<::std::time::Duration>) -> ::std::io::Result<()> {
+            Ok(())
+        }
+
+        fn set_write_timeout(&self, _: Option<::std::time::Duration>) -> ::std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let before = DataStats::snapshot().read_syscalls;
+    let stream = NetStream::Dynamic(Box::new(Memory(Cursor::new(b"hello, world".to_vec()))));
+    let data = Data::from_parts(vec![], 0, stream.counted(), BodyKind::Sized(12));
+    assert_eq!(data.read_syscalls(), Some(3));
+    assert_eq!(read_all(data), b"hello, world");
+    assert!(DataStats::snapshot().read_syscalls >= before + 3);
+
+    assert_eq!(Data::local(b"hello".to_vec()).read_syscalls(), None);
+}
+
+#[test]
+fn test_slowloris_guard() {
+    use std::time::{Duration, Instant};
+    use super::BodyTooSlow;
+    use super::slow::SlowlorisGuard;
+
+    // A slow body within the grace period is read normally.
+    let grace = Duration::from_secs(60);
+    let hook = SlowlorisGuard::new(Instant::now(), grace, 1 << 20, None);
+    assert_eq!(read_all(Data::local(b"hello".to_vec()).with_hook(hook)), b"hello");
+
+    // One that's past it and too slow is rejected.
+    let arrived = Instant::now() - Duration::from_secs(120);
+    let hook = SlowlorisGuard::new(arrived, grace, 1 << 20, None);
+    let data = Data::local(b"hello".to_vec()).with_hook(hook);
+    let err = data.open().read(&mut [0; 8]).unwrap_err();
+    assert_eq!(err.kind(), ::std::io::ErrorKind::TimedOut);
+    let slow = err.get_ref().and_then(|e| e.downcast_ref::<BodyTooSlow>()).cloned().unwrap();
+    assert_eq!(slow.read, 0);
+    assert!(slow.elapsed >= Duration::from_secs(120));
+
+    // The guard also applies to the reads that fill the peek buffer.
+    let hook = SlowlorisGuard::new(arrived, grace, 1 << 20, None);
+    let err = peeked(b"hello", Box::new(hook)).err().unwrap();
+    assert!(err.get_ref().map_or(false, |e| e.is::<BodyTooSlow>()));
+}
+
+#[test]
+fn test_request_line() {
+    use http::Method;
+    use http::uri::Uri;
+
+    let data = Data::local(b"hello".to_vec());
+    assert_eq!(data.method(), None);
+    assert!(data.uri().is_none());
+
+    let data = data.with_request_line(Method::Put, &Uri::new("/upload?name=a"));
+    assert_eq!(data.method(), Some(Method::Put));
+    assert_eq!(data.uri().map(|uri| uri.as_str()), Some("/upload?name=a"));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_splice_with_bytes_past_the_body() {
+    use std::fs;
+    use std::net::{TcpListener, TcpStream};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+
+    // The prebuffered bytes hold a trailing CRLF and a pipelined request.
+    let prefix = b"hello\r\nGET / HTTP/1.1\r\n\r\n".to_vec();
+    let data = Data::from_tcp_stream(server, prefix, Framing::Sized, Some(5)).unwrap();
+
+    let path = ::std::env::temp_dir().join(format!("rocket-splice-test.{}", ::std::process::id()));
+    assert_eq!(data.stream_to_file(&path).unwrap(), 5);
+    assert_eq!(fs::read(&path).unwrap(), b"hello");
+
+    fs::remove_file(&path).unwrap();
+    drop(client);
+}
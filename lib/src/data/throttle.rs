@@ -0,0 +1,52 @@
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::data_stream::ReadHook;
+
+/// A token bucket limiting the rate at which bytes are read from a stream.
+///
+/// The bucket holds at most one second's worth of tokens. Each byte read
+/// consumes a token; when a read overdraws the bucket, the reader sleeps until
+/// the deficit has been refilled.
+pub struct Throttle {
+    rate: u64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl Throttle {
+    /// Creates a throttle permitting `rate` bytes per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is `0`.
+    pub fn new(rate: u64) -> Throttle {
+        assert!(rate > 0, "throttle rate must be non-zero");
+        Throttle { rate: rate, tokens: rate as f64, last: Instant::now() }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last);
+        let secs = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9;
+        self.tokens = (self.tokens + secs * self.rate as f64).min(self.rate as f64);
+        self.last = now;
+    }
+}
+
+impl ReadHook for Throttle {
+    fn after_read(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.refill();
+        self.tokens -= bytes.len() as f64;
+        if self.tokens < 0.0 {
+            let wait = -self.tokens / self.rate as f64;
+            let nanos = (wait.fract() * 1e9) as u32;
+            thread::sleep(Duration::new(wait.trunc() as u64, nanos));
+            self.tokens = 0.0;
+            self.last = Instant::now();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, Shutdown};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use rustls::{Session, ServerSession, ServerConfig, Certificate, PrivateKey};
+use rustls::{ProtocolVersion, CipherSuite};
+use hyper_sync_rustls;
+use http::hyper;
+use http::hyper::net::{HttpStream, NetworkStream, SslServer};
+use config::TlsVersion;
+
+/// The parameters negotiated for the TLS connection a request body arrived
+/// over, as returned by [`Data::tls_info`].
+///
+/// This type is only available when the `tls` feature is enabled.
+///
+/// [`Data::tls_info`]: /rocket/data/struct.Data.html#method.tls_info
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TlsInfo {
+    /// The negotiated protocol version.
+    pub version: ProtocolVersion,
+    /// The negotiated cipher suite.
+    pub cipher_suite: CipherSuite,
+}
+
+impl TlsInfo {
+    /// Returns the negotiated protocol version as a `TlsVersion`, or `None` if
+    /// it's older than TLS 1.0.
+    pub fn tls_version(&self) -> Option<TlsVersion> {
+        match self.version {
+            ProtocolVersion::TLSv1_0 => Some(TlsVersion::V1_0),
+            ProtocolVersion::TLSv1_1 => Some(TlsVersion::V1_1),
+            ProtocolVersion::TLSv1_2 => Some(TlsVersion::V1_2),
+            ProtocolVersion::TLSv1_3 => Some(TlsVersion::V1_3),
+            _ => None
+        }
+    }
+}
+
+/// Hyper's TLS server for Rocket. Unlike `hyper_sync_rustls`'s own server,
+/// the streams it produces keep their rustls session reachable so that the
+/// parameters of a connection can be inspected.
+pub struct TlsServer {
+    config: Arc<ServerConfig>,
+}
+
+impl TlsServer {
+    /// Creates a server presenting the certificate chain `certs` signed by
+    /// `key`, configured exactly as `hyper_sync_rustls` configures its own.
+    pub fn new(certs: Vec<Certificate>, key: PrivateKey) -> TlsServer {
+        TlsServer { config: hyper_sync_rustls::TlsServer::new(certs, key).cfg }
+    }
+}
+
+impl SslServer for TlsServer {
+    type Stream = TlsStream;
+
+    fn wrap_server(&self, stream: HttpStream) -> hyper::Result<TlsStream> {
+        Ok(TlsStream {
+            session: Arc::new(Mutex::new(ServerSession::new(&self.config))),
+            writer: Arc::new(Mutex::new(())),
+            socket: stream,
+        })
+    }
+}
+
+/// A TLS stream served by Rocket. Like Hyper's other streams, clones of a
+/// `TlsStream` share the same connection.
+///
+/// The session is only locked while records are moved in or out of it, never
+/// during socket I/O, so a read waiting on the peer doesn't block a clone
+/// that's writing a response or setting a timeout.
+#[derive(Clone)]
+pub struct TlsStream {
+    session: Arc<Mutex<ServerSession>>,
+    // Held while records leave the session and are written to the socket so
+    // that they reach the peer in the order the session produced them.
+    writer: Arc<Mutex<()>>,
+    socket: HttpStream,
+}
+
+#[inline]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Takes all of the TLS records `session` wants to send.
+fn pending_records(session: &mut ServerSession) -> io::Result<Vec<u8>> {
+    let mut records = vec![];
+    while session.wants_write() {
+        session.write_tls(&mut records)?;
+    }
+
+    Ok(records)
+}
+
+impl TlsStream {
+    /// Returns the parameters negotiated for this connection, or `None` if
+    /// the handshake hasn't completed.
+    pub fn info(&self) -> Option<TlsInfo> {
+        let session = lock(&self.session);
+        if session.is_handshaking() {
+            return None;
+        }
+
+        Some(TlsInfo {
+            version: session.get_protocol_version()?,
+            cipher_suite: session.get_negotiated_ciphersuite()?.suite,
+        })
+    }
+
+    // Feeds `records`, read from the socket, to the session and sends any
+    // records the session produces in response, as during the handshake.
+    fn receive(&mut self, mut records: &[u8]) -> io::Result<()> {
+        let writer = self.writer.clone();
+        let _writing = lock(&writer);
+        let (pending, processed) = {
+            let mut session = lock(&self.session);
+            let mut processed = Ok(());
+            while !records.is_empty() && processed.is_ok() {
+                if session.read_tls(&mut records)? == 0 {
+                    break;
+                }
+
+                processed = session.process_new_packets();
+            }
+
+            (pending_records(&mut session)?, processed)
+        };
+
+        // On an error, `pending` holds the alert telling the peer why.
+        self.socket.write_all(&pending)?;
+        processed.map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("TLS error: {:?}", e))
+        })
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut records = [0; 8192];
+        loop {
+            let n = lock(&self.session).read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+
+            match self.socket.read(&mut records)? {
+                0 => return Ok(0),
+                len => self.receive(&records[..len])?,
+            }
+        }
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let writer = self.writer.clone();
+        let _writing = lock(&writer);
+        let (n, pending) = {
+            let mut session = lock(&self.session);
+            let n = session.write(buf)?;
+            (n, pending_records(&mut session)?)
+        };
+
+        self.socket.write_all(&pending)?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let writer = self.writer.clone();
+        let _writing = lock(&writer);
+        let pending = {
+            let mut session = lock(&self.session);
+            session.flush()?;
+            pending_records(&mut session)?
+        };
+
+        self.socket.write_all(&pending)?;
+        self.socket.flush()
+    }
+}
+
+impl NetworkStream for TlsStream {
+    #[inline]
+    fn peer_addr(&mut self) -> io::Result<SocketAddr> {
+        self.socket.peer_addr()
+    }
+
+    #[inline]
+    fn set_read_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.socket.set_read_timeout(dur)
+    }
+
+    #[inline]
+    fn set_write_timeout(&self, dur: Option<Duration>) -> io::Result<()> {
+        self.socket.set_write_timeout(dur)
+    }
+
+    fn close(&mut self, how: Shutdown) -> io::Result<()> {
+        let writer = self.writer.clone();
+        let _writing = lock(&writer);
+        let pending = {
+            let mut session = lock(&self.session);
+            session.send_close_notify();
+            pending_records(&mut session)
+        };
+
+        if let Ok(pending) = pending {
+            let _ = self.socket.write_all(&pending);
+        }
+
+        self.socket.close(how)
+    }
+}
@@ -0,0 +1,53 @@
+use state::{Container, Storage};
+
+/// A map from types to values, carried by a
+/// [`Data`](/rocket/data/struct.Data.html) so that fairings that inspect a
+/// body can hand what they learned to the handler that reads it.
+///
+/// The map holds at most one value of each type. Values are inserted through
+/// a shared reference, as fairings only receive `&Data`, and once inserted
+/// can't be replaced or removed. The map allocates nothing until the first
+/// value is inserted.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::{Request, Data};
+/// use rocket::fairing::AdHoc;
+///
+/// struct LooksLikeJson(bool);
+///
+/// let fairing = AdHoc::on_request(|_: &mut Request, data: &Data| {
+///     let json = data.peek().first() == Some(&b'{');
+///     data.extensions().insert(LooksLikeJson(json));
+/// });
+///
+/// fn handler(data: Data) {
+///     if let Some(&LooksLikeJson(true)) = data.extensions().get::<LooksLikeJson>() {
+///         /* parse the body as JSON */
+///     }
+/// }
+/// ```
+pub struct TypeMap {
+    map: Storage<Container>,
+}
+
+impl TypeMap {
+    #[inline(always)]
+    pub(crate) fn new() -> TypeMap {
+        TypeMap { map: Storage::new() }
+    }
+
+    /// Inserts `value` into the map, returning `true` if the map held no value
+    /// of type `T` and `false`, leaving the map unchanged, if it did.
+    #[inline]
+    pub fn insert<T: Send + Sync + 'static>(&self, value: T) -> bool {
+        self.map.get_or_set(Container::new).set(value)
+    }
+
+    /// Returns the value of type `T` in the map, if there is one.
+    #[inline]
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map.try_get().and_then(|map| map.try_get::<T>())
+    }
+}
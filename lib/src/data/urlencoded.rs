@@ -0,0 +1,229 @@
+use std::io::{self, Read};
+
+use super::{Data, DataStream, FieldLimitExceeded};
+use http::RawStr;
+
+/// A streaming parser for an `application/x-www-form-urlencoded` body.
+///
+/// Unlike [`FormItems`], which parses a body that has already been read into
+/// memory, `FormPairs` reads the body one pair at a time, so bodies of any
+/// size can be processed while holding only the current pair in memory. Each
+/// pair is yielded with its key and value URL-decoded. A pair may span any
+/// number of reads from the underlying stream, including the end of the
+/// `peek` buffer, and the final pair needn't be followed by a `&`.
+///
+/// # Errors
+///
+/// A pair without a `=`, a pair with an empty key, and a pair that isn't valid
+/// UTF-8 once decoded yield an error of kind `InvalidData`, as does a pair of
+/// more than `limit` encoded bytes. A form that exceeds a limit of a parser
+/// created with [`with_limits`](#method.with_limits) yields an error of kind
+/// `InvalidData` wrapping a [`FieldLimitExceeded`]. I/O errors are yielded
+/// as-is. After an error is yielded, the iterator is exhausted.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io;
+/// use rocket::Data;
+/// use rocket::data::FormPairs;
+///
+/// fn handler(data: Data) -> io::Result<usize> {
+///     let mut rows = 0;
+///     for pair in FormPairs::new(data, 64 * 1024) {
+///         let (key, value) = pair?;
+///         if key == "row" {
+///             /* store `value` */
+///             rows += 1;
+///         }
+///     }
+///
+///     Ok(rows)
+/// }
+/// ```
+///
+/// [`FormItems`]: /rocket/request/struct.FormItems.html
+/// [`FieldLimitExceeded`]: /rocket/data/enum.FieldLimitExceeded.html
+pub struct FormPairs {
+    stream: DataStream,
+    limit: u64,
+    limits: FormLimits,
+    count: usize,
+    done: bool,
+}
+
+/// Field limits applied by [`FormPairs::with_limits`].
+///
+/// Each limit is checked as the form is read, so a form that exceeds one is
+/// rejected before more than a few kilobytes past the limit are buffered. The
+/// lengths of names and values are those of their encoded forms.
+///
+/// [`FormPairs::with_limits`]: /rocket/data/struct.FormPairs.html#method.with_limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormLimits {
+    name: u64,
+    value: u64,
+    fields: usize,
+}
+
+impl Default for FormLimits {
+    /// A limit of 1KiB for names, 64KiB for values, and 1000 fields.
+    fn default() -> FormLimits {
+        FormLimits { name: 1024, value: 64 * 1024, fields: 1000 }
+    }
+}
+
+impl FormLimits {
+    /// Sets the maximum length, in bytes, of a field's name.
+    #[inline]
+    pub fn name(mut self, limit: u64) -> Self {
+        self.name = limit;
+        self
+    }
+
+    /// Sets the maximum length, in bytes, of a field's value.
+    #[inline]
+    pub fn value(mut self, limit: u64) -> Self {
+        self.value = limit;
+        self
+    }
+
+    /// Sets the maximum number of fields.
+    #[inline]
+    pub fn fields(mut self, limit: usize) -> Self {
+        self.fields = limit;
+        self
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+impl FormPairs {
+    /// Returns a parser for the form in `data` whose pairs are each limited
+    /// to `limit` encoded bytes.
+    #[inline]
+    pub fn new(data: Data, limit: u64) -> FormPairs {
+        let limits = FormLimits { name: limit, value: limit, fields: usize::max_value() };
+        FormPairs::create(data, limit, limits)
+    }
+
+    /// Returns a parser for the form in `data` subject to the field limits
+    /// `limits`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::data::{FormPairs, FormLimits};
+    ///
+    /// fn handler(data: Data) -> io::Result<usize> {
+    ///     let limits = FormLimits::default().value(1024).fields(16);
+    ///     let pairs = FormPairs::with_limits(data, limits).collect::<io::Result<Vec<_>>>()?;
+    ///     Ok(pairs.len())
+    /// }
+    /// ```
+    #[inline]
+    pub fn with_limits(data: Data, limits: FormLimits) -> FormPairs {
+        FormPairs::create(data, u64::max_value(), limits)
+    }
+
+    fn create(data: Data, limit: u64, limits: FormLimits) -> FormPairs {
+        FormPairs { stream: data.open(), limit: limit, limits: limits, count: 0, done: false }
+    }
+
+    /// Reads the next `&`-terminated segment of the body into `out`. Returns
+    /// `false` if the body had ended before any byte was read.
+    fn read_segment(&mut self, out: &mut Vec<u8>) -> io::Result<bool> {
+        let mut buf = [0; 4096];
+        let mut read_any = false;
+        let mut equals: Option<usize> = None;
+        loop {
+            let n = match self.stream.read(&mut buf) {
+                Ok(0) => return Ok(read_any),
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            read_any = true;
+            let (segment, rest) = match buf[..n].iter().position(|&b| b == b'&') {
+                Some(i) => (&buf[..i], Some(&buf[(i + 1)..n])),
+                None => (&buf[..n], None)
+            };
+
+            let len = out.len() + segment.len();
+            if len as u64 > self.limit {
+                return Err(invalid("form pair exceeds the limit"));
+            }
+
+            if equals.is_none() {
+                equals = segment.iter().position(|&b| b == b'=').map(|i| out.len() + i);
+            }
+
+            if equals.unwrap_or(len) as u64 > self.limits.name {
+                return Err(FieldLimitExceeded::Name(self.limits.name).into_io());
+            }
+
+            if equals.map_or(false, |i| (len - i - 1) as u64 > self.limits.value) {
+                return Err(FieldLimitExceeded::Value(self.limits.value).into_io());
+            }
+
+            out.extend_from_slice(segment);
+            if let Some(rest) = rest {
+                self.stream.unread(rest.to_vec());
+                return Ok(true);
+            }
+        }
+    }
+
+    fn next_pair(&mut self) -> io::Result<Option<(String, String)>> {
+        let mut segment = vec![];
+        loop {
+            if !self.read_segment(&mut segment)? {
+                return Ok(None);
+            }
+
+            // Empty segments, as in `a=1&&b=2` or a trailing `&`, hold no pair.
+            if !segment.is_empty() {
+                break;
+            }
+        }
+
+        if self.count == self.limits.fields {
+            return Err(FieldLimitExceeded::Fields(self.limits.fields).into_io());
+        }
+
+        self.count += 1;
+
+        let segment = String::from_utf8(segment).map_err(|_| invalid("form pair isn't UTF-8"))?;
+        let (key, value) = match segment.find('=') {
+            Some(i) if i > 0 => (&segment[..i], &segment[(i + 1)..]),
+            Some(_) => return Err(invalid("form pair has an empty key")),
+            None => return Err(invalid("form pair has no value")),
+        };
+
+        let decode = |s: &str| RawStr::from_str(s).url_decode()
+            .map_err(|_| invalid("form pair isn't UTF-8"));
+
+        Ok(Some((decode(key)?, decode(value)?)))
+    }
+}
+
+impl Iterator for FormPairs {
+    type Item = io::Result<(String, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_pair() {
+            Ok(Some(pair)) => Some(Ok(pair)),
+            Ok(None) => { self.done = true; None }
+            Err(e) => { self.done = true; Some(Err(e)) }
+        }
+    }
+}
@@ -15,6 +15,38 @@ pub trait ReadExt: io::Read {
 
         Ok(start_len - buf.len())
     }
+
+    /// Fills `buf` completely, returning `Ok(true)`. If the reader is at EOF
+    /// before any bytes are read, returns `Ok(false)`. If EOF is reached after
+    /// some but not all of `buf` has been filled, returns an error of kind
+    /// `UnexpectedEof`.
+    fn read_exact_or_eof(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+        match self.read_max(buf)? {
+            0 if !buf.is_empty() => Ok(false),
+            n if n == buf.len() => Ok(true),
+            _ => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected EOF"))
+        }
+    }
+
+    /// Copies every remaining byte from `self` into `writer`, returning the
+    /// number of bytes copied. Reads and writes that are interrupted are
+    /// retried. This is the copy loop shared by the body helpers in `data`.
+    fn copy_to<W: io::Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<u64> {
+        let mut buf = [0; 8192];
+        let mut written = 0;
+        loop {
+            let n = match self.read(&mut buf) {
+                Ok(0) => return Ok(written),
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            // `write_all` retries interrupted writes.
+            writer.write_all(&buf[..n])?;
+            written += n as u64;
+        }
+    }
 }
 
 impl<T: io::Read> ReadExt for T {  }
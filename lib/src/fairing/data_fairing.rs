@@ -0,0 +1,53 @@
+use {Request, Data};
+use http::Status;
+
+/// Trait implemented by data fairings: callbacks that may inspect, replace, or
+/// reject the body of every request before it is routed.
+///
+/// Data fairings are attached with
+/// [`Rocket::attach_data`](/rocket/struct.Rocket.html#method.attach_data) and
+/// run in the order they were attached, after every request fairing and
+/// before the request is routed. Each receives the `Data` returned by the one
+/// before it, so a fairing that decompresses a body, for instance, hands the
+/// decompressed body to every later fairing and to the route handlers. If a
+/// fairing returns an `Err`, no later fairing runs and the request fails
+/// with the returned status, which is handled by the matching catcher.
+///
+/// Unlike request fairings, data fairings take the body by value, so they are
+/// the place to enforce a policy that requires reading it. Like request
+/// fairings, they should act locally: a fairing that rewrites every body
+/// affects every route of the application.
+///
+/// Any function or closure with the signature of
+/// [`on_data`](#tymethod.on_data) is a data fairing.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::{Request, Data};
+/// use rocket::http::Status;
+///
+/// fn reject_binary(_: &Request, data: Data) -> Result<Data, Status> {
+///     if data.peek().contains(&0) {
+///         return Err(Status::UnsupportedMediaType);
+///     }
+///
+///     Ok(data)
+/// }
+///
+/// let rocket = rocket::ignite().attach_data(reject_binary);
+/// ```
+pub trait DataFairing: Send + Sync + 'static {
+    /// Returns the body that `request` should be routed with, given its
+    /// current body, `data`, or the status the request should fail with.
+    fn on_data(&self, request: &Request, data: Data) -> Result<Data, Status>;
+}
+
+impl<F> DataFairing for F
+    where F: Fn(&Request, Data) -> Result<Data, Status> + Send + Sync + 'static
+{
+    #[inline(always)]
+    fn on_data(&self, request: &Request, data: Data) -> Result<Data, Status> {
+        self(request, data)
+    }
+}
@@ -9,9 +9,11 @@
 //!
 //! To learn more about writing a fairing, see the [`Fairing`] trait
 //! documentation. You can also use [`AdHoc`] to create a fairing on-the-fly
-//! from a closure or function.
+//! from a closure or function. Fairings that need to inspect, replace, or
+//! reject request bodies implement [`DataFairing`] instead.
 //!
 //! [`AdHoc`]: /rocket/fairing/enum.AdHoc.html
+//! [`DataFairing`]: /rocket/fairing/trait.DataFairing.html
 //!
 //! ## Attaching
 //!
@@ -52,10 +54,12 @@ use {Rocket, Request, Response, Data};
 
 mod fairings;
 mod ad_hoc;
+mod data_fairing;
 mod info_kind;
 
 pub(crate) use self::fairings::Fairings;
 pub use self::ad_hoc::AdHoc;
+pub use self::data_fairing::DataFairing;
 pub use self::info_kind::{Info, Kind};
 
 // We might imagine that a request fairing returns an `Outcome`. If it returns
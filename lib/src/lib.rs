@@ -101,6 +101,13 @@
 #[macro_use] extern crate pear;
 #[cfg(feature = "tls")] extern crate rustls;
 #[cfg(feature = "tls")] extern crate hyper_sync_rustls;
+#[cfg(feature = "gzip")] extern crate flate2;
+#[cfg(any(feature = "aead", feature = "cas", feature = "digest", feature = "hmac"))]
+extern crate ring;
+#[cfg(feature = "crc")] extern crate crc32c;
+#[cfg(feature = "bytes")] extern crate bytes;
+#[cfg(target_os = "linux")] extern crate libc;
+#[cfg(feature = "serde")] extern crate serde;
 #[macro_use] extern crate percent_encoding;
 extern crate yansi;
 extern crate hyper;
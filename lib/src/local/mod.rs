@@ -104,5 +104,5 @@
 mod request;
 mod client;
 
-pub use self::request::{LocalResponse, LocalRequest};
+pub use self::request::{LocalResponse, LocalRequest, BodyRecording};
 pub use self::client::Client;
@@ -3,8 +3,10 @@ use std::rc::Rc;
 use std::mem::transmute;
 use std::net::SocketAddr;
 use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
 
 use {Rocket, Request, Response, Data};
+use data::ReadHook;
 use http::{Header, Cookie};
 
 /// A structure representing a local request as created by [`Client`].
@@ -16,7 +18,8 @@ use http::{Header, Cookie};
 /// [`add_header`] method. Cookies can be added via the [`cookie`] builder
 /// method. The remote IP address can be set via the [`remote`] builder method.
 /// The body of the request can be set via the [`body`] builder method or
-/// [`set_body`] method.
+/// [`set_body`] method. The bytes the application reads from the body can be
+/// captured via the [`record_body`] method.
 ///
 /// ## Example
 ///
@@ -64,6 +67,7 @@ use http::{Header, Cookie};
 /// [`remote`]: #method.remote
 /// [`body`]: #method.body
 /// [`set_body`]: #method.set_body
+/// [`record_body`]: #method.record_body
 /// [`dispatch`]: #method.dispatch
 /// [`mut_dispatch`]: #method.mut_dispatch
 /// [`cloned_dispatch`]: #method.cloned_dispatch
@@ -71,7 +75,8 @@ pub struct LocalRequest<'c> {
     rocket: &'c Rocket,
     ptr: *mut Request<'c>,
     request: Rc<Request<'c>>,
-    data: Vec<u8>
+    data: Vec<u8>,
+    recording: Option<BodyRecording>
 }
 
 impl<'c> LocalRequest<'c> {
@@ -79,7 +84,7 @@ impl<'c> LocalRequest<'c> {
     pub(crate) fn new(rocket: &'c Rocket, request: Request<'c>) -> LocalRequest<'c> {
         let mut req = Rc::new(request);
         let ptr = Rc::get_mut(&mut req).unwrap() as *mut Request;
-        LocalRequest { rocket: rocket, ptr: ptr, request: req, data: vec![] }
+        LocalRequest { rocket: rocket, ptr: ptr, request: req, data: vec![], recording: None }
     }
 
     /// Retrieves the inner `Request` as seen by Rocket.
@@ -235,6 +240,46 @@ impl<'c> LocalRequest<'c> {
         self.data = body.as_ref().into();
     }
 
+    /// Records the bytes the application reads from the body of this request
+    /// when it is dispatched, returning a handle to the recording.
+    ///
+    /// Bytes are recorded as they are read from the body's stream, so bytes
+    /// that are only inspected via `Data::peek` aren't recorded. Each call
+    /// replaces any previous recording.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::local::Client;
+    ///
+    /// let client = Client::new(rocket::ignite()).unwrap();
+    /// let mut req = client.post("/upload").body("hello");
+    /// let recording = req.record_body();
+    /// req.dispatch();
+    ///
+    /// // No route matched, so nothing was read.
+    /// assert!(recording.bytes().is_empty());
+    /// ```
+    pub fn record_body(&mut self) -> BodyRecording {
+        let recording = BodyRecording(Arc::new(Mutex::new(vec![])));
+        self.recording = Some(recording.clone());
+        recording
+    }
+
+    // Creates the data for `body`, recording reads from it if requested.
+    fn body_data(&self, body: Vec<u8>) -> Data {
+        let declared = !body.is_empty() || self.request.headers().contains("Content-Length");
+        let data = match self.recording {
+            Some(ref recording) => Data::local(body)
+                .with_read_buffer(0)
+                .with_hook(Recorder(recording.clone())),
+            None => Data::local(body)
+        };
+
+        let request = &self.request;
+        data.with_declared_length(declared).with_request_line(request.method(), request.uri())
+    }
+
     /// Dispatches the request, returning the response.
     ///
     /// This method consumes `self` and is the preferred mechanism for
@@ -250,8 +295,10 @@ impl<'c> LocalRequest<'c> {
     /// ```
     #[inline(always)]
     pub fn dispatch(mut self) -> LocalResponse<'c> {
+        let data = ::std::mem::replace(&mut self.data, vec![]);
+        let data = self.body_data(data);
         let req = unsafe { transmute(self.request()) };
-        let response = self.rocket.dispatch(req, Data::local(self.data));
+        let response = self.rocket.dispatch(req, data);
 
         LocalResponse {
             _request: self.request,
@@ -280,6 +327,7 @@ impl<'c> LocalRequest<'c> {
         let cloned = (*self.request).clone();
         let mut req = LocalRequest::new(self.rocket, cloned);
         req.data = self.data.clone();
+        req.recording = self.recording.clone();
         req.dispatch()
     }
 
@@ -313,8 +361,9 @@ impl<'c> LocalRequest<'c> {
     #[inline(always)]
     pub fn mut_dispatch(&mut self) -> LocalResponse<'c> {
         let data = ::std::mem::replace(&mut self.data, vec![]);
+        let data = self.body_data(data);
         let req = unsafe { transmute(self.request()) };
-        let response = self.rocket.dispatch(req, Data::local(data));
+        let response = self.rocket.dispatch(req, data);
 
         LocalResponse {
             _request: self.request.clone(),
@@ -329,6 +378,36 @@ impl<'c> fmt::Debug for LocalRequest<'c> {
     }
 }
 
+/// The bytes an application read from the body of a dispatched
+/// `LocalRequest`, as returned by
+/// [`LocalRequest::record_body`](/rocket/local/struct.LocalRequest.html#method.record_body).
+#[derive(Debug, Clone)]
+pub struct BodyRecording(Arc<Mutex<Vec<u8>>>);
+
+impl BodyRecording {
+    /// Returns a copy of the bytes recorded so far.
+    pub fn bytes(&self) -> Vec<u8> {
+        match self.0.lock() {
+            Ok(bytes) => bytes.clone(),
+            Err(poisoned) => poisoned.into_inner().clone()
+        }
+    }
+}
+
+/// A read hook that appends every byte read to a `BodyRecording`.
+struct Recorder(BodyRecording);
+
+impl ReadHook for Recorder {
+    fn after_read(&mut self, bytes: &[u8]) -> ::std::io::Result<()> {
+        match (self.0).0.lock() {
+            Ok(mut recorded) => recorded.extend_from_slice(bytes),
+            Err(poisoned) => poisoned.into_inner().extend_from_slice(bytes)
+        }
+
+        Ok(())
+    }
+}
+
 /// A structure representing a response from dispatching a local request.
 ///
 /// This structure is a thin wrapper around [`Response`]. It implements no
@@ -34,7 +34,7 @@ use std::fmt::Debug;
 
 use outcome::Outcome::*;
 use request::Request;
-use data::{self, Data};
+use data::{self, Data, read_error_status};
 use self::form::FormResult;
 use http::Status;
 
@@ -54,7 +54,7 @@ fn from_data<'f, T>(request: &Request,
     let mut stream = data.open().take(limit);
     if let Err(e) = stream.read_to_string(&mut form_string) {
         error_!("IO Error: {:?}", e);
-        Failure((Status::InternalServerError, None))
+        Failure((read_error_status(&e, Status::InternalServerError), None))
     } else {
         match Form::new(form_string, strict) {
             FormResult::Ok(form) => Success(form),
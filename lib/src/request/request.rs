@@ -11,6 +11,7 @@ use super::{FromParam, FromSegments, FromRequest, Outcome};
 use rocket::Rocket;
 use router::Route;
 use config::{Config, Limits};
+use data::SpillLimiter;
 use http::uri::{Uri, Segments};
 use error::Error;
 use http::{Method, Header, HeaderMap, Cookies, CookieJar};
@@ -21,6 +22,7 @@ use http::hyper;
 struct RequestState<'r> {
     config: &'r Config,
     state: &'r Container,
+    spills: &'r SpillLimiter,
     params: RefCell<Vec<(usize, usize)>>,
     route: Cell<Option<&'r Route>>,
     cookies: RefCell<CookieJar>,
@@ -60,6 +62,7 @@ impl<'r> Request<'r> {
             state: RequestState {
                 config: &rocket.config,
                 state: &rocket.state,
+                spills: &rocket.spills,
                 route: Cell::new(None),
                 params: RefCell::new(Vec::new()),
                 cookies: RefCell::new(CookieJar::new()),
@@ -407,6 +410,18 @@ impl<'r> Request<'r> {
         &self.state.config.limits
     }
 
+    /// Returns the configuration of the application handling `self`.
+    #[inline(always)]
+    pub(crate) fn config(&self) -> &'r Config {
+        self.state.config
+    }
+
+    /// Returns the limiter shared by every spill to disk in the application.
+    #[inline(always)]
+    pub(crate) fn spill_limiter(&self) -> &'r SpillLimiter {
+        self.state.spills
+    }
+
     /// Get the presently matched route, if any.
     ///
     /// This method returns `Some` any time a handler or its guards are being
@@ -1,25 +1,28 @@
 use std::collections::HashMap;
+use std::ascii::AsciiExt;
 use std::str::from_utf8_unchecked;
 use std::cmp::min;
-use std::net::SocketAddr;
+use std::net::{SocketAddr, IpAddr};
 use std::io::{self, Write};
 use std::mem;
+use std::time::Instant;
 
 use yansi::Paint;
 use state::Container;
 
-#[cfg(feature = "tls")] use hyper_sync_rustls::TlsServer;
+#[cfg(feature = "tls")] use data::TlsServer;
 use {logger, handler};
 use ext::ReadExt;
-use config::{self, Config, LoggedValue};
+use config::{self, Config, LoggedValue, StrictBodies, Cidr};
 use request::{Request, FormItems};
-use data::Data;
+use data::{self, Data, DataError, StreamRecognizer, OpenStreams, BodyBudgets};
+use data::{SpillLimiter, ShutdownHandle};
 use response::{Body, Response};
 use router::{Router, Route};
 use catcher::{self, Catcher};
 use outcome::Outcome;
 use error::{Error, LaunchError, LaunchErrorKind};
-use fairing::{Fairing, Fairings};
+use fairing::{Fairing, Fairings, DataFairing};
 
 use http::{Method, Status, Header};
 use http::hyper::{self, header};
@@ -33,7 +36,13 @@ pub struct Rocket {
     default_catchers: HashMap<u16, Catcher>,
     catchers: HashMap<u16, Catcher>,
     pub(crate) state: Container,
+    pub(crate) stream_recognizers: Vec<StreamRecognizer>,
+    pub(crate) open_streams: OpenStreams,
+    pub(crate) body_budgets: BodyBudgets,
+    pub(crate) spills: SpillLimiter,
+    pub(crate) shutdown: ShutdownHandle,
     fairings: Fairings,
+    data_fairings: Vec<Box<DataFairing>>,
 }
 
 #[doc(hidden)]
@@ -48,6 +57,9 @@ impl hyper::Handler for Rocket {
         hyp_req: hyper::Request<'h, 'k>,
         res: hyper::FreshResponse<'h>,
     ) {
+        // Hyper calls us once the request's headers have been read.
+        let arrived = Instant::now();
+
         // Get all of the information from Hyper.
         let (h_addr, h_method, h_headers, h_uri, _, h_body) = hyp_req.deconstruct();
 
@@ -64,17 +76,44 @@ impl hyper::Handler for Rocket {
         };
 
         // Retrieve the data from the hyper body.
-        let data = match Data::from_hyp(h_body) {
+        let data = match Data::from_hyp(self, &req, h_body, arrived) {
             Ok(data) => data,
-            Err(reason) => {
-                error_!("Bad data in request: {}", reason);
-                let r = self.handle_error(Status::InternalServerError, &req);
+            Err(e) => {
+                error_!("Bad data in request: {}", e);
+                let status = match e {
+                    DataError::ConflictingLength { .. } => Status::BadRequest,
+                    DataError::TlsVersionTooOld { .. } => Status::UpgradeRequired,
+                    DataError::TooManyOpenStreams(_) => Status::ServiceUnavailable,
+                    DataError::PeekFailed(ref e) => data::read_error_status(e, Status::BadRequest),
+                    _ => Status::InternalServerError
+                };
+
+                // The rest of the connection can't be trusted to start at the
+                // next request: the body is ambiguous, or a previous one is
+                // still being read. Close it rather than parse what follows.
+                let mut r = self.handle_error(status, &req);
+                match e {
+                    DataError::ConflictingLength { .. } | DataError::TooManyOpenStreams(_) => {
+                        r.set_raw_header("Connection", "close");
+                    }
+                    _ => {}
+                }
+
                 return self.issue_response(r, res);
             }
         };
 
         // Dispatch the request to get a response, then write that response out.
-        let response = self.dispatch(&mut req, data);
+        let mut response = self.dispatch(&mut req, data);
+
+        // Close a connection that's sent more body bytes than its budget.
+        let budget = self.config.connection_body_budget;
+        if budget > 0 && self.body_budgets.take_exhausted(h_addr, budget) {
+            warn_!("Closing connection from {}: body budget of {} bytes exceeded.",
+                   h_addr, budget);
+            response.set_raw_header("Connection", "close");
+        }
+
         self.issue_response(response, res)
     }
 }
@@ -170,26 +209,70 @@ impl Rocket {
         }
     }
 
+    /// Applies the configured `strict_bodies` policy to `req` and its `data`.
+    /// Returns `false` if the request should be rejected. The check uses the
+    /// method as received, before any `_method` rewriting.
+    fn check_strict_bodies(&self, req: &Request, data: &Data) -> bool {
+        let policy = self.config.strict_bodies;
+        if policy == StrictBodies::Off || data.len() == Some(0) {
+            return true;
+        }
+
+        match req.method() {
+            Method::Get | Method::Delete | Method::Trace => {},
+            _ => return true
+        }
+
+        if policy == StrictBodies::Reject {
+            warn_!("Rejecting {} request with a body.", req.method());
+            return false;
+        }
+
+        warn_!("{} request has a body.", req.method());
+        true
+    }
+
+    /// Checks the `charset` parameter of the `Content-Type` of `req`, if it has
+    /// a body, against the configured `accepted_charsets`. Returns `false` if
+    /// the request should be rejected.
+    fn check_charset(&self, req: &Request, data: &Data) -> bool {
+        let accepted = &self.config.accepted_charsets;
+        if accepted.is_empty() || data.len() == Some(0) {
+            return true;
+        }
+
+        let charset = req.content_type().and_then(|ct| {
+            ct.params().find(|&(name, _)| name.eq_ignore_ascii_case("charset"))
+                .map(|(_, value)| value.trim_matches('"').to_string())
+        });
+
+        match charset {
+            Some(ref charset) if !accepted.iter().any(|c| c.eq_ignore_ascii_case(charset)) => {
+                warn_!("Rejecting a body in the unaccepted charset '{}'.", charset);
+                false
+            }
+            _ => true
+        }
+    }
+
     /// Preprocess the request for Rocket things. Currently, this means:
     ///
     ///   * Rewriting the method in the request if _method form field exists.
-    ///   * Rewriting the remote IP if the 'X-Real-IP' header is set.
+    ///   * Rewriting the remote IP if the request came through a trusted proxy
+    ///     that set the 'Forwarded' or 'X-Forwarded-For' header, or if the
+    ///     'X-Real-IP' header is set and no trusted proxies are configured or
+    ///     the request came through one.
     ///
     /// Keep this in-sync with derive_form when preprocessing form fields.
-    fn preprocess_request(&self, req: &mut Request, data: &Data) {
+    fn preprocess_request(&self, req: &mut Request, data: &mut Data) {
         // Rewrite the remote IP address. The request must already have an
         // address associated with it to do this since we need to know the port.
+        // The body's peer is kept in sync with the rewritten address.
         if let Some(current) = req.remote() {
-            let ip = req.headers()
-                .get_one("X-Real-IP")
-                .and_then(|ip| {
-                    ip.parse()
-                        .map_err(|_| warn_!("'X-Real-IP' header is malformed: {}", ip))
-                        .ok()
-                });
-
-            if let Some(ip) = ip {
-                req.set_remote(SocketAddr::new(ip, current.port()));
+            if let Some(ip) = resolve_client(req, current.ip(), &self.config.trusted_proxies) {
+                let remote = SocketAddr::new(ip, current.port());
+                req.set_remote(remote);
+                data.set_peer(remote);
             }
         }
 
@@ -219,15 +302,47 @@ impl Rocket {
         &'s self,
         request: &'r mut Request<'s>,
         data: Data,
+    ) -> Response<'r> {
+        // Apply the configured policy for bodies on bodiless methods, then
+        // reject bodies in charsets that aren't accepted. This is done here,
+        // rather than in `handle`, so that local requests are checked too.
+        let rejected = if !self.check_strict_bodies(request, &data) {
+            Some(Status::BadRequest)
+        } else if !self.check_charset(request, &data) {
+            Some(Status::UnsupportedMediaType)
+        } else {
+            None
+        };
+
+        match rejected {
+            Some(status) => self.handle_error(status, request),
+            None => self.dispatch_with(request, data, true),
+        }
+    }
+
+    // Dispatches `request`, running data fairings only if `run_data_fairings`
+    // is `true`: a `HEAD` request retried as `GET` already ran them.
+    fn dispatch_with<'s, 'r>(
+        &'s self,
+        request: &'r mut Request<'s>,
+        mut data: Data,
+        run_data_fairings: bool,
     ) -> Response<'r> {
         info!("{}:", request);
 
         // Do a bit of preprocessing before routing; run the attached fairings.
-        self.preprocess_request(request, &data);
+        self.preprocess_request(request, &mut data);
         self.fairings.handle_request(request, &data);
 
-        // Route the request to get a response.
-        let mut response = match self.route(request, data) {
+        // Let the data fairings replace or reject the body, then route the
+        // request to get a response.
+        let data = if run_data_fairings { self.handle_data(request, data) } else { Ok(data) };
+        let outcome = match data {
+            Ok(data) => self.route(request, data),
+            Err(status) => Outcome::Failure(status),
+        };
+
+        let mut response = match outcome {
             Outcome::Success(mut response) => {
                 // A user's route responded! Set the cookies.
                 for cookie in request.cookies().delta() {
@@ -249,7 +364,7 @@ impl Rocket {
                 if request.method() == Method::Head {
                     info_!("Autohandling {} request.", Paint::white("HEAD"));
                     request.set_method(Method::Get);
-                    let mut response = self.dispatch(request, data);
+                    let mut response = self.dispatch_with(request, data, false);
                     response.strip_body();
                     response
                 } else {
@@ -267,6 +382,16 @@ impl Rocket {
         response
     }
 
+    /// Runs each data fairing, in order, over `data`, returning the resulting
+    /// body or the status of the first fairing to reject it.
+    fn handle_data(&self, request: &Request, mut data: Data) -> Result<Data, Status> {
+        for fairing in self.data_fairings.iter() {
+            data = fairing.on_data(request, data)?;
+        }
+
+        Ok(data)
+    }
+
     /// Tries to find a `Responder` for a given `request`. It does this by
     /// routing the request and calling the handler for each matching route
     /// until one of the handlers returns success or failure, or there are no
@@ -428,7 +553,13 @@ impl Rocket {
             default_catchers: catcher::defaults::get(),
             catchers: catcher::defaults::get(),
             state: Container::new(),
+            stream_recognizers: data::default_recognizers(),
+            open_streams: OpenStreams::default(),
+            body_budgets: BodyBudgets::default(),
+            spills: SpillLimiter::default(),
+            shutdown: ShutdownHandle::default(),
             fairings: Fairings::new(),
+            data_fairings: vec![],
         }
     }
 
@@ -604,6 +735,38 @@ impl Rocket {
         self
     }
 
+    /// Registers the network stream type `S` with this instance of Rocket.
+    ///
+    /// Rocket recovers the concrete network stream underlying an incoming
+    /// request by downcasting the stream handed to it by the HTTP server. By
+    /// default, only plain TCP streams and, with the `tls` feature enabled,
+    /// Rocket's own TLS streams are recognized; requests arriving over any
+    /// other kind of stream are rejected. Calling this method allows requests
+    /// arriving over streams of type `S`, for instance a TLS stream from a
+    /// layer added independently of Rocket's `tls` feature, to be handled.
+    ///
+    /// Stream types are tried in the order they were registered, after the
+    /// built-in types.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// extern crate hyper_sync_rustls;
+    ///
+    /// use hyper_sync_rustls::{WrappedStream, ServerSession};
+    ///
+    /// rocket::ignite()
+    ///     .recognize_stream::<WrappedStream<ServerSession>>()
+    ///     .launch();
+    /// ```
+    #[inline]
+    pub fn recognize_stream<S>(mut self) -> Self
+        where S: hyper::net::NetworkStream + Clone
+    {
+        self.stream_recognizers.push(data::recognize_dynamic::<S>);
+        self
+    }
+
     /// Attaches a fairing to this instance of Rocket.
     ///
     /// # Example
@@ -634,6 +797,28 @@ impl Rocket {
         self
     }
 
+    /// Attaches a data fairing to this instance of Rocket. Data fairings run in
+    /// the order they are attached, after every request fairing and before
+    /// routing. See [`DataFairing`](/rocket/fairing/trait.DataFairing.html).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Request, Data};
+    /// use rocket::http::Status;
+    ///
+    /// let rocket = rocket::ignite()
+    ///     .attach_data(|_: &Request, data: Data| match data.len() {
+    ///         Some(len) if len > 1 << 20 => Err(Status::PayloadTooLarge),
+    ///         _ => Ok(data)
+    ///     });
+    /// ```
+    #[inline]
+    pub fn attach_data<F: DataFairing>(mut self, fairing: F) -> Self {
+        self.data_fairings.push(Box::new(fairing));
+        self
+    }
+
     pub(crate) fn prelaunch_check(&self) -> Option<LaunchError> {
         let collisions = self.router.collisions();
         if !collisions.is_empty() {
@@ -768,4 +953,196 @@ impl Rocket {
     pub fn config(&self) -> &Config {
         &self.config
     }
+
+    /// Returns a handle through which shutdown of this application can be
+    /// requested, bounding how much longer request bodies are read. See
+    /// [`ShutdownHandle`](/rocket/data/struct.ShutdownHandle.html).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// let rocket = rocket::ignite();
+    /// let handle = rocket.shutdown_handle();
+    /// assert!(!handle.is_shutting_down());
+    /// ```
+    #[inline]
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+}
+
+/// Returns the address of the client that sent `req` to the socket peer `peer`
+/// if the request's headers identify one. Forwarding headers are consulted only
+/// when `peer` is a `trusted` proxy. The `X-Real-IP` header is consulted when
+/// no forwarded client is found and either no trusted proxies are configured or
+/// `peer` is one of them.
+fn resolve_client(req: &Request, peer: IpAddr, trusted: &[Cidr]) -> Option<IpAddr> {
+    let peer_trusted = trusted.iter().any(|p| p.contains(&peer));
+    let forwarded = if peer_trusted { forwarded_client(req, trusted) } else { None };
+    if forwarded.is_some() || !(trusted.is_empty() || peer_trusted) {
+        return forwarded;
+    }
+
+    req.headers().get_one("X-Real-IP").and_then(|ip| {
+        ip.parse()
+            .map_err(|_| warn_!("'X-Real-IP' header is malformed: {}", ip))
+            .ok()
+    })
+}
+
+/// Parses a node from a `Forwarded` or `X-Forwarded-For` header: an IP address
+/// optionally quoted, bracketed, and followed by a port.
+fn parse_forwarded_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if node.starts_with('[') {
+        let end = node.find(']')?;
+        return node[1..end].parse().ok();
+    }
+
+    node.parse().ok().or_else(|| node.split(':').next().and_then(|ip| ip.parse().ok()))
+}
+
+/// Returns the address of the client that sent `req` through a chain of
+/// proxies. The chain, from the `Forwarded` header if it is present and the
+/// `X-Forwarded-For` header otherwise, is walked from the nearest proxy
+/// outward, skipping `trusted` proxies. Returns `None` if there is no chain or
+/// if an untrustworthy entry is found before the client.
+fn forwarded_client(req: &Request, trusted: &[Cidr]) -> Option<IpAddr> {
+    let chain: Vec<&str> = match req.headers().get("Forwarded").next() {
+        Some(_) => req.headers().get("Forwarded")
+            .flat_map(|value| value.split(','))
+            .filter_map(|element| element.split(';')
+                .map(|pair| pair.trim())
+                .find(|pair| pair.len() > 4 && pair[..4].eq_ignore_ascii_case("for="))
+                .map(|pair| &pair[4..]))
+            .collect(),
+        None => req.headers().get("X-Forwarded-For")
+            .flat_map(|value| value.split(','))
+            .collect()
+    };
+
+    let mut client = None;
+    for node in chain.iter().rev() {
+        match parse_forwarded_node(node) {
+            Some(ip) => {
+                client = Some(ip);
+                if !trusted.iter().any(|p| p.contains(&ip)) {
+                    break;
+                }
+            }
+            None => {
+                warn_!("Forwarded client address is malformed: {}", node);
+                return None;
+            }
+        }
+    }
+
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use super::{resolve_client, forwarded_client, parse_forwarded_node};
+    use {Rocket, Request, Config};
+    use config::Cidr;
+    use http::{Method, Header};
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn trusted() -> Vec<Cidr> {
+        vec!["10.0.0.0/8".parse().unwrap()]
+    }
+
+    // Returns the client resolved for a request from `peer` with `headers`.
+    fn client(peer: &str, trusted: &[Cidr], headers: &[(&'static str, &'static str)])
+        -> Option<IpAddr>
+    {
+        let rocket = Rocket::custom(Config::development().unwrap(), false);
+        let mut req = Request::new(&rocket, Method::Get, "/");
+        for &(name, value) in headers {
+            req.add_header(Header::new(name, value));
+        }
+
+        resolve_client(&req, ip(peer), trusted)
+    }
+
+    #[test]
+    fn test_parse_forwarded_node() {
+        assert_eq!(parse_forwarded_node("192.0.2.60"), Some(ip("192.0.2.60")));
+        assert_eq!(parse_forwarded_node(" 192.0.2.60:4711 "), Some(ip("192.0.2.60")));
+        assert_eq!(parse_forwarded_node("\"192.0.2.60\""), Some(ip("192.0.2.60")));
+        assert_eq!(parse_forwarded_node("2001:db8::1"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_forwarded_node("[2001:db8::1]"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_forwarded_node("\"[2001:db8::1]:4711\""), Some(ip("2001:db8::1")));
+        assert_eq!(parse_forwarded_node("unknown"), None);
+        assert_eq!(parse_forwarded_node("_hidden"), None);
+        assert_eq!(parse_forwarded_node("[2001:db8::1"), None);
+        assert_eq!(parse_forwarded_node(""), None);
+    }
+
+    #[test]
+    fn test_trusted_chain() {
+        let headers = [("X-Forwarded-For", "192.0.2.60, 10.0.0.3, 10.0.0.2")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("192.0.2.60")));
+
+        let headers = [("Forwarded", "for=192.0.2.60, for=10.0.0.2;proto=https")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("192.0.2.60")));
+    }
+
+    #[test]
+    fn test_untrusted_middle_hop() {
+        let headers = [("X-Forwarded-For", "192.0.2.60, 198.51.100.7, 10.0.0.2")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("198.51.100.7")));
+    }
+
+    #[test]
+    fn test_untrusted_peer() {
+        let headers = [("X-Forwarded-For", "192.0.2.60"), ("X-Real-IP", "192.0.2.61")];
+        assert_eq!(client("198.51.100.7", &trusted(), &headers), None);
+        assert_eq!(client("198.51.100.7", &[], &headers), Some(ip("192.0.2.61")));
+    }
+
+    #[test]
+    fn test_malformed_node() {
+        let headers = [("X-Forwarded-For", "192.0.2.60, bogus, 10.0.0.2")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), None);
+
+        let rocket = Rocket::custom(Config::development().unwrap(), false);
+        let mut req = Request::new(&rocket, Method::Get, "/");
+        req.add_header(Header::new("Forwarded", "for=\"[2001:db8::1\", for=10.0.0.2"));
+        assert_eq!(forwarded_client(&req, &trusted()), None);
+    }
+
+    #[test]
+    fn test_quoted_and_ipv6_forwarded_nodes() {
+        let headers = [("Forwarded", "for=\"[2001:db8::1]:4711\", for=\"10.0.0.2:80\"")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("2001:db8::1")));
+
+        let headers = [("Forwarded", "proto=http;For=\"192.0.2.60\";by=10.0.0.1")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("192.0.2.60")));
+    }
+
+    #[test]
+    fn test_forwarded_takes_precedence() {
+        let headers = [
+            ("X-Forwarded-For", "198.51.100.7"),
+            ("Forwarded", "for=192.0.2.60"),
+        ];
+
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("192.0.2.60")));
+    }
+
+    #[test]
+    fn test_x_real_ip_from_trusted_peer() {
+        let headers = [("X-Real-IP", "192.0.2.60")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("192.0.2.60")));
+        assert_eq!(client("198.51.100.7", &trusted(), &headers), None);
+
+        let headers = [("X-Forwarded-For", "198.51.100.7"), ("X-Real-IP", "192.0.2.60")];
+        assert_eq!(client("10.0.0.1", &trusted(), &headers), Some(ip("198.51.100.7")));
+    }
 }
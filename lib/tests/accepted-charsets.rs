@@ -0,0 +1,73 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[post("/", data = "<body>")]
+fn echo(body: String) -> String {
+    body
+}
+
+mod accepted_charsets_tests {
+    use rocket;
+    use rocket::config::{Environment, Config};
+    use rocket::local::Client;
+    use rocket::http::{Status, Header};
+
+    fn rocket_client(config: Config) -> Client {
+        Client::new(rocket::custom(config, true).mount("/", routes![super::echo])).unwrap()
+    }
+
+    fn post(client: &Client, content_type: &'static str, body: &str) -> Status {
+        client.post("/")
+            .header(Header::new("Content-Type", content_type))
+            .body(body)
+            .dispatch()
+            .status()
+    }
+
+    #[test]
+    fn accepted_charsets_are_accepted() {
+        let config = Config::build(Environment::Development)
+            .accepted_charsets(vec!["utf-8".to_string()])
+            .unwrap();
+
+        let client = rocket_client(config);
+        assert_eq!(post(&client, "text/plain; charset=utf-8", "hi"), Status::Ok);
+        assert_eq!(post(&client, "text/plain; charset=\"UTF-8\"", "hi"), Status::Ok);
+        assert_eq!(post(&client, "text/plain", "hi"), Status::Ok);
+    }
+
+    #[test]
+    fn other_charsets_are_rejected() {
+        let config = Config::build(Environment::Development)
+            .accepted_charsets(vec!["utf-8".to_string()])
+            .unwrap();
+
+        let client = rocket_client(config);
+        let status = post(&client, "text/plain; charset=iso-8859-1", "hi");
+        assert_eq!(status, Status::UnsupportedMediaType);
+
+        // A request without a body isn't checked.
+        assert_eq!(post(&client, "text/plain; charset=iso-8859-1", ""), Status::Ok);
+    }
+
+    #[test]
+    fn mixed_case_charsets_are_matched_case_insensitively() {
+        let config = Config::build(Environment::Development)
+            .accepted_charsets(vec!["UTF-8".to_string()])
+            .unwrap();
+
+        assert_eq!(config.accepted_charsets, vec!["utf-8".to_string()]);
+        let client = rocket_client(config);
+        assert_eq!(post(&client, "text/plain; charset=utf-8", "hi"), Status::Ok);
+        assert_eq!(post(&client, "text/plain; charset=Utf-8", "hi"), Status::Ok);
+
+        let mut config = Config::development().unwrap();
+        config.accepted_charsets = vec!["UTF-8".to_string()];
+        let client = rocket_client(config);
+        assert_eq!(post(&client, "text/plain; charset=utf-8", "hi"), Status::Ok);
+        let status = post(&client, "text/plain; charset=us-ascii", "hi");
+        assert_eq!(status, Status::UnsupportedMediaType);
+    }
+}
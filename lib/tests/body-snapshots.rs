@@ -0,0 +1,53 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Read;
+use std::path::PathBuf;
+
+use rocket::{Request, Data};
+
+fn snapshot_path() -> PathBuf {
+    ::std::env::temp_dir().join(format!("rocket-snapshot-test.{}.body", ::std::process::id()))
+}
+
+#[post("/", data = "<data>")]
+fn recorded(request: &Request, data: Data) -> String {
+    let data = data.record_snapshot(request, snapshot_path()).unwrap();
+    let mut body = String::new();
+    data.open().read_to_string(&mut body).unwrap();
+    body
+}
+
+mod body_snapshot_tests {
+    use super::snapshot_path;
+    use std::io::Read;
+    use rocket;
+    use rocket::Data;
+    use rocket::data::SnapshotMeta;
+    use rocket::local::Client;
+    use rocket::http::ContentType;
+
+    #[test]
+    fn snapshots_replay_faithfully() {
+        let client = Client::new(rocket::ignite().mount("/", routes![super::recorded])).unwrap();
+        let mut response = client.post("/")
+            .header(ContentType::JSON)
+            .body(r#"{ "line": "one\ntwo" }"#)
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some(r#"{ "line": "one\ntwo" }"#.into()));
+
+        let data = Data::from_snapshot(snapshot_path()).unwrap();
+        let meta = data.extensions().get::<SnapshotMeta>().cloned().unwrap();
+        assert_eq!(meta.content_type, Some("application/json".into()));
+        assert_eq!(meta.content_encoding, None);
+
+        let mut body = String::new();
+        data.open().read_to_string(&mut body).unwrap();
+        assert_eq!(body, r#"{ "line": "one\ntwo" }"#);
+
+        ::std::fs::remove_file(snapshot_path()).unwrap();
+    }
+}
@@ -0,0 +1,50 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::data::{CleanText, SingleLine};
+
+#[post("/multiline", data = "<text>")]
+fn multiline(text: CleanText<String>) -> String {
+    text.into_inner()
+}
+
+#[post("/single", data = "<text>")]
+fn single(text: CleanText<String, SingleLine>) -> String {
+    text.into_inner()
+}
+
+mod clean_text_tests {
+    use rocket;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    fn client() -> Client {
+        let rocket = rocket::ignite().mount("/", routes![super::multiline, super::single]);
+        Client::new(rocket).unwrap()
+    }
+
+    #[test]
+    fn clean_text_is_accepted() {
+        let client = client();
+        let mut response = client.post("/multiline").body("line one\r\n\tline two\n").dispatch();
+        assert_eq!(response.body_string(), Some("line one\r\n\tline two\n".into()));
+
+        let mut response = client.post("/single").body("just\tone line").dispatch();
+        assert_eq!(response.body_string(), Some("just\tone line".into()));
+    }
+
+    #[test]
+    fn control_bytes_are_rejected() {
+        let client = client();
+        let response = client.post("/multiline").body("null\0byte").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.post("/multiline").body("escape\x1b[2J").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.post("/single").body("two\nlines").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
@@ -0,0 +1,68 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[post("/", data = "<body>")]
+fn upload(body: String) -> String {
+    body
+}
+
+#[get("/smuggled")]
+fn smuggled() -> &'static str {
+    "smuggled"
+}
+
+mod conflicting_length_tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+    use std::time::Duration;
+
+    use rocket;
+    use rocket::config::{Config, Environment};
+
+    const PORT: u16 = 38944;
+
+    fn connect() -> TcpStream {
+        let config = Config::build(Environment::Development)
+            .address("127.0.0.1")
+            .port(PORT)
+            .unwrap();
+
+        thread::spawn(move || {
+            let routes = routes![super::upload, super::smuggled];
+            rocket::custom(config, false).mount("/", routes).launch();
+        });
+
+        for _ in 0..100 {
+            if let Ok(socket) = TcpStream::connect(("127.0.0.1", PORT)) {
+                socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+                return socket;
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        panic!("the server never started listening");
+    }
+
+    #[test]
+    fn ambiguous_body_closes_the_connection() {
+        let mut socket = connect();
+        socket.write_all(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\
+                           Transfer-Encoding: chunked\r\n\r\n0\r\n\r\n\
+                           GET /smuggled HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+        // The server must close the connection after rejecting the first
+        // request instead of answering the smuggled second one.
+        let mut response = vec![];
+        socket.read_to_end(&mut response).expect("connection closed");
+
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{}", response);
+        assert!(response.contains("Connection: close\r\n"), "{}", response);
+        assert_eq!(response.matches("HTTP/1.1 ").count(), 1, "{}", response);
+        assert!(!response.contains("smuggled"), "{}", response);
+    }
+}
@@ -0,0 +1,62 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Read;
+
+use rocket::{Request, Data};
+use rocket::http::Status;
+
+#[post("/", data = "<body>")]
+fn echo(body: String) -> String {
+    body
+}
+
+fn uppercase(_: &Request, data: Data) -> Result<Data, Status> {
+    let mut body = String::new();
+    data.open().read_to_string(&mut body).map_err(|_| Status::BadRequest)?;
+    Ok(Data::from_bytes(body.to_uppercase().into_bytes()))
+}
+
+fn reject_empty(_: &Request, data: Data) -> Result<Data, Status> {
+    match data.peek().is_empty() {
+        true => Err(Status::UnprocessableEntity),
+        false => Ok(data)
+    }
+}
+
+mod data_fairing_tests {
+    use rocket;
+    use rocket::{Request, Data};
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    fn client() -> Client {
+        let rocket = rocket::ignite()
+            .mount("/", routes![super::echo])
+            .attach_data(super::reject_empty)
+            .attach_data(super::uppercase)
+            .attach_data(|_: &Request, data: Data| {
+                let mut bytes = data.peek().to_vec();
+                bytes.extend_from_slice(b"!");
+                Ok(Data::from_bytes(bytes))
+            });
+
+        Client::new(rocket).unwrap()
+    }
+
+    #[test]
+    fn data_fairings_replace_body_in_order() {
+        let client = client();
+        let mut response = client.post("/").body("hello").dispatch();
+        assert_eq!(response.body_string(), Some("HELLO!".into()));
+    }
+
+    #[test]
+    fn data_fairings_can_reject() {
+        let client = client();
+        let response = client.post("/").dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+}
@@ -0,0 +1,103 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::mem;
+
+use rocket::Data;
+
+#[post("/leak", data = "<data>")]
+fn leak(data: Data) -> &'static str {
+    mem::forget(data.open());
+    "leaked"
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "index"
+}
+
+mod leaked_stream_tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+    use std::time::Duration;
+
+    use rocket;
+    use rocket::config::{Config, Environment};
+
+    const PORT: u16 = 38945;
+
+    fn connect() -> TcpStream {
+        let config = Config::build(Environment::Development)
+            .address("127.0.0.1")
+            .port(PORT)
+            .unwrap();
+
+        thread::spawn(move || {
+            let routes = routes![super::leak, super::index];
+            rocket::custom(config, false).mount("/", routes).launch();
+        });
+
+        for _ in 0..100 {
+            if let Ok(socket) = TcpStream::connect(("127.0.0.1", PORT)) {
+                socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+                return socket;
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        panic!("the server never started listening");
+    }
+
+    // Reads one response, with a `Content-Length`, from `socket`.
+    fn read_response(socket: &mut TcpStream) -> String {
+        let mut response = vec![];
+        let mut buf = [0; 1024];
+        loop {
+            let n = socket.read(&mut buf).expect("read a response");
+            assert!(n > 0, "connection closed mid-response");
+            response.extend_from_slice(&buf[..n]);
+
+            let text = String::from_utf8_lossy(&response).into_owned();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let len: usize = text[..end].lines()
+                    .find(|line| line.to_lowercase().starts_with("content-length:"))
+                    .map_or(0, |line| line["content-length:".len()..].trim().parse().unwrap());
+
+                if response.len() >= end + 4 + len {
+                    return text;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn leaked_stream_fails_the_next_request() {
+        let mut socket = connect();
+        socket.write_all(b"POST /leak HTTP/1.1\r\nHost: localhost\r\n\
+                           Content-Length: 5\r\n\r\nhello").unwrap();
+
+        let response = read_response(&mut socket);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("leaked"), "{}", response);
+
+        // The first body is still open, so the next request on the same
+        // connection is refused and the connection is closed.
+        socket.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let response = read_response(&mut socket);
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"), "{}", response);
+        assert!(response.contains("Connection: close\r\n"), "{}", response);
+        assert!(!response.contains("index"), "{}", response);
+
+        // A new connection is unaffected.
+        let mut fresh = TcpStream::connect(("127.0.0.1", PORT)).unwrap();
+        fresh.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        fresh.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").unwrap();
+        let response = read_response(&mut fresh);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("index"), "{}", response);
+    }
+}
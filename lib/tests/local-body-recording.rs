@@ -0,0 +1,50 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Read;
+
+use rocket::Data;
+
+#[post("/prefix", data = "<data>")]
+fn prefix(data: Data) -> String {
+    let mut prefix = [0; 5];
+    data.open().read_exact(&mut prefix).unwrap();
+    String::from_utf8_lossy(&prefix).into_owned()
+}
+
+#[post("/all", data = "<body>")]
+fn all(body: String) -> String {
+    body
+}
+
+mod local_body_recording_tests {
+    use rocket;
+    use rocket::local::Client;
+
+    fn client() -> Client {
+        Client::new(rocket::ignite().mount("/", routes![super::prefix, super::all])).unwrap()
+    }
+
+    #[test]
+    fn records_what_the_handler_read() {
+        let client = client();
+        let mut req = client.post("/prefix").body("hello, world");
+        let recording = req.record_body();
+        let mut response = req.dispatch();
+
+        assert_eq!(response.body_string(), Some("hello".into()));
+        assert_eq!(recording.bytes(), b"hello");
+    }
+
+    #[test]
+    fn records_the_entire_body() {
+        let client = client();
+        let mut req = client.post("/all").body("hello, world");
+        let recording = req.record_body();
+        req.dispatch();
+
+        assert_eq!(recording.bytes(), b"hello, world");
+    }
+}
@@ -0,0 +1,27 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::data::NoBody;
+
+#[post("/ping", data = "<_empty>")]
+fn ping(_empty: NoBody) -> &'static str {
+    "pong"
+}
+
+mod no_body_tests {
+    use rocket;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    #[test]
+    fn no_body_is_accepted_only_without_a_body() {
+        let client = Client::new(rocket::ignite().mount("/", routes![super::ping])).unwrap();
+        let mut response = client.post("/ping").dispatch();
+        assert_eq!(response.body_string(), Some("pong".into()));
+
+        let response = client.post("/ping").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
@@ -0,0 +1,70 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[cfg(feature = "hmac")]
+mod signed_body_tests {
+    use rocket;
+    use rocket::local::Client;
+    use rocket::http::{Header, Status};
+    use rocket::data::{SignedBody, SigningSecret};
+
+    #[post("/", data = "<body>")]
+    fn hook(body: SignedBody<String>) -> String {
+        body.into_inner()
+    }
+
+    // The HMAC-SHA256 test vector from RFC 4231, test case 2.
+    const BODY: &str = "what do ya want for nothing?";
+    const SIGNATURE: &str =
+        "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+
+    fn client() -> Client {
+        let secret = SigningSecret::new("X-Signature", "Jefe").prefix("sha256=");
+        Client::new(rocket::ignite().manage(secret).mount("/", routes![hook])).unwrap()
+    }
+
+    #[test]
+    fn test_signed_body() {
+        let client = client();
+        let mut response = client.post("/")
+            .header(Header::new("X-Signature", SIGNATURE))
+            .body(BODY)
+            .dispatch();
+
+        assert_eq!(response.body_string(), Some(BODY.into()));
+    }
+
+    #[test]
+    fn test_bad_signatures() {
+        let client = client();
+        let response = client.post("/")
+            .header(Header::new("X-Signature", SIGNATURE))
+            .body("what do ya want for nothing!")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.post("/").body(BODY).dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.post("/")
+            .header(Header::new("X-Signature", "sha256=xyz"))
+            .body(BODY)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn test_no_secret() {
+        let client = Client::new(rocket::ignite().mount("/", routes![hook])).unwrap();
+        let response = client.post("/")
+            .header(Header::new("X-Signature", SIGNATURE))
+            .body(BODY)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
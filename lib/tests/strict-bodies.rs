@@ -0,0 +1,90 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/")]
+fn get() -> &'static str {
+    "get"
+}
+
+#[delete("/")]
+fn delete() -> &'static str {
+    "delete"
+}
+
+#[post("/", data = "<body>")]
+fn post(body: String) -> String {
+    body
+}
+
+mod strict_bodies_tests {
+    use rocket;
+    use rocket::config::{Environment, Config, StrictBodies};
+    use rocket::local::Client;
+    use rocket::http::{Status, Header};
+
+    fn client(policy: StrictBodies) -> Client {
+        let config = Config::build(Environment::Development)
+            .strict_bodies(policy)
+            .unwrap();
+
+        let routes = routes![super::get, super::delete, super::post];
+        Client::new(rocket::custom(config, true).mount("/", routes)).unwrap()
+    }
+
+    #[test]
+    fn off_accepts_bodies() {
+        let client = client(StrictBodies::Off);
+        let mut response = client.get("/").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("get".into()));
+
+        let response = client.delete("/").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn warn_accepts_bodies() {
+        let client = client(StrictBodies::Warn);
+        let mut response = client.get("/").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("get".into()));
+
+        let response = client.delete("/").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn reject_rejects_bodies() {
+        let client = client(StrictBodies::Reject);
+        let response = client.get("/").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.delete("/").body("smuggled").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        // Methods that may carry a body are unaffected.
+        let mut response = client.post("/").body("fine").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("fine".into()));
+    }
+
+    #[test]
+    fn reject_accepts_empty_bodies() {
+        let client = client(StrictBodies::Reject);
+        let mut response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("get".into()));
+
+        let response = client.get("/")
+            .header(Header::new("Content-Length", "0"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.head("/")
+            .header(Header::new("Content-Length", "0"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+}
@@ -0,0 +1,107 @@
+#![feature(plugin, decl_macro)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+#[cfg(feature = "tls")] extern crate rustls;
+
+#[cfg(feature = "tls")]
+mod tls_tests {
+    use std::fs::File;
+    use std::io::{BufReader, Read, Write};
+    use std::net::TcpStream;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use rustls::{ClientConfig, ClientSession, Session};
+
+    use rocket;
+    use rocket::Data;
+    use rocket::config::{Config, Environment};
+
+    #[post("/", data = "<data>")]
+    fn echo(data: Data) -> String {
+        let version = data.tls_info()
+            .and_then(|info| info.tls_version())
+            .map_or("none".to_string(), |version| version.to_string());
+
+        let mut body = String::new();
+        data.open().read_to_string(&mut body).unwrap();
+        format!("{} over TLS {}", body, version)
+    }
+
+    const PORT: u16 = 38943;
+    const PRIVATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/tls/private");
+
+    fn launch() {
+        let config = Config::build(Environment::Development)
+            .address("127.0.0.1")
+            .port(PORT)
+            .tls(format!("{}/cert.pem", PRIVATE), format!("{}/key.pem", PRIVATE))
+            .unwrap();
+
+        thread::spawn(move || {
+            rocket::custom(config, false).mount("/", routes![echo]).launch();
+        });
+    }
+
+    fn connect() -> TcpStream {
+        for _ in 0..100 {
+            if let Ok(socket) = TcpStream::connect(("127.0.0.1", PORT)) {
+                socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+                return socket;
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        panic!("the TLS server never started listening");
+    }
+
+    // Sends `request` over a new TLS connection and returns everything the
+    // server sends back before closing the connection.
+    fn exchange(request: &[u8]) -> String {
+        let mut config = ClientConfig::new();
+        let ca = File::open(format!("{}/ca_cert.pem", PRIVATE)).unwrap();
+        config.root_store.add_pem_file(&mut BufReader::new(ca)).unwrap();
+
+        let mut session = ClientSession::new(&Arc::new(config), "localhost");
+        session.write_all(request).unwrap();
+
+        let mut socket = connect();
+        let mut response = vec![];
+        let mut buf = [0; 1024];
+        loop {
+            while session.wants_write() {
+                session.write_tls(&mut socket).unwrap();
+            }
+
+            match session.read_tls(&mut socket) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => session.process_new_packets().unwrap(),
+            }
+
+            loop {
+                match session.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => response.extend_from_slice(&buf[..n]),
+                }
+            }
+        }
+
+        String::from_utf8(response).unwrap()
+    }
+
+    #[test]
+    fn body_is_read_and_answered_over_tls() {
+        launch();
+
+        let request = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\
+                       Connection: close\r\n\r\nhello";
+
+        let response = exchange(request.as_bytes());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(response.ends_with("hello over TLS 1.2")
+                || response.ends_with("hello over TLS 1.3"), "{}", response);
+    }
+}